@@ -0,0 +1,174 @@
+use serde_json::{Map, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::atomic_write;
+use crate::error::IgneError;
+use crate::paths::safe_join_path;
+use crate::scope::ensure_in_scope;
+use crate::write_lock::with_write_lock;
+
+/// Settings only the backend writes, kept as they are on disk whatever the frontend sends:
+/// the frontend can't lift the path scope for itself, and its settings store writes back
+/// the copy it loaded at startup, which would undo `set_locale`, `set_log_level` and
+/// `set_performance_mode`
+const BACKEND_OWNED_SETTINGS: &[&str] = &["disablePathScope", "locale", "logLevel", "performanceModes"];
+
+/// Name of the settings file in the app data dir
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// Files of a plain folder's config, kept under `workspaces/<hash>/` since the folder has
+/// no `.obsidian`
+const WORKSPACE_FILES: &[&str] = &["app.json", "appearance.json", "workspace.json", "hotkeys.json"];
+
+/// The app data dir is outside the path scope, so the frontend reaches its own config
+/// files through these names only: `settings.json`, `vaults.json`, `window-state.json`
+/// and `workspaces/<hash>/<file>`
+fn config_path(app: &AppHandle, name: &str) -> Result<PathBuf, IgneError> {
+    let allowed = match name.split('/').collect::<Vec<_>>()[..] {
+        [SETTINGS_FILE | "vaults.json" | "window-state.json"] => true,
+        ["workspaces", hash, file] => {
+            !hash.is_empty() && hash.chars().all(|c| c.is_ascii_alphanumeric()) && WORKSPACE_FILES.contains(&file)
+        }
+        _ => false,
+    };
+    if !allowed {
+        return Err(IgneError::invalid_input(format!("Not an app config file: {}", name)));
+    }
+
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| IgneError::other(format!("Failed to get app data dir: {}", e)))?;
+    safe_join_path(&dir, name)
+}
+
+/// `<app_data_dir>/settings.json`
+pub fn settings_path(app: &AppHandle) -> Result<PathBuf, IgneError> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(SETTINGS_FILE))
+        .map_err(|e| IgneError::other(format!("Failed to get app data dir: {}", e)))
+}
+
+/// The settings as a JSON object, empty when the file doesn't exist yet. A file that
+/// doesn't parse is an error, so saving one key never wipes the rest.
+pub fn load_settings(path: &Path) -> Result<Map<String, Value>, IgneError> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| {
+            IgneError::invalid_input(format!("{} is not a JSON object, not overwriting it: {}", path.display(), e))
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Map::new()),
+        Err(e) => Err(IgneError::io(e, path)),
+    }
+}
+
+/// Change the backend's own keys in settings.json under the file's write lock, so saves
+/// from other commands and windows aren't lost
+pub async fn update_settings(
+    app: &AppHandle,
+    change: impl FnOnce(&mut Map<String, Value>) -> Result<(), IgneError> + Send,
+) -> Result<(), IgneError> {
+    let path = settings_path(app)?;
+    with_write_lock(app, &path, || {
+        let mut settings = load_settings(&path)?;
+        change(&mut settings)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| IgneError::io(e, dir))?;
+        }
+        atomic_write(&path, serde_json::to_string_pretty(&settings)?.as_bytes())
+    })
+    .await
+}
+
+/// Keep the backend-owned settings as they are on disk, whatever the frontend sent
+fn keep_backend_settings(path: &Path, incoming: &mut Map<String, Value>) {
+    let existing: Map<String, Value> = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    for key in BACKEND_OWNED_SETTINGS {
+        match existing.get(*key) {
+            Some(value) => incoming.insert(key.to_string(), value.clone()),
+            None => incoming.remove(*key),
+        };
+    }
+}
+
+/// Registering a vault widens the path scope, so new vault and folder entries must be
+/// paths the user already picked or that are already in scope
+fn check_new_vaults(app: &AppHandle, incoming: &Map<String, Value>) -> Result<(), IgneError> {
+    let registered = crate::registered_vault_roots(app);
+    let entries = incoming.get("vaults").and_then(Value::as_array).into_iter().flatten();
+    for entry in entries {
+        let kind = entry.get("type").and_then(Value::as_str);
+        if !matches!(kind, None | Some("vault") | Some("folder")) {
+            continue;
+        }
+        let Some(path) = entry.get("path").and_then(Value::as_str) else {
+            continue;
+        };
+        let path = Path::new(path);
+        if !registered.iter().any(|root| root == path) {
+            ensure_in_scope(app, path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Read one of the app's config files from the app data dir; `None` when it doesn't exist
+/// yet
+#[tauri::command]
+pub fn read_app_config(name: String, app: AppHandle) -> Result<Option<String>, IgneError> {
+    let path = config_path(&app, &name)?;
+    match fs::read_to_string(&path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(IgneError::io(e, &path)),
+    }
+}
+
+/// Replace one of the app's config files with `content`, which must be a JSON object
+#[tauri::command]
+pub async fn write_app_config(name: String, content: String, app: AppHandle) -> Result<(), IgneError> {
+    let path = config_path(&app, &name)?;
+    let mut incoming: Map<String, Value> = serde_json::from_str(&content)
+        .map_err(|e| IgneError::invalid_input(format!("{} must be a JSON object: {}", name, e)))?;
+
+    with_write_lock(&app, &path, || {
+        match name.as_str() {
+            SETTINGS_FILE => keep_backend_settings(&path, &mut incoming),
+            "vaults.json" => check_new_vaults(&app, &incoming)?,
+            _ => {}
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| IgneError::io(e, dir))?;
+        }
+        atomic_write(&path, serde_json::to_string_pretty(&incoming)?.as_bytes())
+    })
+    .await
+}
+
+/// Append lines to the frontend's log for this run, `<app_data_dir>/logs/session-<id>.log`
+#[tauri::command]
+pub fn append_session_log(session_id: String, content: String, app: AppHandle) -> Result<(), IgneError> {
+    if session_id.is_empty() || !session_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(IgneError::invalid_input(format!("Not a session id: {}", session_id)));
+    }
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| IgneError::other(format!("Failed to get app data dir: {}", e)))?
+        .join("logs");
+    fs::create_dir_all(&dir).map_err(|e| IgneError::io(e, &dir))?;
+
+    let path = dir.join(format!("session-{}.log", session_id));
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|e| IgneError::io(e, &path))
+}
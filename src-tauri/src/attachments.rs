@@ -0,0 +1,88 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::error::IgneError;
+use crate::images::IMAGE_EXTENSIONS;
+use crate::paths::vault_relative;
+use crate::scope::ensure_in_scope;
+use crate::vault::walk_vault_files;
+use crate::{is_markdown_file, unix_secs};
+
+pub const AUDIO_EXTENSIONS: [&str; 8] = ["mp3", "wav", "m4a", "ogg", "flac", "webm", "3gp", "aac"];
+pub const VIDEO_EXTENSIONS: [&str; 5] = ["mp4", "mov", "mkv", "ogv", "avi"];
+
+#[derive(Serialize, Clone)]
+pub struct Attachment {
+    pub path: String,
+    pub relative_path: String,
+    pub size: u64,
+    pub modified: u64,
+}
+
+/// A vault's non-markdown files by kind, each sorted by path
+#[derive(Serialize, Clone, Default)]
+pub struct Attachments {
+    pub images: Vec<Attachment>,
+    pub audio: Vec<Attachment>,
+    pub video: Vec<Attachment>,
+    pub pdf: Vec<Attachment>,
+    pub other: Vec<Attachment>,
+}
+
+impl Attachments {
+    /// The group for `path`, by extension. `.webm` counts as audio, as in Obsidian's recordings.
+    fn group(&mut self, path: &Path) -> &mut Vec<Attachment> {
+        let ext = path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let ext = ext.as_str();
+        if IMAGE_EXTENSIONS.contains(&ext) {
+            &mut self.images
+        } else if AUDIO_EXTENSIONS.contains(&ext) {
+            &mut self.audio
+        } else if VIDEO_EXTENSIONS.contains(&ext) {
+            &mut self.video
+        } else if ext == "pdf" {
+            &mut self.pdf
+        } else {
+            &mut self.other
+        }
+    }
+}
+
+/// Every file in the vault that isn't a note, grouped into images, audio, video, PDFs and
+/// everything else, for the attachments browser. Hidden folders such as `.obsidian` and
+/// `.git` are skipped.
+#[tauri::command]
+pub fn list_attachments(root: String, app: AppHandle) -> Result<Attachments, IgneError> {
+    let root_path = PathBuf::from(&root);
+    ensure_in_scope(&app, &root_path)?;
+
+    let mut attachments = Attachments::default();
+    for file in walk_vault_files(&root_path)? {
+        if is_markdown_file(&file.to_string_lossy()) {
+            continue;
+        }
+        let Ok(metadata) = fs::metadata(&file) else {
+            continue;
+        };
+        let attachment = Attachment {
+            relative_path: vault_relative(&root_path, &file)?,
+            path: file.to_string_lossy().to_string(),
+            size: metadata.len(),
+            modified: unix_secs(metadata.modified()),
+        };
+        attachments.group(&file).push(attachment);
+    }
+
+    for group in [
+        &mut attachments.images,
+        &mut attachments.audio,
+        &mut attachments.video,
+        &mut attachments.pdf,
+        &mut attachments.other,
+    ] {
+        group.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    }
+    Ok(attachments)
+}
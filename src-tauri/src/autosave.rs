@@ -0,0 +1,318 @@
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager, State, Window};
+
+use crate::error::IgneError;
+use crate::formatting::LineEnding;
+use crate::line_endings::{self, LineStyles};
+use crate::scope::ensure_in_scope;
+use crate::vault_trash::{unique_path, TRASH_DIR};
+use crate::write_lock::with_write_lock;
+use crate::{atomic_write, containing_vault, is_markdown_file, snapshots};
+
+const DEFAULT_INTERVAL_SECS: u64 = 30;
+/// How often a disabled scheduler checks whether autosave was turned back on
+const DISABLED_RECHECK: Duration = Duration::from_secs(5);
+const DEFAULT_DEBOUNCE_MS: u64 = 500;
+
+/// Unsaved editor content per file, written out by the scheduler so edits survive the
+/// webview crashing or being closed mid-debounce
+pub struct AutosaveState {
+    buffers: Mutex<HashMap<PathBuf, String>>,
+    /// Label of the window that last sent each file's buffer, so closing a window without
+    /// saving drops only its edits
+    owners: Mutex<HashMap<PathBuf, String>>,
+    /// `debounced_write_file` tasks waiting out their delay, by file
+    pending: Mutex<HashMap<PathBuf, JoinHandle<()>>>,
+}
+
+impl AutosaveState {
+    pub fn new() -> Self {
+        Self {
+            buffers: Mutex::new(HashMap::new()),
+            owners: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Drop the buffers last sent by `window` and cancel their pending writes, so "Don't
+    /// Save" isn't undone by the scheduler or the flush on exit
+    pub fn discard_window(&self, window: &str) -> Result<(), IgneError> {
+        let mut owners = self.owners.lock()?;
+        let paths: Vec<PathBuf> = owners
+            .iter()
+            .filter(|(_, owner)| *owner == window)
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        let mut pending = self.pending.lock()?;
+        let mut buffers = self.buffers.lock()?;
+        for path in paths {
+            if let Some(task) = pending.remove(&path) {
+                task.abort();
+            }
+            buffers.remove(&path);
+            owners.remove(&path);
+        }
+        Ok(())
+    }
+
+    /// How many unsaved notes `window` has handed to the backend, which a flush would write
+    pub fn held_by(&self, window: &str) -> usize {
+        let (Ok(owners), Ok(buffers)) = (self.owners.lock(), self.buffers.lock()) else {
+            return 0;
+        };
+        buffers
+            .keys()
+            .filter(|path| owners.get(*path).is_some_and(|owner| owner == window))
+            .count()
+    }
+}
+
+impl Default for AutosaveState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Interval from `<app_data_dir>/settings.json`; 0 turns the scheduler off
+#[derive(Deserialize)]
+struct AutosaveSettings {
+    #[serde(default = "default_interval_secs", rename = "autosaveIntervalSecs")]
+    interval_secs: u64,
+}
+
+fn default_interval_secs() -> u64 {
+    DEFAULT_INTERVAL_SECS
+}
+
+pub fn autosave_interval(app: &AppHandle) -> u64 {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join("settings.json")).ok())
+        .and_then(|content| serde_json::from_str::<AutosaveSettings>(&content).ok())
+        .map(|settings| settings.interval_secs)
+        .unwrap_or(DEFAULT_INTERVAL_SECS)
+}
+
+/// Payload of the `buffer-flushed` event
+#[derive(Serialize, Clone)]
+pub struct BufferFlushed {
+    pub path: String,
+    /// Where the content went instead when the file was deleted outside the app
+    pub recovered_to: Option<String>,
+}
+
+/// Store the latest unsaved content of `path`, replacing any earlier buffer
+#[tauri::command]
+pub fn update_dirty_buffer(
+    path: String,
+    content: String,
+    app: AppHandle,
+    window: Window,
+    autosave: State<'_, AutosaveState>,
+) -> Result<(), IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+    autosave.owners.lock()?.insert(path_obj.clone(), window.label().to_string());
+    autosave.buffers.lock()?.insert(path_obj, content);
+    Ok(())
+}
+
+/// Forget the buffer of `path`, e.g. after the editor saved it itself or discarded changes
+#[tauri::command]
+pub fn clear_dirty_buffer(path: String, autosave: State<'_, AutosaveState>) -> Result<(), IgneError> {
+    if let Some(task) = autosave.pending.lock()?.remove(Path::new(&path)) {
+        task.abort();
+    }
+    autosave.buffers.lock()?.remove(Path::new(&path));
+    autosave.owners.lock()?.remove(Path::new(&path));
+    Ok(())
+}
+
+/// Write `content` to `path` once no newer call for the same file has arrived for
+/// `delay_ms` (500 by default), so an editor can call this on every keystroke. Until
+/// then the content is a dirty buffer, written by the scheduler and on exit like any other.
+#[tauri::command]
+pub fn debounced_write_file(
+    path: String,
+    content: String,
+    delay_ms: Option<u64>,
+    app: AppHandle,
+    window: Window,
+    autosave: State<'_, AutosaveState>,
+) -> Result<(), IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+    autosave.owners.lock()?.insert(path_obj.clone(), window.label().to_string());
+    autosave.buffers.lock()?.insert(path_obj.clone(), content);
+
+    let delay = Duration::from_millis(delay_ms.unwrap_or(DEFAULT_DEBOUNCE_MS));
+    let mut pending = autosave.pending.lock()?;
+    if let Some(previous) = pending.remove(&path_obj) {
+        previous.abort();
+    }
+    pending.retain(|_, task| !task.inner().is_finished());
+    let task_path = path_obj.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        flush_one(&app, &task_path).await;
+    });
+    pending.insert(path_obj, task);
+    Ok(())
+}
+
+/// Write every dirty buffer now ("Save all")
+#[tauri::command]
+pub async fn flush_dirty_buffers(app: AppHandle) -> Result<Vec<BufferFlushed>, IgneError> {
+    Ok(flush_all(&app).await)
+}
+
+/// `.trash` of the registered vault containing `path`, or of the app data folder when the
+/// file isn't in a vault
+fn recovery_dir(app: &AppHandle, path: &Path) -> Result<PathBuf, IgneError> {
+    match containing_vault(app, path) {
+        Some(root) => Ok(root.join(TRASH_DIR)),
+        None => app
+            .path()
+            .app_data_dir()
+            .map(|dir| dir.join(TRASH_DIR))
+            .map_err(|e| IgneError::other(format!("Failed to get app data dir: {}", e))),
+    }
+}
+
+/// Save one buffer in the line-break style the file had when read, like `write_file`
+/// with "preserve". A file that no longer exists was deleted outside the app, so the
+/// content is kept in the trash instead of silently bringing the file back.
+async fn flush_buffer(app: &AppHandle, path: &Path, content: &str) -> Result<BufferFlushed, IgneError> {
+    let line_styles = app.state::<LineStyles>();
+    let content = &line_endings::apply(
+        content.to_string(),
+        Some(LineEnding::Preserve),
+        None,
+        line_styles.get(path),
+    );
+    let mut recovered_to = None;
+
+    with_write_lock(app, path, || {
+        if path.exists() {
+            atomic_write(path, content.as_bytes())?;
+            line_styles.record(path, line_endings::detect(content));
+            if is_markdown_file(&path.to_string_lossy()) {
+                snapshots::record_snapshot_logged(app, path, content.as_bytes());
+            }
+            return Ok(());
+        }
+
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| IgneError::invalid_path(format!("Not a file path: {}", path.display()), path))?;
+        let trash = recovery_dir(app, path)?;
+        fs::create_dir_all(&trash).map_err(|e| IgneError::io(e, &trash))?;
+        let target = unique_path(&trash.join(file_name));
+        atomic_write(&target, content.as_bytes())?;
+        recovered_to = Some(target.to_string_lossy().to_string());
+        Ok(())
+    })
+    .await?;
+
+    Ok(BufferFlushed {
+        path: path.to_string_lossy().to_string(),
+        recovered_to,
+    })
+}
+
+/// Write the buffer of `path`, if there still is one, emitting `buffer-flushed`. The
+/// buffer is only dropped once written and unchanged, so a write cancelled halfway by a
+/// newer `debounced_write_file` loses nothing.
+async fn flush_one(app: &AppHandle, path: &Path) {
+    let autosave = app.state::<AutosaveState>();
+    let Some(content) = autosave.buffers.lock().ok().and_then(|buffers| buffers.get(path).cloned()) else {
+        return;
+    };
+
+    match flush_buffer(app, path, &content).await {
+        Ok(result) => {
+            if let Ok(mut buffers) = autosave.buffers.lock() {
+                if buffers.get(path) == Some(&content) {
+                    buffers.remove(path);
+                }
+            }
+            if let Some(recovered) = &result.recovered_to {
+                info!("{} was deleted externally; unsaved changes kept at {}", path.display(), recovered);
+            }
+            let _ = app.emit("buffer-flushed", result);
+        }
+        Err(e) => warn!("Debounced write of {} failed: {}", path.display(), e),
+    }
+}
+
+/// Write the buffers last sent by `window`. Failed ones stay buffered, so `held_by` tells
+/// whether everything went out.
+pub async fn flush_window(app: &AppHandle, window: &str) {
+    let autosave = app.state::<AutosaveState>();
+    let paths: Vec<PathBuf> = match autosave.owners.lock() {
+        Ok(owners) => owners
+            .iter()
+            .filter(|(_, owner)| *owner == window)
+            .map(|(path, _)| path.clone())
+            .collect(),
+        Err(_) => return,
+    };
+    for path in paths {
+        flush_one(app, &path).await;
+    }
+}
+
+/// Drain and write all buffers, emitting `buffer-flushed` for each saved file. Failed
+/// buffers go back into the queue unless the editor has sent newer content meanwhile.
+pub async fn flush_all(app: &AppHandle) -> Vec<BufferFlushed> {
+    let autosave = app.state::<AutosaveState>();
+    let pending: Vec<(PathBuf, String)> = match autosave.buffers.lock() {
+        Ok(mut buffers) => buffers.drain().collect(),
+        Err(_) => return vec![],
+    };
+
+    let mut flushed = Vec::with_capacity(pending.len());
+    for (path, content) in pending {
+        match flush_buffer(app, &path, &content).await {
+            Ok(result) => {
+                if let Some(recovered) = &result.recovered_to {
+                    info!("{} was deleted externally; unsaved changes kept at {}", path.display(), recovered);
+                }
+                let _ = app.emit("buffer-flushed", result.clone());
+                flushed.push(result);
+            }
+            Err(e) => {
+                warn!("Autosave of {} failed: {}", path.display(), e);
+                if let Ok(mut buffers) = autosave.buffers.lock() {
+                    buffers.entry(path).or_insert(content);
+                }
+            }
+        }
+    }
+    flushed
+}
+
+/// Background task flushing dirty buffers every `autosaveIntervalSecs`. The setting is
+/// re-read each round so changing it takes effect without a restart.
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let interval = autosave_interval(&app);
+            if interval == 0 {
+                tokio::time::sleep(DISABLED_RECHECK).await;
+                continue;
+            }
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+            flush_all(&app).await;
+        }
+    });
+}
@@ -0,0 +1,311 @@
+use log::warn;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::IgneError;
+use crate::paths::{long_path, vault_absolute, vault_relative, VaultPath};
+use crate::scope::ensure_in_scope;
+use crate::write_lock::with_write_lock;
+use crate::{atomic_write, containing_vault};
+
+/// Obsidian's bookmarks file. Entries are vault-relative; groups nest their own `items`.
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+/// A bookmarked note or folder. Other bookmark types, such as searches and URLs, are kept
+/// in the file but not listed.
+#[derive(Serialize, Clone)]
+pub struct Bookmark {
+    /// "file" or "folder"
+    pub kind: String,
+    #[serde(flatten)]
+    pub location: VaultPath,
+    pub title: Option<String>,
+    /// Title of the group holding the bookmark, if any
+    pub group: Option<String>,
+}
+
+/// Payload of `bookmarks-changed`
+#[derive(Serialize, Clone)]
+struct BookmarksChanged {
+    vault_path: String,
+}
+
+/// What to do with a bookmark entry
+enum Change {
+    Keep,
+    Remove,
+    MoveTo(String),
+}
+
+fn bookmarks_path(vault: &Path) -> PathBuf {
+    vault.join(".obsidian").join(BOOKMARKS_FILE)
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// The bookmarks document, or an empty one when there is no file yet. A file that isn't
+/// valid JSON is an error rather than an empty list, so it's never overwritten.
+fn load(path: &Path) -> Result<Value, IgneError> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(json!({ "items": [] })),
+        Err(e) => Err(IgneError::io(e, path)),
+    }
+}
+
+fn save(path: &Path, document: &Value) -> Result<(), IgneError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| IgneError::io(e, dir))?;
+    }
+    atomic_write(path, serde_json::to_string_pretty(document)?.as_bytes())
+}
+
+/// The top-level `items` list, created if the document lacks one
+fn items_mut(document: &mut Value) -> Result<&mut Vec<Value>, IgneError> {
+    let invalid = || IgneError::invalid_input(format!("{} is not a bookmarks file", BOOKMARKS_FILE));
+    let object = document.as_object_mut().ok_or_else(invalid)?;
+    object
+        .entry("items")
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+        .ok_or_else(invalid)
+}
+
+fn entry_path(item: &Value) -> Option<&str> {
+    match item.get("type").and_then(Value::as_str) {
+        Some("file" | "folder") => item.get("path").and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+fn group_items(item: &Value) -> Option<&Vec<Value>> {
+    match item.get("type").and_then(Value::as_str) {
+        Some("group") => item.get("items").and_then(Value::as_array),
+        _ => None,
+    }
+}
+
+fn collect(vault: &Path, items: &[Value], group: Option<&str>, out: &mut Vec<Bookmark>) {
+    for item in items {
+        if let Some(children) = group_items(item) {
+            let title = item.get("title").and_then(Value::as_str);
+            collect(vault, children, title.or(group), out);
+            continue;
+        }
+        let Some(relative) = entry_path(item) else {
+            continue;
+        };
+        let Ok(path) = vault_absolute(vault, relative) else {
+            continue;
+        };
+        out.push(Bookmark {
+            kind: item["type"].as_str().unwrap_or_default().to_string(),
+            location: VaultPath {
+                path: path.to_string_lossy().to_string(),
+                relative_path: relative.to_string(),
+            },
+            title: item.get("title").and_then(Value::as_str).map(str::to_string),
+            group: group.map(str::to_string),
+        });
+    }
+}
+
+fn contains_path(items: &[Value], relative: &str) -> bool {
+    items.iter().any(|item| {
+        entry_path(item) == Some(relative) || group_items(item).is_some_and(|children| contains_path(children, relative))
+    })
+}
+
+/// Apply `change` to every file and folder entry, groups included. Returns whether
+/// anything changed.
+fn apply(items: &mut Vec<Value>, change: &dyn Fn(&str) -> Change) -> bool {
+    let mut changed = false;
+    items.retain_mut(|item| {
+        if item.get("type").and_then(Value::as_str) == Some("group") {
+            if let Some(children) = item.get_mut("items").and_then(Value::as_array_mut) {
+                changed |= apply(children, change);
+            }
+            return true;
+        }
+        let Some(path) = entry_path(item) else {
+            return true;
+        };
+        match change(path) {
+            Change::Keep => true,
+            Change::Remove => {
+                changed = true;
+                false
+            }
+            Change::MoveTo(new_path) => {
+                item["path"] = Value::String(new_path);
+                changed = true;
+                true
+            }
+        }
+    });
+    changed
+}
+
+/// Load, edit and save the vault's bookmarks under the file's write lock, announcing the
+/// change when `edit` reports one
+async fn update(
+    app: &AppHandle,
+    vault: &Path,
+    edit: impl FnOnce(&mut Vec<Value>) -> Result<bool, IgneError> + Send,
+) -> Result<(), IgneError> {
+    let path = bookmarks_path(vault);
+    let changed = with_write_lock(app, &path, || {
+        let mut document = load(&path)?;
+        let changed = edit(items_mut(&mut document)?)?;
+        if changed {
+            save(&path, &document)?;
+        }
+        Ok(changed)
+    })
+    .await?;
+
+    if changed {
+        let _ = app.emit(
+            "bookmarks-changed",
+            BookmarksChanged {
+                vault_path: vault.to_string_lossy().to_string(),
+            },
+        );
+    }
+    Ok(())
+}
+
+/// Bookmarked notes and folders in the order the bookmarks file lists them
+#[tauri::command]
+pub fn get_bookmarks(vault_path: String, app: AppHandle) -> Result<Vec<Bookmark>, IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &vault)?;
+
+    let mut document = load(&bookmarks_path(&vault))?;
+    let mut bookmarks = vec![];
+    collect(&vault, items_mut(&mut document)?, None, &mut bookmarks);
+    Ok(bookmarks)
+}
+
+/// Bookmark a note or folder, inside `group` when given (created if needed). A path
+/// that's already bookmarked is left where it is.
+#[tauri::command]
+pub async fn add_bookmark(
+    vault_path: String,
+    path: String,
+    group: Option<String>,
+    app: AppHandle,
+) -> Result<(), IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    let target = PathBuf::from(&path);
+    ensure_in_scope(&app, &vault)?;
+    ensure_in_scope(&app, &target)?;
+    if !long_path(&target).exists() {
+        return Err(IgneError::not_found(format!("Path does not exist: {}", path), &path));
+    }
+    let relative = vault_relative(&vault, &target)?;
+    if relative.is_empty() {
+        return Err(IgneError::invalid_path("Can't bookmark the vault itself", &path));
+    }
+    let kind = if long_path(&target).is_dir() { "folder" } else { "file" };
+
+    update(&app, &vault, move |items| {
+        if contains_path(items, &relative) {
+            return Ok(false);
+        }
+        let entry = json!({ "type": kind, "ctime": now_millis(), "path": relative });
+
+        let Some(group) = group.filter(|g| !g.trim().is_empty()) else {
+            items.push(entry);
+            return Ok(true);
+        };
+        let existing = items.iter().position(|item| {
+            group_items(item).is_some() && item.get("title").and_then(Value::as_str) == Some(group.as_str())
+        });
+        let index = existing.unwrap_or_else(|| {
+            items.push(json!({ "type": "group", "ctime": now_millis(), "items": [], "title": group }));
+            items.len() - 1
+        });
+        if let Some(children) = items[index].get_mut("items").and_then(Value::as_array_mut) {
+            children.push(entry);
+        }
+        Ok(true)
+    })
+    .await
+}
+
+/// Remove every bookmark of a note or folder, wherever it's grouped
+#[tauri::command]
+pub async fn remove_bookmark(vault_path: String, path: String, app: AppHandle) -> Result<(), IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &vault)?;
+    let relative = vault_relative(&vault, Path::new(&path))?;
+
+    update(&app, &vault, move |items| {
+        Ok(apply(items, &|entry| if entry == relative { Change::Remove } else { Change::Keep }))
+    })
+    .await
+}
+
+/// Whether `entry` is `target` or inside it, and if so the part after it ("" or "/...")
+fn under<'a>(entry: &'a str, target: &str) -> Option<&'a str> {
+    entry.strip_prefix(target).filter(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Follow a moved or renamed note or folder in its vault's bookmarks. Best effort: the
+/// move already happened, so failures are only logged.
+pub async fn path_moved(app: &AppHandle, from: &Path, to: &Path) {
+    let Some(vault) = containing_vault(app, from) else {
+        return;
+    };
+    if !bookmarks_path(&vault).exists() {
+        return;
+    }
+    let Ok(from) = vault_relative(&vault, from) else {
+        return;
+    };
+    // Moved to another vault: the bookmark can't follow it
+    let to = vault_relative(&vault, to).ok();
+
+    let result = update(app, &vault, move |items| {
+        Ok(apply(items, &|entry| match (under(entry, &from), &to) {
+            (Some(rest), Some(to)) => Change::MoveTo(format!("{}{}", to, rest)),
+            (Some(_), None) => Change::Remove,
+            (None, _) => Change::Keep,
+        }))
+    })
+    .await;
+    if let Err(e) = result {
+        warn!("Failed to update bookmarks after a move: {}", e);
+    }
+}
+
+/// Drop bookmarks of a deleted note or folder, and of everything inside it
+pub async fn path_deleted(app: &AppHandle, path: &Path) {
+    let Some(vault) = containing_vault(app, path) else {
+        return;
+    };
+    if !bookmarks_path(&vault).exists() {
+        return;
+    }
+    let Ok(deleted) = vault_relative(&vault, path) else {
+        return;
+    };
+
+    let result = update(app, &vault, move |items| {
+        Ok(apply(items, &|entry| if under(entry, &deleted).is_some() { Change::Remove } else { Change::Keep }))
+    })
+    .await;
+    if let Err(e) = result {
+        warn!("Failed to update bookmarks after a delete: {}", e);
+    }
+}
@@ -0,0 +1,253 @@
+use log::warn;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::error::IgneError;
+use crate::file_type;
+use crate::paths::long_path;
+use crate::scope::ensure_in_scope;
+
+const MIN_CHUNK_SIZE: usize = 4 * 1024;
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+struct ActiveRead {
+    window: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Reads started by `read_file_chunked` that are still running, by read id
+pub struct ChunkedReads {
+    next_id: Mutex<u64>,
+    active: Mutex<HashMap<u64, ActiveRead>>,
+}
+
+impl ChunkedReads {
+    pub fn new() -> Self {
+        Self {
+            next_id: Mutex::new(0),
+            active: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn finish(&self, id: u64) {
+        if let Ok(mut active) = self.active.lock() {
+            active.remove(&id);
+        }
+    }
+
+    /// Stop every read a window started, e.g. because it closed
+    pub fn remove_window(&self, label: &str) {
+        if let Ok(mut active) = self.active.lock() {
+            active.retain(|_, read| {
+                if read.window == label {
+                    read.cancelled.store(true, Ordering::SeqCst);
+                }
+                read.window != label
+            });
+        }
+    }
+}
+
+impl Default for ChunkedReads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Payload of `file-chunk`
+#[derive(Serialize, Clone)]
+struct FileChunk {
+    read_id: u64,
+    path: String,
+    /// 0 for the first chunk, then counting up
+    seq: u64,
+    /// Byte offset of the chunk in the file
+    offset: u64,
+    content: String,
+}
+
+/// Payload of `file-chunk-done`, sent once per read however it ended
+#[derive(Serialize, Clone)]
+struct FileChunkDone {
+    read_id: u64,
+    path: String,
+    /// Chunks and bytes sent before the read ended, also when it failed partway
+    chunks: u64,
+    bytes: u64,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+/// Length of `bytes` without a UTF-8 character cut off at the end, which is carried over
+/// to the next chunk instead of decoding as replacement characters
+fn complete_len(bytes: &[u8]) -> usize {
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        if byte & 0xC0 == 0x80 {
+            continue;
+        }
+        let needed = match byte {
+            0xF0.. => 4,
+            0xE0.. => 3,
+            0xC0.. => 2,
+            _ => 1,
+        };
+        return if needed > back { bytes.len() - back } else { bytes.len() };
+    }
+    bytes.len()
+}
+
+/// Emit the file's chunks, counting the chunks and bytes sent in `sent`, which keeps them
+/// when the read fails partway
+fn stream(
+    app: &AppHandle,
+    label: &str,
+    id: u64,
+    path: &str,
+    chunk_size: usize,
+    cancelled: &AtomicBool,
+    sent: &mut (u64, u64),
+) -> Result<(), IgneError> {
+    let mut file = File::open(long_path(Path::new(path))).map_err(|e| IgneError::io(e, path))?;
+    let mut buffer = vec![0; chunk_size];
+    let mut carry: Vec<u8> = vec![];
+
+    loop {
+        if cancelled.load(Ordering::SeqCst) {
+            break;
+        }
+        let read = file.read(&mut buffer).map_err(|e| IgneError::io(e, path))?;
+        if read == 0 && carry.is_empty() {
+            break;
+        }
+        carry.extend_from_slice(&buffer[..read]);
+        // At the end of the file a cut-off character is as complete as it gets
+        let len = if read == 0 { carry.len() } else { complete_len(&carry) };
+        let content = String::from_utf8_lossy(&carry[..len]).into_owned();
+        carry.drain(..len);
+
+        let (seq, offset) = *sent;
+        let chunk = FileChunk { read_id: id, path: path.to_string(), seq, offset, content };
+        let _ = app.emit_to(label, "file-chunk", chunk);
+        *sent = (seq + 1, offset + len as u64);
+        if read == 0 {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Read a text file in chunks of about `chunk_size` bytes (4 KiB to 16 MiB), for files too
+/// big to open with `read_file` in one go. Returns a read id at once; the chunks follow as
+/// `file-chunk` events to the calling window, split between characters, then one
+/// `file-chunk-done`. Bytes that aren't UTF-8 become replacement characters.
+#[tauri::command]
+pub fn read_file_chunked(
+    path: String,
+    chunk_size: usize,
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    reads: State<'_, ChunkedReads>,
+) -> Result<u64, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+    let file_type = file_type::detect(&long_path(&path_obj)).map_err(|e| IgneError::io(e, &path))?;
+    if file_type.is_binary {
+        return Err(IgneError::binary_file(&file_type.mime, &path));
+    }
+
+    let id = {
+        let mut next_id = reads.next_id.lock()?;
+        *next_id += 1;
+        *next_id
+    };
+    let label = window.label().to_string();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let read = ActiveRead { window: label.clone(), cancelled: cancelled.clone() };
+    reads.active.lock()?.insert(id, read);
+
+    let chunk_size = chunk_size.clamp(MIN_CHUNK_SIZE, MAX_CHUNK_SIZE);
+    std::thread::spawn(move || {
+        let mut sent = (0, 0);
+        let result = stream(&app, &label, id, &path, chunk_size, &cancelled, &mut sent);
+        app.state::<ChunkedReads>().finish(id);
+
+        let (chunks, bytes) = sent;
+        let error = result.err().map(|e| {
+            warn!("Chunked read of {} failed after {} bytes: {}", path, bytes, e);
+            e.to_string()
+        });
+        let done = FileChunkDone {
+            read_id: id,
+            path,
+            chunks,
+            bytes,
+            cancelled: cancelled.load(Ordering::SeqCst),
+            error,
+        };
+        let _ = app.emit_to(label.as_str(), "file-chunk-done", done);
+    });
+    Ok(id)
+}
+
+/// Stop a `read_file_chunked` read, e.g. when the user switches to another file. Its
+/// `file-chunk-done` reports `cancelled`. Unknown or finished reads are ignored.
+#[tauri::command]
+pub fn cancel_chunked_read(read_id: u64, reads: State<'_, ChunkedReads>) -> Result<(), IgneError> {
+    if let Some(read) = reads.active.lock()?.remove(&read_id) {
+        read.cancelled.store(true, Ordering::SeqCst);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn carries_characters_cut_off_at_the_chunk_end() {
+        let text = "aé€😀";
+        let (e_acute, euro, emoji) = (1, 3, 6);
+        let cases = [
+            ("", 0),
+            ("a", 1),
+            (&text[..e_acute + 2], e_acute + 2),
+            (&text[..euro + 3], euro + 3),
+            (text, text.len()),
+        ];
+        for (bytes, expected) in cases {
+            assert_eq!(complete_len(bytes.as_bytes()), expected, "{:?}", bytes);
+        }
+
+        // Every cut inside a 2, 3 or 4 byte character leaves the whole character for later
+        for (start, width) in [(e_acute, 2), (euro, 3), (emoji, 4)] {
+            for cut in 1..width {
+                assert_eq!(complete_len(&text.as_bytes()[..start + cut]), start, "{} of {}", cut, width);
+            }
+        }
+    }
+
+    #[test]
+    fn passes_invalid_trailing_bytes_through() {
+        let cases: [(&[u8], usize); 6] = [
+            // Stray continuation bytes aren't the start of anything to wait for
+            (b"a\x80", 2),
+            (b"\x80\x80\x80", 3),
+            (b"\x80\x80\x80\x80", 4),
+            // A lead byte followed by too many continuations is complete as it will get
+            (b"\xC3\xA9\xA9", 3),
+            // A lone lead byte might still be completed by the next chunk
+            (b"a\xFF", 1),
+            (b"a\xE2\x82", 1),
+        ];
+        for (bytes, expected) in cases {
+            assert_eq!(complete_len(bytes), expected, "{:?}", bytes);
+        }
+    }
+}
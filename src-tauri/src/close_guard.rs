@@ -0,0 +1,281 @@
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult};
+
+use crate::autosave::{self, AutosaveState};
+use crate::error::IgneError;
+use crate::window_state;
+
+const SAVE: &str = "Save";
+const DONT_SAVE: &str = "Don't Save";
+const CANCEL: &str = "Cancel";
+
+/// How long to wait for a window to report its notes saved before giving up on closing
+const SAVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Unsaved note count per window, reported by the frontend, so closing or quitting can
+/// ask before throwing edits away
+pub struct CloseGuard {
+    dirty: Mutex<HashMap<String, u32>>,
+    /// A prompt is on screen; further close requests are ignored until it's answered
+    prompting: AtomicBool,
+    /// The user already confirmed quitting, so the next exit request goes through
+    quit_confirmed: AtomicBool,
+}
+
+impl CloseGuard {
+    pub fn new() -> Self {
+        Self {
+            dirty: Mutex::new(HashMap::new()),
+            prompting: AtomicBool::new(false),
+            quit_confirmed: AtomicBool::new(false),
+        }
+    }
+
+    fn dirty_count(&self, label: &str) -> u32 {
+        self.dirty
+            .lock()
+            .map(|dirty| dirty.get(label).copied().unwrap_or(0))
+            .unwrap_or(0)
+    }
+
+    fn dirty_windows(&self) -> Vec<String> {
+        let Ok(dirty) = self.dirty.lock() else {
+            return vec![];
+        };
+        let mut labels: Vec<String> = dirty
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(label, _)| label.clone())
+            .collect();
+        labels.sort();
+        labels
+    }
+
+    /// Forget a window once it's gone
+    pub fn remove_window(&self, label: &str) {
+        if let Ok(mut dirty) = self.dirty.lock() {
+            dirty.remove(label);
+        }
+    }
+}
+
+impl Default for CloseGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Payload of `window-close-choice`, sent to the window the prompt was about. On "save"
+/// the window should save and then report `set_window_dirty(label, 0)`; the backend
+/// finishes the close once it does.
+#[derive(Serialize, Clone)]
+struct CloseChoice {
+    action: &'static str,
+    quitting: bool,
+}
+
+#[derive(Deserialize)]
+struct CloseGuardSettings {
+    #[serde(default, rename = "skipUnsavedPromptWithAutosave")]
+    skip_with_autosave: bool,
+}
+
+/// Autosave is on and the user asked not to be prompted, so a window whose edits the
+/// backend holds has them flushed instead. The caller still checks that it holds them.
+fn skip_prompt(app: &AppHandle) -> bool {
+    let skip = app
+        .path()
+        .app_data_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join("settings.json")).ok())
+        .and_then(|content| serde_json::from_str::<CloseGuardSettings>(&content).ok())
+        .is_some_and(|settings| settings.skip_with_autosave);
+    skip && autosave::autosave_interval(app) > 0
+}
+
+#[tauri::command]
+pub fn set_window_dirty(
+    window_label: String,
+    dirty_count: u32,
+    guard: State<'_, CloseGuard>,
+) -> Result<(), IgneError> {
+    guard.dirty.lock()?.insert(window_label, dirty_count);
+    Ok(())
+}
+
+enum Choice {
+    Save,
+    Discard,
+    Cancel,
+}
+
+fn ask(app: &AppHandle, label: &str, count: u32) -> Choice {
+    let notes = if count == 1 {
+        "1 note".to_string()
+    } else {
+        format!("{} notes", count)
+    };
+    let mut dialog = app
+        .dialog()
+        .message(format!("You have unsaved changes in {}. Save them before closing?", notes))
+        .title("Unsaved changes")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::YesNoCancelCustom(
+            SAVE.to_string(),
+            DONT_SAVE.to_string(),
+            CANCEL.to_string(),
+        ));
+    if let Some(window) = app.get_webview_window(label) {
+        let _ = window.set_focus();
+        dialog = dialog.parent(&window);
+    }
+
+    match dialog.blocking_show_with_result() {
+        MessageDialogResult::Yes => Choice::Save,
+        MessageDialogResult::No => Choice::Discard,
+        MessageDialogResult::Custom(label) if label == SAVE => Choice::Save,
+        MessageDialogResult::Custom(label) if label == DONT_SAVE => Choice::Discard,
+        _ => Choice::Cancel,
+    }
+}
+
+/// Wait for the frontend to save the window's notes and report it clean
+fn wait_until_saved(guard: &CloseGuard, label: &str) -> bool {
+    let started = Instant::now();
+    while guard.dirty_count(label) > 0 {
+        if started.elapsed() > SAVE_TIMEOUT {
+            warn!("Window {} did not finish saving; not closing", label);
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    true
+}
+
+/// Prompt for each window in `labels` that has unsaved notes, then close those windows
+/// (or quit). Every prompt is answered before anything is saved or dropped, so cancelling
+/// any of them aborts the whole close with every window as it was. "Don't Save" drops the
+/// window's autosave buffers, so only saved windows' edits are flushed on exit. Runs on
+/// its own thread since the dialogs block.
+fn confirm_close(app: AppHandle, labels: Vec<String>, quitting: bool) {
+    std::thread::spawn(move || {
+        let guard = app.state::<CloseGuard>();
+        let autosave = app.state::<AutosaveState>();
+        let skip = skip_prompt(&app);
+        let abort = || guard.prompting.store(false, Ordering::SeqCst);
+
+        let mut choices = vec![];
+        let mut flushed = vec![];
+        for label in &labels {
+            let count = guard.dirty_count(label);
+            if count == 0 {
+                continue;
+            }
+            // Only edits sent with `update_dirty_buffer` or `debounced_write_file` can be
+            // flushed here; a window with others must still be asked
+            if skip && autosave.held_by(label) >= count as usize {
+                flushed.push(label);
+                continue;
+            }
+            match ask(&app, label, count) {
+                Choice::Cancel => {
+                    let action = "cancel";
+                    let _ = app.emit_to(label.as_str(), "window-close-choice", CloseChoice { action, quitting });
+                    abort();
+                    return;
+                }
+                choice => choices.push((label.clone(), choice)),
+            }
+        }
+
+        let saving: Vec<&String> = choices
+            .iter()
+            .filter(|(_, choice)| matches!(choice, Choice::Save))
+            .map(|(label, _)| label)
+            .collect();
+        for label in &saving {
+            let action = "save";
+            let _ = app.emit_to(label.as_str(), "window-close-choice", CloseChoice { action, quitting });
+        }
+        if !saving.iter().all(|label| wait_until_saved(&guard, label)) {
+            abort();
+            return;
+        }
+
+        for label in &flushed {
+            tauri::async_runtime::block_on(autosave::flush_window(&app, label));
+            // A failed write stays buffered; closing now would lose it
+            if autosave.held_by(label) > 0 {
+                warn!("Autosave of window {} failed; not closing", label);
+                abort();
+                return;
+            }
+        }
+
+        for (label, choice) in &choices {
+            if matches!(choice, Choice::Discard) {
+                let action = "discard";
+                let _ = app.emit_to(label.as_str(), "window-close-choice", CloseChoice { action, quitting });
+                // Otherwise the flush on exit, or the scheduler, would write the edits anyway
+                if let Err(e) = autosave.discard_window(label) {
+                    warn!("Could not drop unsaved edits of window {}: {}", label, e);
+                }
+            }
+            guard.remove_window(label);
+        }
+
+        guard.prompting.store(false, Ordering::SeqCst);
+        // Destroying and exiting skip `CloseRequested`, where geometry is normally saved
+        if quitting {
+            for label in app.webview_windows().keys() {
+                window_state::remember(&app, label);
+            }
+            guard.quit_confirmed.store(true, Ordering::SeqCst);
+            app.exit(0);
+        } else {
+            for label in &labels {
+                if let Some(window) = app.get_webview_window(label) {
+                    window_state::remember(&app, label);
+                    let _ = window.destroy();
+                }
+            }
+        }
+    });
+}
+
+/// Handle a window's close button. Returns whether the close must be held back while
+/// the user is asked.
+pub fn on_close_requested(app: &AppHandle, label: &str) -> bool {
+    let guard = app.state::<CloseGuard>();
+    if guard.dirty_count(label) == 0 {
+        return false;
+    }
+    if !guard.prompting.swap(true, Ordering::SeqCst) {
+        confirm_close(app.clone(), vec![label.to_string()], false);
+    }
+    true
+}
+
+/// Handle Quit (menu, Cmd+Q) for every window. Returns whether the exit must be held
+/// back while the user is asked.
+pub fn on_exit_requested(app: &AppHandle) -> bool {
+    let guard = app.state::<CloseGuard>();
+    if guard.quit_confirmed.load(Ordering::SeqCst) {
+        return false;
+    }
+    let labels = guard.dirty_windows();
+    if labels.is_empty() {
+        return false;
+    }
+    if !guard.prompting.swap(true, Ordering::SeqCst) {
+        confirm_close(app.clone(), labels, true);
+    }
+    true
+}
@@ -0,0 +1,176 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::IgneError;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorScheme {
+    Light,
+    Dark,
+    Unknown,
+}
+
+pub struct ColorSchemeState {
+    /// The scheme last sent as `color-scheme-changed`. Every window reports a theme
+    /// change, and only the first of them is passed on.
+    last: Mutex<Option<ColorScheme>>,
+    /// The `gsettings monitor` process `watch` started, killed by `stop`
+    #[cfg(target_os = "linux")]
+    monitor: Mutex<Option<std::process::Child>>,
+}
+
+impl ColorSchemeState {
+    pub fn new() -> Self {
+        Self {
+            last: Mutex::new(None),
+            #[cfg(target_os = "linux")]
+            monitor: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for ColorSchemeState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<tauri::Theme> for ColorScheme {
+    fn from(theme: tauri::Theme) -> Self {
+        match theme {
+            tauri::Theme::Light => ColorScheme::Light,
+            tauri::Theme::Dark => ColorScheme::Dark,
+            _ => ColorScheme::Unknown,
+        }
+    }
+}
+
+/// A `color-scheme` value as gsettings prints it, e.g. `'prefer-dark'`
+#[cfg(target_os = "linux")]
+fn parse_gnome_scheme(value: &str) -> Option<ColorScheme> {
+    match value.trim().trim_matches('\'') {
+        "prefer-dark" => Some(ColorScheme::Dark),
+        "prefer-light" | "default" => Some(ColorScheme::Light),
+        _ => None,
+    }
+}
+
+/// GNOME's `color-scheme` setting, else a `GTK_THEME` such as `Adwaita:dark`
+#[cfg(target_os = "linux")]
+fn detect(_app: &AppHandle) -> ColorScheme {
+    let gnome = std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| parse_gnome_scheme(&String::from_utf8_lossy(&output.stdout)));
+    if gnome == Some(ColorScheme::Dark) {
+        return ColorScheme::Dark;
+    }
+
+    match std::env::var("GTK_THEME") {
+        Ok(theme) if theme.to_lowercase().contains("dark") => ColorScheme::Dark,
+        Ok(theme) if !theme.is_empty() => ColorScheme::Light,
+        _ => gnome.unwrap_or(ColorScheme::Unknown),
+    }
+}
+
+/// The window's theme, which the OS reports from `NSApplication.effectiveAppearance` on
+/// macOS and the `AppsUseLightTheme` registry value on Windows
+#[cfg(not(target_os = "linux"))]
+fn detect(app: &AppHandle) -> ColorScheme {
+    let label = crate::focused_window_label(app);
+    app.get_webview_window(&label)
+        .or_else(|| app.webview_windows().into_values().next())
+        .and_then(|window| window.theme().ok())
+        .map_or(ColorScheme::Unknown, ColorScheme::from)
+}
+
+/// Emit `color-scheme-changed` to every window unless `scheme` was the last one sent
+pub fn notify(app: &AppHandle, scheme: ColorScheme) {
+    if let Ok(mut last) = app.state::<ColorSchemeState>().last.lock() {
+        if *last == Some(scheme) {
+            return;
+        }
+        *last = Some(scheme);
+    }
+    let _ = app.emit("color-scheme-changed", scheme);
+}
+
+/// A window saw the OS theme change. On Linux that's the GTK theme, which may disagree
+/// with GNOME's `color-scheme`, so the scheme is detected again.
+pub fn on_theme_changed(app: &AppHandle, theme: tauri::Theme) {
+    #[cfg(target_os = "linux")]
+    let scheme = {
+        let _ = theme;
+        detect(app)
+    };
+    #[cfg(not(target_os = "linux"))]
+    let scheme = ColorScheme::from(theme);
+    notify(app, scheme);
+}
+
+/// Follow OS scheme changes. Windows report theme changes themselves (see
+/// `WindowEvent::ThemeChanged`); GNOME's `color-scheme` setting isn't one of them, so
+/// on Linux it's watched with `gsettings monitor`.
+pub fn watch(app: &AppHandle) {
+    if let Ok(mut last) = app.state::<ColorSchemeState>().last.lock() {
+        *last = Some(detect(app));
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use std::io::{BufRead, BufReader};
+        use std::process::{Command, Stdio};
+
+        let Ok(mut child) = Command::new("gsettings")
+            .args(["monitor", "org.gnome.desktop.interface", "color-scheme"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            return;
+        };
+        let stdout = child.stdout.take();
+        if let Ok(mut monitor) = app.state::<ColorSchemeState>().monitor.lock() {
+            if let Some(mut previous) = monitor.replace(child) {
+                let _ = previous.kill();
+                let _ = previous.wait();
+            }
+        }
+        let app = app.clone();
+        // Ends when `stop` kills the monitor and its output closes
+        std::thread::spawn(move || {
+            let Some(stdout) = stdout else {
+                return;
+            };
+            // Lines look like `color-scheme: 'prefer-dark'`
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if line.split_once(':').and_then(|(_, value)| parse_gnome_scheme(value)).is_some() {
+                    notify(&app, detect(&app));
+                }
+            }
+        });
+    }
+}
+
+/// Stop following OS scheme changes, killing the monitor process `watch` started
+pub fn stop(app: &AppHandle) {
+    #[cfg(target_os = "linux")]
+    if let Ok(mut monitor) = app.state::<ColorSchemeState>().monitor.lock() {
+        if let Some(mut child) = monitor.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = app;
+}
+
+/// Whether the OS is in light or dark mode, for the "follow system" appearance option
+#[tauri::command]
+pub fn get_color_scheme(app: AppHandle) -> Result<ColorScheme, IgneError> {
+    Ok(detect(&app))
+}
@@ -0,0 +1,145 @@
+use chrono::Local;
+use serde::Serialize;
+use std::backtrace::Backtrace;
+use std::fs;
+use std::io::Write;
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::error::IgneError;
+
+const CRASH_DIR: &str = "crashes";
+/// Reports the user has already been told about
+const REPORTED_DIR: &str = "reported";
+
+/// One panic is written at a time so concurrent panics can't interleave in a report
+static WRITING: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Clone)]
+pub struct CrashReport {
+    pub file_name: String,
+    pub path: String,
+    pub content: String,
+}
+
+fn crash_dir(app: &AppHandle) -> Result<PathBuf, IgneError> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(CRASH_DIR))
+        .map_err(|e| IgneError::other(format!("Failed to get app data dir: {}", e)))
+}
+
+fn panic_message(info: &PanicHookInfo) -> String {
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string());
+    match info.location() {
+        Some(location) => format!("{} at {}:{}", message, location.file(), location.line()),
+        None => message,
+    }
+}
+
+fn write_report(dir: &Path, info: &PanicHookInfo) -> std::io::Result<()> {
+    let _guard = WRITING.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    fs::create_dir_all(dir)?;
+
+    let now = Local::now();
+    let thread = std::thread::current();
+    let report = format!(
+        "Igne {}\nOS: {} {}\nTime: {}\nThread: {}\n\n{}\n\nBacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        now.to_rfc3339(),
+        thread.name().unwrap_or("<unnamed>"),
+        panic_message(info),
+        Backtrace::force_capture(),
+    );
+
+    // Several panics in the same millisecond each get their own file
+    let stamp = now.format("%Y%m%d-%H%M%S%.3f").to_string();
+    let mut n = 0;
+    let mut file = loop {
+        let name = match n {
+            0 => format!("{}.txt", stamp),
+            n => format!("{}-{}.txt", stamp, n),
+        };
+        match fs::File::options().write(true).create_new(true).open(dir.join(name)) {
+            Ok(file) => break file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && n < 100 => n += 1,
+            Err(e) => return Err(e),
+        }
+    };
+    file.write_all(report.as_bytes())?;
+    file.sync_all()
+}
+
+/// Write a report to `<app_data_dir>/crashes/<timestamp>.txt` for every panic, then run
+/// the previous hook so the message still reaches stderr
+pub fn install_panic_hook(app: &AppHandle) {
+    let Ok(dir) = crash_dir(app) else {
+        return;
+    };
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        // A panic inside the hook aborts the process and can't be caught, so write_report
+        // returns its failures instead and they're ignored here
+        let _ = write_report(&dir, info);
+        previous(info);
+    }));
+}
+
+fn report_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|e| e == "txt"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Crash reports from earlier sessions the user hasn't seen yet, oldest first
+#[tauri::command]
+pub fn get_unreported_crashes(app: AppHandle) -> Result<Vec<CrashReport>, IgneError> {
+    let dir = crash_dir(&app)?;
+    Ok(report_files(&dir)
+        .into_iter()
+        .filter_map(|path| {
+            let content = fs::read_to_string(&path).ok()?;
+            Some(CrashReport {
+                file_name: path.file_name()?.to_string_lossy().to_string(),
+                path: path.to_string_lossy().to_string(),
+                content,
+            })
+        })
+        .collect())
+}
+
+/// Move the pending reports to `crashes/reported` so they aren't shown again but can
+/// still be attached to a bug report
+#[tauri::command]
+pub fn mark_crashes_reported(app: AppHandle) -> Result<(), IgneError> {
+    let dir = crash_dir(&app)?;
+    let reported = dir.join(REPORTED_DIR);
+    let files = report_files(&dir);
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    fs::create_dir_all(&reported).map_err(|e| IgneError::io(e, &reported))?;
+    for file in files {
+        if let Some(name) = file.file_name() {
+            fs::rename(&file, reported.join(name)).map_err(|e| IgneError::io(e, &file))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+use crate::atomic_write;
+use crate::error::IgneError;
+use crate::paths::safe_join_path;
+use crate::scope::ensure_in_scope;
+use crate::write_lock::with_write_lock;
+
+/// Key in `.obsidian/appearance.json` listing enabled snippets by name (file stem)
+const ENABLED_KEY: &str = "enabledCssSnippets";
+
+#[derive(Serialize, Clone)]
+pub struct CssSnippet {
+    /// File name without `.css`, as Obsidian lists it
+    pub name: String,
+    pub path: String,
+    pub enabled: bool,
+}
+
+/// Payload of `css-snippet-changed`
+#[derive(Serialize, Clone)]
+struct CssSnippetChanged {
+    vault_path: String,
+    name: String,
+    enabled: bool,
+}
+
+fn snippets_dir(vault: &Path) -> PathBuf {
+    vault.join(".obsidian").join("snippets")
+}
+
+fn appearance_path(vault: &Path) -> PathBuf {
+    vault.join(".obsidian").join("appearance.json")
+}
+
+/// `appearance.json` as an object; empty when missing. Invalid JSON is an error so the
+/// user's settings are never overwritten with defaults.
+fn load_appearance(path: &Path) -> Result<Map<String, Value>, IgneError> {
+    match fs::read_to_string(path) {
+        Ok(content) => match serde_json::from_str(&content)? {
+            Value::Object(map) => Ok(map),
+            _ => Err(IgneError::invalid_input(format!("Not a settings object: {}", path.display()))),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Map::new()),
+        Err(e) => Err(IgneError::io(e, path)),
+    }
+}
+
+fn enabled_names(appearance: &Map<String, Value>) -> Vec<String> {
+    appearance
+        .get(ENABLED_KEY)
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// CSS snippets in `.obsidian/snippets`, sorted by name, with whether each is enabled
+#[tauri::command]
+pub fn list_css_snippets(vault_path: String, app: AppHandle) -> Result<Vec<CssSnippet>, IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &vault)?;
+
+    let dir = snippets_dir(&vault);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(IgneError::io(e, &dir)),
+    };
+    let enabled = enabled_names(&load_appearance(&appearance_path(&vault))?);
+
+    let mut snippets: Vec<CssSnippet> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("css")))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            Some(CssSnippet {
+                enabled: enabled.contains(&name),
+                path: path.to_string_lossy().to_string(),
+                name,
+            })
+        })
+        .collect();
+    snippets.sort_by_key(|snippet| snippet.name.to_lowercase());
+    Ok(snippets)
+}
+
+/// Turn a snippet on or off in `appearance.json`, keeping its other settings, and emit
+/// `css-snippet-changed` so open windows reload their styles
+#[tauri::command]
+pub async fn set_css_snippet_enabled(
+    vault_path: String,
+    snippet_name: String,
+    enabled: bool,
+    app: AppHandle,
+) -> Result<(), IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &vault)?;
+    let name = snippet_name.strip_suffix(".css").unwrap_or(&snippet_name).to_string();
+    if name.is_empty() || name.contains(['/', '\\']) {
+        return Err(IgneError::invalid_input(format!("Invalid CSS snippet name: {}", snippet_name)));
+    }
+    let file = safe_join_path(&snippets_dir(&vault), &format!("{}.css", name))?;
+    // Disabling a snippet whose file is gone still cleans up the list
+    if enabled && !file.is_file() {
+        return Err(IgneError::not_found(format!("CSS snippet not found: {}", name), &file));
+    }
+
+    let path = appearance_path(&vault);
+    with_write_lock(&app, &path, || {
+        let mut appearance = load_appearance(&path)?;
+        let mut names = enabled_names(&appearance);
+        names.retain(|existing| *existing != name);
+        if enabled {
+            names.push(name.clone());
+        }
+        appearance.insert(ENABLED_KEY.to_string(), Value::from(names));
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| IgneError::io(e, dir))?;
+        }
+        atomic_write(&path, serde_json::to_string_pretty(&appearance)?.as_bytes())
+    })
+    .await?;
+
+    let _ = app.emit(
+        "css-snippet-changed",
+        CssSnippetChanged {
+            vault_path,
+            name,
+            enabled,
+        },
+    );
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use serde::Serialize;
+use similar::{DiffTag, TextDiff};
+
+use crate::error::IgneError;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// A run of lines with the same change kind. Starts are 0-based line numbers.
+#[derive(Serialize, Clone)]
+pub struct DiffChunk {
+    pub kind: DiffKind,
+    pub old_start: u64,
+    pub new_start: u64,
+    pub lines: Vec<String>,
+}
+
+/// CRLF and lone CR would otherwise make every line of a re-saved file look changed
+fn normalize_line_endings(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Line-level diff between two texts, e.g. a note and one of its saved revisions
+#[tauri::command]
+pub fn compute_diff(old: String, new: String) -> Result<Vec<DiffChunk>, IgneError> {
+    let old = normalize_line_endings(&old);
+    let new = normalize_line_endings(&new);
+
+    let diff = TextDiff::from_lines(&old, &new);
+    let old_lines = diff.old_slices();
+    let new_lines = diff.new_slices();
+    let collect = |slices: &[&str]| -> Vec<String> {
+        slices.iter().map(|l| l.trim_end_matches('\n').to_string()).collect()
+    };
+
+    let mut chunks = Vec::new();
+    for op in diff.ops() {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+
+        // A replace is reported as the deleted lines followed by the inserted ones
+        if matches!(tag, DiffTag::Equal | DiffTag::Delete | DiffTag::Replace) {
+            chunks.push(DiffChunk {
+                kind: if tag == DiffTag::Equal { DiffKind::Equal } else { DiffKind::Delete },
+                old_start: old_range.start as u64,
+                new_start: new_range.start as u64,
+                lines: collect(&old_lines[old_range.clone()]),
+            });
+        }
+        if matches!(tag, DiffTag::Insert | DiffTag::Replace) {
+            chunks.push(DiffChunk {
+                kind: DiffKind::Insert,
+                old_start: old_range.end as u64,
+                new_start: new_range.start as u64,
+                lines: collect(&new_lines[new_range]),
+            });
+        }
+    }
+
+    Ok(chunks)
+}
@@ -0,0 +1,242 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, State};
+
+use crate::error::IgneError;
+use crate::scope::ensure_in_scope;
+
+const DEFAULT_MAX_STALENESS_SECS: u64 = 30;
+
+#[derive(Serialize, Clone, Copy, Default)]
+pub struct FolderSize {
+    pub total_bytes: u64,
+    pub file_count: u64,
+    /// Subfolders, not counting the folder itself
+    pub dir_count: u64,
+    /// False when the timeout ran out first and the numbers are a lower bound
+    pub complete: bool,
+}
+
+/// Folder sizes with the time they were measured. The directory watcher drops every
+/// entry containing a changed path, so watched vaults are re-measured after edits.
+pub struct DirectorySizeCache {
+    entries: Mutex<HashMap<String, (FolderSize, Instant)>>,
+}
+
+impl DirectorySizeCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn invalidate_containing(&self, changed: &[PathBuf]) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.retain(|dir, _| !changed.iter().any(|path| path.starts_with(dir)));
+        }
+    }
+
+    /// Drop every measurement of `root` and the folders in or around it
+    pub fn invalidate_under(&self, root: &Path) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.retain(|dir, _| !Path::new(dir).starts_with(root) && !root.starts_with(dir));
+        }
+    }
+
+    fn get(&self, path: &str, max_age: Duration) -> Option<FolderSize> {
+        let entries = self.entries.lock().ok()?;
+        let (size, measured_at) = entries.get(path)?;
+        (measured_at.elapsed() <= max_age).then_some(*size)
+    }
+
+    /// Partial results aren't kept, so the next call tries again
+    fn insert(&self, path: String, size: FolderSize) {
+        if !size.complete {
+            return;
+        }
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(path, (size, Instant::now()));
+        }
+    }
+}
+
+impl Default for DirectorySizeCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// State shared by the walker threads of one measurement
+struct Walk {
+    deadline: Option<Instant>,
+    timed_out: AtomicBool,
+    /// (device, inode) of files with several hard links, so each is counted once
+    seen_links: Mutex<HashSet<(u64, u64)>>,
+}
+
+impl Walk {
+    fn expired(&self) -> bool {
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.timed_out.store(true, Ordering::Relaxed);
+        }
+        self.timed_out.load(Ordering::Relaxed)
+    }
+
+    /// Whether this file's bytes were already counted through another hard link
+    #[cfg(unix)]
+    fn seen_before(&self, metadata: &fs::Metadata) -> bool {
+        use std::os::unix::fs::MetadataExt;
+
+        metadata.nlink() > 1
+            && self
+                .seen_links
+                .lock()
+                .is_ok_and(|mut seen| !seen.insert((metadata.dev(), metadata.ino())))
+    }
+
+    /// File IDs need an open handle on Windows; too slow to do for every file
+    #[cfg(not(unix))]
+    fn seen_before(&self, _metadata: &fs::Metadata) -> bool {
+        false
+    }
+}
+
+fn add(into: &mut FolderSize, other: FolderSize) {
+    into.total_bytes += other.total_bytes;
+    into.file_count += other.file_count;
+    into.dir_count += other.dir_count;
+}
+
+/// Sum one directory level into `size`, returning its subfolders. Symlinks are not
+/// followed or counted.
+fn scan_dir(dir: &Path, walk: &Walk, size: &mut FolderSize) -> Vec<PathBuf> {
+    let mut subdirs = vec![];
+    let Ok(entries) = fs::read_dir(dir) else {
+        return subdirs;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            size.dir_count += 1;
+            subdirs.push(entry.path());
+        } else if file_type.is_file() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            size.file_count += 1;
+            if !walk.seen_before(&metadata) {
+                size.total_bytes += metadata.len();
+            }
+        }
+    }
+    subdirs
+}
+
+fn walk_subtrees(roots: Vec<PathBuf>, walk: &Walk) -> FolderSize {
+    let mut size = FolderSize::default();
+    let mut stack = roots;
+    while let Some(dir) = stack.pop() {
+        if walk.expired() {
+            break;
+        }
+        stack.extend(scan_dir(&dir, walk, &mut size));
+    }
+    size
+}
+
+/// Measure `root`, spreading its top-level subfolders over one thread per core
+fn measure(root: &Path, timeout: Option<Duration>) -> FolderSize {
+    let walk = Walk {
+        deadline: timeout.map(|timeout| Instant::now() + timeout),
+        timed_out: AtomicBool::new(false),
+        seen_links: Mutex::new(HashSet::new()),
+    };
+
+    let mut size = FolderSize::default();
+    let subdirs = scan_dir(root, &walk, &mut size);
+    let workers = std::thread::available_parallelism().map_or(4, |n| n.get()).min(subdirs.len().max(1));
+    let mut shares: Vec<Vec<PathBuf>> = vec![vec![]; workers];
+    for (i, dir) in subdirs.into_iter().enumerate() {
+        shares[i % workers].push(dir);
+    }
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = shares
+            .into_iter()
+            .map(|share| scope.spawn(|| walk_subtrees(share, &walk)))
+            .collect();
+        for handle in handles {
+            if let Ok(part) = handle.join() {
+                add(&mut size, part);
+            }
+        }
+    });
+
+    size.complete = !walk.timed_out.load(Ordering::Relaxed);
+    size
+}
+
+fn check_dir(path: &str) -> Result<(), IgneError> {
+    if Path::new(path).is_dir() {
+        Ok(())
+    } else {
+        Err(IgneError::not_found(format!("Not a directory: {}", path), path))
+    }
+}
+
+/// Total bytes under a folder, e.g. for vault sizes in the vault switcher. A cached size
+/// is returned while it's at most `max_staleness_secs` old (default 30; 0 always
+/// re-measures).
+#[tauri::command]
+pub fn get_directory_size(
+    path: String,
+    max_staleness_secs: Option<u64>,
+    app: AppHandle,
+    cache: State<'_, DirectorySizeCache>,
+) -> Result<u64, IgneError> {
+    ensure_in_scope(&app, Path::new(&path))?;
+
+    let max_age = Duration::from_secs(max_staleness_secs.unwrap_or(DEFAULT_MAX_STALENESS_SECS));
+    if let Some(size) = cache.get(&path, max_age) {
+        return Ok(size.total_bytes);
+    }
+
+    check_dir(&path)?;
+    let size = measure(Path::new(&path), None);
+    cache.insert(path, size);
+    Ok(size.total_bytes)
+}
+
+/// Bytes, files and subfolders under a folder for the "folder properties" popover.
+/// Hard-linked files count once (where the platform exposes inode numbers) and symlinked
+/// folders aren't followed. With `timeout_ms` the walk stops early and returns what it
+/// has so far, marked incomplete.
+#[tauri::command]
+pub async fn get_folder_size(
+    path: String,
+    timeout_ms: Option<u64>,
+    app: AppHandle,
+    cache: State<'_, DirectorySizeCache>,
+) -> Result<FolderSize, IgneError> {
+    ensure_in_scope(&app, Path::new(&path))?;
+
+    if let Some(size) = cache.get(&path, Duration::from_secs(DEFAULT_MAX_STALENESS_SECS)) {
+        return Ok(size);
+    }
+
+    check_dir(&path)?;
+    let root = PathBuf::from(&path);
+    let timeout = timeout_ms.map(Duration::from_millis);
+    let size = tauri::async_runtime::spawn_blocking(move || measure(&root, timeout))
+        .await
+        .map_err(|e| IgneError::other(format!("Folder size task failed: {}", e)))?;
+    cache.insert(path, size);
+    Ok(size)
+}
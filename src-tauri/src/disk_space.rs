@@ -0,0 +1,71 @@
+use serde::Serialize;
+use std::io;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::error::IgneError;
+use crate::scope::{ensure_in_scope, resolve};
+
+#[derive(Serialize, Clone, Copy)]
+pub struct DiskSpace {
+    pub total: u64,
+    /// Space the current user can actually use (excludes blocks reserved for root)
+    pub available: u64,
+    pub used: u64,
+}
+
+#[cfg(unix)]
+fn query(path: &Path) -> io::Result<DiskSpace> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let block = stats.f_frsize as u64;
+    let total = stats.f_blocks as u64 * block;
+    let free = stats.f_bfree as u64 * block;
+    Ok(DiskSpace {
+        total,
+        available: stats.f_bavail as u64 * block,
+        used: total.saturating_sub(free),
+    })
+}
+
+#[cfg(windows)]
+fn query(path: &Path) -> io::Result<DiskSpace> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let (mut available, mut total, mut free) = (0u64, 0u64, 0u64);
+    if unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, &mut total, &mut free) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(DiskSpace {
+        total,
+        available,
+        used: total.saturating_sub(free),
+    })
+}
+
+/// The path itself, or its closest existing ancestor for a destination not created yet
+fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+    resolve(path).ancestors().find(|p| p.exists()).map(Path::to_path_buf)
+}
+
+/// Total, available and used bytes of the filesystem holding `path`, for a pre-flight
+/// check before bulk imports and exports
+#[tauri::command]
+pub fn get_disk_space(path: String, app: AppHandle) -> Result<DiskSpace, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+
+    let existing = existing_ancestor(&path_obj)
+        .ok_or_else(|| IgneError::not_found(format!("No mounted filesystem for {}", path), &path))?;
+    query(&existing).map_err(|e| IgneError::io(e, &path))
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsStr;
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::IgneError;
+
+/// Only the app's own variables are visible to the frontend, so tokens and other secrets
+/// in the user's environment never cross the IPC boundary
+const READABLE_PREFIX: &str = "IGNE_";
+
+/// Variables the frontend may change at runtime (inherited by processes the app starts)
+const SETTABLE: &[&str] = &["IGNE_VAULT_PATH", "IGNE_DEBUG"];
+
+/// Values set with `set_env_var`. The process environment itself is never changed, since
+/// `env::set_var` races with any other thread reading it; processes the app starts get
+/// these through `command` instead.
+pub struct EnvOverrides(Mutex<HashMap<String, String>>);
+
+impl EnvOverrides {
+    pub fn new() -> Self {
+        Self(Mutex::new(HashMap::new()))
+    }
+}
+
+/// A `Command` for `program` with the variables set at runtime added to its environment
+pub fn command(app: &AppHandle, program: impl AsRef<OsStr>) -> Command {
+    let mut command = Command::new(program);
+    if let Ok(overrides) = app.state::<EnvOverrides>().0.lock() {
+        command.envs(overrides.iter());
+    }
+    command
+}
+
+#[tauri::command]
+pub fn get_env_var(name: String, overrides: State<'_, EnvOverrides>) -> Result<Option<String>, IgneError> {
+    if !name.starts_with(READABLE_PREFIX) {
+        return Err(IgneError::invalid_input(format!(
+            "Only {}* environment variables can be read: {}",
+            READABLE_PREFIX, name
+        )));
+    }
+    if let Some(value) = overrides.0.lock()?.get(&name) {
+        return Ok(Some(value.clone()));
+    }
+    Ok(env::var(&name).ok())
+}
+
+#[tauri::command]
+pub fn set_env_var(name: String, value: String, overrides: State<'_, EnvOverrides>) -> Result<(), IgneError> {
+    if !SETTABLE.contains(&name.as_str()) {
+        return Err(IgneError::invalid_input(format!(
+            "Environment variable can't be changed at runtime: {}",
+            name
+        )));
+    }
+    if value.contains('\0') {
+        return Err(IgneError::invalid_input("Environment values can't contain NUL"));
+    }
+    overrides.0.lock()?.insert(name, value);
+    Ok(())
+}
@@ -0,0 +1,333 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::sync::PoisonError;
+
+/// Error returned by every command. Serializes as `{ code, message, path }` so the
+/// frontend can branch on the stable `code` instead of parsing the English `message`.
+#[derive(Debug, Clone)]
+pub enum IgneError {
+    NotFound { message: String, path: Option<String> },
+    PermissionDenied { message: String, path: Option<String> },
+    AlreadyExists { message: String, path: Option<String> },
+    NotADirectory { message: String, path: Option<String> },
+    Conflict { message: String, path: Option<String> },
+    InvalidPath { message: String, path: Option<String> },
+    InvalidInput { message: String, path: Option<String> },
+    /// Destructive operation on the vault root, app data dir, home dir or `.obsidian`
+    ProtectedPath { message: String, path: Option<String> },
+    /// Path falls outside registered vaults and granted paths
+    ScopeDenied { message: String, path: Option<String> },
+    /// File is bigger than the caller's read limit; also serializes `size` and `limit`
+    FileTooLarge { message: String, path: Option<String>, size: u64, limit: u64 },
+    /// Text read of a file whose content is binary (image, archive, ...)
+    BinaryFile { message: String, path: Option<String> },
+    /// Text read of a file that isn't valid UTF-8; also serializes the byte `offset` and
+    /// 1-based `line` of the first invalid sequence
+    InvalidUtf8 { message: String, path: Option<String>, offset: u64, line: u64 },
+    /// Image that is corrupt or in a format that can't be decoded
+    UnsupportedImage { message: String, path: Option<String> },
+    /// Operation this platform can't do, e.g. listing the system trash on macOS
+    Unsupported { message: String, path: Option<String> },
+    Io { message: String, path: Option<String> },
+}
+
+fn path_string(path: impl AsRef<Path>) -> Option<String> {
+    Some(path.as_ref().to_string_lossy().to_string())
+}
+
+impl IgneError {
+    /// Map an I/O error onto a code, keeping the OS message the frontend already shows
+    pub fn io(err: io::Error, path: impl AsRef<Path>) -> Self {
+        Self::from_io_kind(err.kind(), err.to_string(), path_string(path))
+    }
+
+    fn from_io_kind(kind: io::ErrorKind, message: String, path: Option<String>) -> Self {
+        match kind {
+            io::ErrorKind::NotFound => Self::NotFound { message, path },
+            io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem => {
+                Self::PermissionDenied { message, path }
+            }
+            io::ErrorKind::AlreadyExists => Self::AlreadyExists { message, path },
+            io::ErrorKind::NotADirectory => Self::NotADirectory { message, path },
+            io::ErrorKind::DirectoryNotEmpty | io::ErrorKind::ResourceBusy => {
+                Self::Conflict { message, path }
+            }
+            io::ErrorKind::InvalidInput | io::ErrorKind::InvalidFilename => {
+                Self::InvalidPath { message, path }
+            }
+            _ => Self::Io { message, path },
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>, path: impl AsRef<Path>) -> Self {
+        Self::NotFound { message: message.into(), path: path_string(path) }
+    }
+
+    pub fn already_exists(message: impl Into<String>, path: impl AsRef<Path>) -> Self {
+        Self::AlreadyExists { message: message.into(), path: path_string(path) }
+    }
+
+    pub fn not_a_directory(message: impl Into<String>, path: impl AsRef<Path>) -> Self {
+        Self::NotADirectory { message: message.into(), path: path_string(path) }
+    }
+
+    pub fn conflict(message: impl Into<String>, path: impl AsRef<Path>) -> Self {
+        Self::Conflict { message: message.into(), path: path_string(path) }
+    }
+
+    pub fn invalid_path(message: impl Into<String>, path: impl AsRef<Path>) -> Self {
+        Self::InvalidPath { message: message.into(), path: path_string(path) }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::InvalidInput { message: message.into(), path: None }
+    }
+
+    pub fn protected(message: impl Into<String>, path: impl AsRef<Path>) -> Self {
+        Self::ProtectedPath { message: message.into(), path: path_string(path) }
+    }
+
+    pub fn scope_denied(path: impl AsRef<Path>) -> Self {
+        Self::ScopeDenied {
+            message: format!("Permission denied by scope: {}", path.as_ref().display()),
+            path: path_string(path),
+        }
+    }
+
+    pub fn file_too_large(path: impl AsRef<Path>, size: u64, limit: u64) -> Self {
+        Self::FileTooLarge {
+            message: format!(
+                "File is too large to open ({} bytes, limit {} bytes): {}",
+                size,
+                limit,
+                path.as_ref().display()
+            ),
+            path: path_string(path),
+            size,
+            limit,
+        }
+    }
+
+    pub fn binary_file(mime: &str, path: impl AsRef<Path>) -> Self {
+        Self::BinaryFile {
+            message: format!("File is not text ({}): {}", mime, path.as_ref().display()),
+            path: path_string(path),
+        }
+    }
+
+    pub fn invalid_utf8(path: impl AsRef<Path>, offset: u64, line: u64) -> Self {
+        Self::InvalidUtf8 {
+            message: format!(
+                "File is not valid UTF-8 (invalid byte at offset {}, line {}): {}",
+                offset,
+                line,
+                path.as_ref().display()
+            ),
+            path: path_string(path),
+            offset,
+            line,
+        }
+    }
+
+    pub fn unsupported_image(message: impl Into<String>, path: impl AsRef<Path>) -> Self {
+        Self::UnsupportedImage { message: message.into(), path: path_string(path) }
+    }
+
+    pub fn unsupported(message: impl Into<String>) -> Self {
+        Self::Unsupported { message: message.into(), path: None }
+    }
+
+    pub fn other(message: impl Into<String>) -> Self {
+        Self::Io { message: message.into(), path: None }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotFound { .. } => "NOT_FOUND",
+            Self::PermissionDenied { .. } => "PERMISSION_DENIED",
+            Self::AlreadyExists { .. } => "ALREADY_EXISTS",
+            Self::NotADirectory { .. } => "NOT_A_DIRECTORY",
+            Self::Conflict { .. } => "CONFLICT",
+            Self::InvalidPath { .. } => "INVALID_PATH",
+            Self::InvalidInput { .. } => "INVALID_INPUT",
+            Self::ProtectedPath { .. } => "PROTECTED_PATH",
+            Self::ScopeDenied { .. } => "SCOPE_DENIED",
+            Self::FileTooLarge { .. } => "FILE_TOO_LARGE",
+            Self::BinaryFile { .. } => "BINARY_FILE",
+            Self::InvalidUtf8 { .. } => "INVALID_UTF8",
+            Self::UnsupportedImage { .. } => "UNSUPPORTED_IMAGE",
+            Self::Unsupported { .. } => "UNSUPPORTED",
+            Self::Io { .. } => "IO",
+        }
+    }
+
+    fn parts(&self) -> (&str, Option<&str>) {
+        match self {
+            Self::NotFound { message, path }
+            | Self::PermissionDenied { message, path }
+            | Self::AlreadyExists { message, path }
+            | Self::NotADirectory { message, path }
+            | Self::Conflict { message, path }
+            | Self::InvalidPath { message, path }
+            | Self::InvalidInput { message, path }
+            | Self::ProtectedPath { message, path }
+            | Self::ScopeDenied { message, path }
+            | Self::FileTooLarge { message, path, .. }
+            | Self::BinaryFile { message, path }
+            | Self::InvalidUtf8 { message, path, .. }
+            | Self::UnsupportedImage { message, path }
+            | Self::Unsupported { message, path }
+            | Self::Io { message, path } => (message, path.as_deref()),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        self.parts().0
+    }
+
+    pub fn path(&self) -> Option<&str> {
+        self.parts().1
+    }
+}
+
+impl fmt::Display for IgneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for IgneError {}
+
+impl Serialize for IgneError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let extra: &[(&'static str, u64)] = match self {
+            Self::FileTooLarge { size, limit, .. } => &[("size", *size), ("limit", *limit)],
+            Self::InvalidUtf8 { offset, line, .. } => &[("offset", *offset), ("line", *line)],
+            _ => &[],
+        };
+        let mut state = serializer.serialize_struct("IgneError", 3 + extra.len())?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        state.serialize_field("path", &self.path())?;
+        for (name, value) in extra {
+            state.serialize_field(name, value)?;
+        }
+        state.end()
+    }
+}
+
+impl From<io::Error> for IgneError {
+    fn from(err: io::Error) -> Self {
+        Self::from_io_kind(err.kind(), err.to_string(), None)
+    }
+}
+
+impl<T> From<PoisonError<T>> for IgneError {
+    fn from(err: PoisonError<T>) -> Self {
+        Self::other(err.to_string())
+    }
+}
+
+impl From<notify::Error> for IgneError {
+    fn from(err: notify::Error) -> Self {
+        let message = err.to_string();
+        match err.kind {
+            notify::ErrorKind::Io(io_err) => io_err.into(),
+            notify::ErrorKind::PathNotFound => Self::NotFound {
+                message,
+                path: err.paths.first().and_then(path_string),
+            },
+            _ => Self::other(message),
+        }
+    }
+}
+
+impl From<serde_json::Error> for IgneError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::other(err.to_string())
+    }
+}
+
+impl From<git2::Error> for IgneError {
+    fn from(err: git2::Error) -> Self {
+        match err.code() {
+            git2::ErrorCode::NotFound => Self::NotFound { message: err.message().to_string(), path: None },
+            git2::ErrorCode::Exists => Self::AlreadyExists { message: err.message().to_string(), path: None },
+            git2::ErrorCode::Locked | git2::ErrorCode::Conflict => Self::Conflict { message: err.message().to_string(), path: None },
+            _ => Self::other(err.message()),
+        }
+    }
+}
+
+impl From<trash::Error> for IgneError {
+    fn from(err: trash::Error) -> Self {
+        match err {
+            trash::Error::RestoreCollision { path, .. } => {
+                Self::already_exists(format!("Something already exists at {}", path.display()), &path)
+            }
+            #[cfg(all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android")))]
+            trash::Error::FileSystem { path, source } => Self::io(source, &path),
+            _ => Self::other(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn serializes_code_message_and_path() {
+        let err = IgneError::not_found("No such note", "/vault/a.md");
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({ "code": "NOT_FOUND", "message": "No such note", "path": "/vault/a.md" })
+        );
+        assert_eq!(
+            serde_json::to_value(IgneError::invalid_input("Bad level")).unwrap(),
+            json!({ "code": "INVALID_INPUT", "message": "Bad level", "path": null })
+        );
+    }
+
+    #[test]
+    fn serializes_extra_fields() {
+        let value = serde_json::to_value(IgneError::file_too_large("/vault/big.md", 20, 10)).unwrap();
+        assert_eq!(value["code"], "FILE_TOO_LARGE");
+        assert_eq!((value["size"].as_u64(), value["limit"].as_u64()), (Some(20), Some(10)));
+
+        let value = serde_json::to_value(IgneError::invalid_utf8("/vault/a.md", 7, 2)).unwrap();
+        assert_eq!(value["code"], "INVALID_UTF8");
+        assert_eq!((value["offset"].as_u64(), value["line"].as_u64()), (Some(7), Some(2)));
+    }
+
+    #[test]
+    fn maps_io_kinds_to_codes() {
+        let cases = [
+            (io::ErrorKind::NotFound, "NOT_FOUND"),
+            (io::ErrorKind::PermissionDenied, "PERMISSION_DENIED"),
+            (io::ErrorKind::AlreadyExists, "ALREADY_EXISTS"),
+            (io::ErrorKind::DirectoryNotEmpty, "CONFLICT"),
+            (io::ErrorKind::InvalidInput, "INVALID_PATH"),
+            (io::ErrorKind::Other, "IO"),
+        ];
+        for (kind, code) in cases {
+            let err = IgneError::io(io::Error::new(kind, "os message"), "/vault/a.md");
+            assert_eq!(err.code(), code, "{:?}", kind);
+            assert_eq!(err.message(), "os message");
+            assert_eq!(err.path(), Some("/vault/a.md"));
+        }
+    }
+
+    #[test]
+    fn conversions_keep_the_message() {
+        let err: IgneError = io::Error::new(io::ErrorKind::NotFound, "gone").into();
+        assert_eq!((err.code(), err.message(), err.path()), ("NOT_FOUND", "gone", None));
+
+        let err: IgneError = serde_json::from_str::<Value>("{").unwrap_err().into();
+        assert_eq!(err.code(), "IO");
+        assert_eq!(err.to_string(), err.message());
+    }
+}
@@ -0,0 +1,235 @@
+use serde::Serialize;
+#[cfg(any(target_os = "linux", windows))]
+use std::process::Command;
+
+use crate::error::IgneError;
+
+/// Same extensions as `bundle.fileAssociations` in tauri.conf.json
+const EXTENSIONS: [&str; 3] = ["md", "markdown", "mdx"];
+
+#[derive(Serialize, Clone)]
+pub struct FileAssociationStatus {
+    pub extension: String,
+    /// Igne is offered as a handler ("Open with")
+    pub registered: bool,
+    /// Igne opens the file on double-click; `None` where it can't be determined
+    pub is_default: Option<bool>,
+}
+
+/// The launcher to put in associations: the AppImage itself when running from one,
+/// since `current_exe` points into its temporary mount
+#[cfg(any(target_os = "linux", windows))]
+fn launcher_path() -> Result<String, IgneError> {
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Ok(appimage.to_string_lossy().to_string());
+    }
+    std::env::current_exe()
+        .map(|exe| exe.to_string_lossy().to_string())
+        .map_err(|e| IgneError::other(format!("Failed to locate the Igne executable: {}", e)))
+}
+
+#[cfg(any(target_os = "linux", windows))]
+fn run(program: &str, args: &[&str]) -> Result<String, IgneError> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| IgneError::other(format!("Failed to run {}: {}", program, e)))?;
+    if !output.status.success() {
+        return Err(IgneError::other(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::{launcher_path, run, FileAssociationStatus, EXTENSIONS};
+    use crate::error::IgneError;
+
+    const DESKTOP_FILE: &str = "igne.desktop";
+    const MIME_TYPE: &str = "text/markdown";
+    /// Maps `.mdx` (unknown to most shared-mime-info versions) onto text/markdown
+    const MIME_PACKAGE: &str = "igne-markdown.xml";
+
+    fn data_home() -> Result<PathBuf, IgneError> {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+            .ok_or_else(|| IgneError::other("Could not determine the XDG data directory"))
+    }
+
+    fn desktop_entry(exec: &str) -> String {
+        format!(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name=Igne\n\
+             Comment=Markdown notes\n\
+             Exec=\"{}\" %f\n\
+             Icon=igne\n\
+             Terminal=false\n\
+             Categories=Office;TextEditor;\n\
+             MimeType={};\n",
+            exec.replace('"', "\\\""),
+            MIME_TYPE
+        )
+    }
+
+    fn mime_package() -> String {
+        let globs: String = EXTENSIONS
+            .iter()
+            .map(|ext| format!("    <glob pattern=\"*.{}\"/>\n", ext))
+            .collect();
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <mime-info xmlns=\"http://www.freedesktop.org/standards/shared-mime-info\">\n\
+             \x20 <mime-type type=\"{}\">\n{}\x20 </mime-type>\n\
+             </mime-info>\n",
+            MIME_TYPE, globs
+        )
+    }
+
+    pub fn register() -> Result<(), IgneError> {
+        let data_home = data_home()?;
+        let applications = data_home.join("applications");
+        let packages = data_home.join("mime").join("packages");
+        fs::create_dir_all(&applications).map_err(|e| IgneError::io(e, &applications))?;
+        fs::create_dir_all(&packages).map_err(|e| IgneError::io(e, &packages))?;
+
+        let desktop = applications.join(DESKTOP_FILE);
+        fs::write(&desktop, desktop_entry(&launcher_path()?)).map_err(|e| IgneError::io(e, &desktop))?;
+        let package = packages.join(MIME_PACKAGE);
+        fs::write(&package, mime_package()).map_err(|e| IgneError::io(e, &package))?;
+
+        // The caches speed up lookups but aren't required, and the tools may be missing
+        let _ = run("update-mime-database", &[&data_home.join("mime").to_string_lossy()]);
+        let _ = run("update-desktop-database", &[&applications.to_string_lossy()]);
+        run("xdg-mime", &["default", DESKTOP_FILE, MIME_TYPE])?;
+        Ok(())
+    }
+
+    pub fn status() -> Result<Vec<FileAssociationStatus>, IgneError> {
+        let registered = data_home()?.join("applications").join(DESKTOP_FILE).is_file();
+        let is_default = run("xdg-mime", &["query", "default", MIME_TYPE])
+            .ok()
+            .map(|handler| handler == DESKTOP_FILE);
+        Ok(EXTENSIONS
+            .iter()
+            .map(|ext| FileAssociationStatus {
+                extension: ext.to_string(),
+                registered,
+                is_default,
+            })
+            .collect())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{launcher_path, run, FileAssociationStatus, EXTENSIONS};
+    use crate::error::IgneError;
+
+    const PROG_ID: &str = "Igne.Markdown";
+    const CLASSES: &str = r"HKCU\Software\Classes";
+
+    fn reg_add(key: &str, value: Option<&str>, kind: &str, data: &str) -> Result<(), IgneError> {
+        let mut args = vec!["add", key];
+        match value {
+            Some(value) => args.extend(["/v", value]),
+            None => args.push("/ve"),
+        }
+        args.extend(["/t", kind, "/d", data, "/f"]);
+        run("reg", &args).map(|_| ())
+    }
+
+    /// Registers Igne under the current user as a ProgID and in each extension's "Open
+    /// with" list. Windows doesn't let apps take over the default handler; the user
+    /// picks Igne once in the prompt Explorer shows on the next double-click.
+    pub fn register() -> Result<(), IgneError> {
+        let exe = launcher_path()?;
+        let prog_key = format!(r"{}\{}", CLASSES, PROG_ID);
+        reg_add(&prog_key, None, "REG_SZ", "Markdown document")?;
+        reg_add(&format!(r"{}\DefaultIcon", prog_key), None, "REG_SZ", &format!("\"{}\",0", exe))?;
+        reg_add(
+            &format!(r"{}\shell\open\command", prog_key),
+            None,
+            "REG_SZ",
+            &format!("\"{}\" \"%1\"", exe),
+        )?;
+
+        for ext in EXTENSIONS {
+            let ext_key = format!(r"{}\.{}\OpenWithProgids", CLASSES, ext);
+            reg_add(&ext_key, Some(PROG_ID), "REG_NONE", "")?;
+        }
+        Ok(())
+    }
+
+    pub fn status() -> Result<Vec<FileAssociationStatus>, IgneError> {
+        Ok(EXTENSIONS
+            .iter()
+            .map(|ext| {
+                let registered = run(
+                    "reg",
+                    &["query", &format!(r"{}\.{}\OpenWithProgids", CLASSES, ext), "/v", PROG_ID],
+                )
+                .is_ok();
+                let user_choice = format!(
+                    r"HKCU\Software\Microsoft\Windows\CurrentVersion\Explorer\FileExts\.{}\UserChoice",
+                    ext
+                );
+                let is_default = match run("reg", &["query", &user_choice, "/v", "ProgId"]) {
+                    Ok(output) => Some(output.contains(PROG_ID)),
+                    // No choice recorded yet: Windows asks on the next double-click
+                    Err(_) => Some(false),
+                };
+                FileAssociationStatus {
+                    extension: ext.to_string(),
+                    registered,
+                    is_default,
+                }
+            })
+            .collect())
+    }
+}
+
+/// The app bundle's Info.plist declares the document types; Launch Services picks them
+/// up when the app is installed
+#[cfg(not(any(target_os = "linux", windows)))]
+mod platform {
+    use super::{FileAssociationStatus, EXTENSIONS};
+    use crate::error::IgneError;
+
+    pub fn register() -> Result<(), IgneError> {
+        Ok(())
+    }
+
+    pub fn status() -> Result<Vec<FileAssociationStatus>, IgneError> {
+        Ok(EXTENSIONS
+            .iter()
+            .map(|ext| FileAssociationStatus {
+                extension: ext.to_string(),
+                registered: true,
+                is_default: None,
+            })
+            .collect())
+    }
+}
+
+/// Register Igne as a handler for markdown files for the current user, so double-clicked
+/// files reach `open-standalone-file`. A no-op on macOS, where the bundle does this.
+#[tauri::command]
+pub fn register_file_associations() -> Result<(), IgneError> {
+    platform::register()
+}
+
+/// Whether Igne is registered for, and the default handler of, each markdown extension
+#[tauri::command]
+pub fn check_file_association() -> Result<Vec<FileAssociationStatus>, IgneError> {
+    platform::status()
+}
@@ -0,0 +1,63 @@
+use serde::Serialize;
+use std::io;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::error::IgneError;
+use crate::scope::ensure_in_scope;
+
+/// Identity of a file that survives renames and moves within the same filesystem
+#[derive(Serialize, Clone)]
+pub struct InodeInfo {
+    /// Inode number, or the NTFS file index on Windows
+    pub inode: u64,
+    /// Device id, or the volume serial number on Windows
+    pub device: u64,
+    pub path: String,
+}
+
+#[cfg(unix)]
+fn identity(path: &Path) -> io::Result<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::metadata(path)?;
+    Ok((metadata.ino(), metadata.dev()))
+}
+
+#[cfg(windows)]
+fn identity(path: &Path) -> io::Result<(u64, u64)> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION, FILE_FLAG_BACKUP_SEMANTICS,
+    };
+
+    // Backup semantics lets folders be opened too; no access rights are needed to query
+    let file = std::fs::OpenOptions::new()
+        .access_mode(0)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)?;
+    let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+    if unsafe { GetFileInformationByHandle(file.as_raw_handle(), &mut info) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let index = ((info.nFileIndexHigh as u64) << 32) | info.nFileIndexLow as u64;
+    Ok((index, info.dwVolumeSerialNumber as u64))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn identity(_path: &Path) -> io::Result<(u64, u64)> {
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+}
+
+/// The `(device, inode)` pair of a file or folder, so open tabs can follow a note that
+/// was renamed or moved outside the app. Symlinks are followed.
+#[tauri::command]
+pub fn get_inode(path: String, app: AppHandle) -> Result<InodeInfo, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+
+    let (inode, device) = identity(&path_obj).map_err(|e| IgneError::io(e, &path))?;
+    Ok(InodeInfo { inode, device, path })
+}
@@ -0,0 +1,182 @@
+use serde::Serialize;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::error::IgneError;
+use crate::is_markdown_file;
+use crate::paths::long_path;
+use crate::scope::ensure_in_scope;
+
+/// Bytes sniffed from the start of a file
+const SAMPLE_SIZE: u64 = 8 * 1024;
+
+/// Magic numbers of the binary formats the viewer knows, with their MIME type
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"PK\x05\x06", "application/zip"),
+];
+
+#[derive(Serialize, Clone, Default)]
+pub struct FileTypeInfo {
+    pub mime: String,
+    pub is_text: bool,
+    pub is_binary: bool,
+    /// "lf", "crlf" or "mixed" for text with line breaks
+    pub line_ending: Option<String>,
+    /// "ascii", "utf-8", "utf-8-bom", "utf-16le", "utf-16be" or "windows-1252" for text
+    pub encoding_guess: Option<String>,
+}
+
+fn read_sample(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut sample = Vec::with_capacity(SAMPLE_SIZE as usize);
+    File::open(path)?.take(SAMPLE_SIZE).read_to_end(&mut sample)?;
+    Ok(sample)
+}
+
+fn magic_mime(sample: &[u8]) -> Option<&'static str> {
+    if sample.len() >= 12 && sample.starts_with(b"RIFF") && &sample[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    SIGNATURES
+        .iter()
+        .find(|(magic, _)| sample.starts_with(magic))
+        .map(|(_, mime)| *mime)
+}
+
+/// Valid UTF-8, allowing the sample to end partway through a character
+fn is_utf8(sample: &[u8]) -> bool {
+    match std::str::from_utf8(sample) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+/// Bytes windows-1252 leaves undefined, so text that isn't UTF-8 can't contain them
+const CP1252_UNDEFINED: &[u8] = &[0x81, 0x8d, 0x8f, 0x90, 0x9d];
+
+fn guess_encoding(sample: &[u8]) -> Option<&'static str> {
+    if sample.starts_with(b"\xef\xbb\xbf") {
+        return Some("utf-8-bom");
+    }
+    if sample.starts_with(b"\xff\xfe") {
+        return Some("utf-16le");
+    }
+    if sample.starts_with(b"\xfe\xff") {
+        return Some("utf-16be");
+    }
+    // Text never contains NUL. Other control bytes do, e.g. ANSI colours in logs, so
+    // they don't make a file binary.
+    if sample.contains(&0) {
+        return None;
+    }
+    if sample.is_ascii() {
+        Some("ascii")
+    } else if is_utf8(sample) {
+        Some("utf-8")
+    } else if sample.iter().any(|b| CP1252_UNDEFINED.contains(b)) {
+        None
+    } else {
+        Some("windows-1252")
+    }
+}
+
+fn line_ending(sample: &[u8]) -> Option<&'static str> {
+    let crlf = sample.windows(2).filter(|w| w == b"\r\n").count();
+    let lf = sample.iter().filter(|b| **b == b'\n').count() - crlf;
+    match (crlf > 0, lf > 0) {
+        (true, true) => Some("mixed"),
+        (true, false) => Some("crlf"),
+        (false, true) => Some("lf"),
+        (false, false) => None,
+    }
+}
+
+/// SVG is XML text, recognised by an `<svg` root near the start
+fn is_svg(sample: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(sample).to_lowercase();
+    let trimmed = head.trim_start_matches('\u{feff}').trim_start();
+    (trimmed.starts_with("<?xml") || trimmed.starts_with("<!--") || trimmed.starts_with("<svg")
+        || trimmed.starts_with("<!doctype svg"))
+        && head.contains("<svg")
+}
+
+fn text_mime(path: &Path) -> &'static str {
+    if is_markdown_file(&path.to_string_lossy()) {
+        "text/markdown"
+    } else {
+        "text/plain"
+    }
+}
+
+pub fn detect(path: &Path) -> std::io::Result<FileTypeInfo> {
+    let sample = read_sample(path)?;
+
+    if let Some(mime) = magic_mime(&sample) {
+        return Ok(FileTypeInfo {
+            mime: mime.to_string(),
+            is_binary: true,
+            ..Default::default()
+        });
+    }
+
+    match guess_encoding(&sample) {
+        Some(encoding) => {
+            let mime = if is_svg(&sample) { "image/svg+xml" } else { text_mime(path) };
+            // Line breaks in UTF-16 text are two bytes wide and not worth decoding here
+            let line_ending = if encoding.starts_with("utf-16") { None } else { line_ending(&sample) };
+            Ok(FileTypeInfo {
+                mime: mime.to_string(),
+                is_text: true,
+                is_binary: false,
+                line_ending: line_ending.map(str::to_string),
+                encoding_guess: Some(encoding.to_string()),
+            })
+        }
+        None => Ok(FileTypeInfo {
+            mime: "application/octet-stream".to_string(),
+            is_binary: true,
+            ..Default::default()
+        }),
+    }
+}
+
+/// What a file really is, from its first 8 KB rather than its extension: magic bytes for
+/// common images, PDFs and zips, and an encoding check for text. Only a signature, a NUL
+/// byte or bytes that decode as neither UTF-8 nor windows-1252 make a file binary.
+#[tauri::command]
+pub fn detect_file_type(path: String, app: AppHandle) -> Result<FileTypeInfo, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+    detect(&long_path(&path_obj)).map_err(|e| IgneError::io(e, &path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ansi_coloured_log_is_text() {
+        let log = "\x1b[32mINFO\x1b[0m started\n\x1b[31mERROR\x1b[0m failed\n".repeat(20);
+        assert_eq!(guess_encoding(log.as_bytes()), Some("ascii"));
+        let utf8 = "\x1b[33m⚠ warnung\x1b[0m\n".repeat(20);
+        assert_eq!(guess_encoding(utf8.as_bytes()), Some("utf-8"));
+    }
+
+    #[test]
+    fn nul_bytes_are_binary() {
+        assert_eq!(guess_encoding(b"text\0more"), None);
+    }
+
+    #[test]
+    fn undecodable_bytes_are_binary() {
+        assert_eq!(guess_encoding(b"caf\xe9 cr\xe8me"), Some("windows-1252"));
+        assert_eq!(guess_encoding(b"\xc3\x28\x81\x9d"), None);
+    }
+}
@@ -0,0 +1,77 @@
+use chrono::Local;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::atomic_write;
+use crate::error::IgneError;
+use crate::paths::{long_path, validate_new_path};
+use crate::scope::ensure_in_scope;
+use crate::write_lock::with_write_lock;
+
+/// Fill in the same `{{...}}` variables as the frontend's `applyTemplate`
+fn apply_template(template: &str, title: &str) -> String {
+    let now = Local::now();
+    [
+        ("title", title.to_string()),
+        ("date", now.format("%Y-%m-%d").to_string()),
+        ("time", now.format("%H:%M").to_string()),
+        ("datetime", now.format("%Y-%m-%d %H:%M").to_string()),
+        ("year", now.format("%Y").to_string()),
+        ("month", now.format("%m").to_string()),
+        ("day", now.format("%d").to_string()),
+    ]
+    .iter()
+    .fold(template.to_string(), |text, (key, value)| {
+        text.replace(&format!("{{{{{}}}}}", key), value)
+    })
+}
+
+/// Create `<folder>/<folder name>.md`, optionally from a template, and return its path.
+/// An existing folder note is left alone and its path returned.
+#[tauri::command]
+pub async fn create_folder_note(
+    folder_path: String,
+    template_path: Option<String>,
+    app: AppHandle,
+) -> Result<String, IgneError> {
+    let folder = PathBuf::from(&folder_path);
+    ensure_in_scope(&app, &folder)?;
+    if !long_path(&folder).is_dir() {
+        return Err(IgneError::not_a_directory(
+            format!("Not a folder: {}", folder_path),
+            &folder,
+        ));
+    }
+
+    let name = folder
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| IgneError::invalid_path(format!("Folder has no name: {}", folder_path), &folder))?;
+    let note = folder.join(format!("{}.md", name));
+    if long_path(&note).exists() {
+        return Ok(note.to_string_lossy().to_string());
+    }
+    let note = validate_new_path(&note)?;
+
+    let content = match template_path {
+        Some(template_path) => {
+            let template = PathBuf::from(&template_path);
+            ensure_in_scope(&app, &template)?;
+            let template = fs::read_to_string(&template).map_err(|e| IgneError::io(e, &template))?;
+            apply_template(&template, &name)
+        }
+        None => String::new(),
+    };
+
+    with_write_lock(&app, &note, || {
+        // Another window may have created it while we were waiting for the lock
+        if !long_path(&note).exists() {
+            atomic_write(&note, content.as_bytes())?;
+        }
+        Ok(())
+    })
+    .await?;
+
+    Ok(note.to_string_lossy().to_string())
+}
@@ -0,0 +1,264 @@
+use pulldown_cmark::{Event, Parser, Tag};
+use serde::Deserialize;
+
+use crate::error::IgneError;
+use crate::markdown;
+use crate::markdown_ast::parser_options;
+
+#[derive(Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// Keep each line's own ending
+    #[default]
+    Preserve,
+    Lf,
+    Crlf,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FormatOptions {
+    trim_trailing_whitespace: bool,
+    normalize_line_endings: LineEnding,
+    ensure_final_newline: bool,
+    /// Longest run of blank lines kept; 0 leaves runs alone
+    max_blank_lines: u8,
+    reformat_front_matter: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            trim_trailing_whitespace: true,
+            normalize_line_endings: LineEnding::Preserve,
+            ensure_final_newline: true,
+            max_blank_lines: 1,
+            reformat_front_matter: false,
+        }
+    }
+}
+
+/// Byte ranges of code and HTML blocks, whose whitespace can be meaningful
+fn verbatim_ranges(content: &str) -> Vec<(usize, usize)> {
+    Parser::new_ext(content, parser_options())
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::Start(Tag::CodeBlock(_)) | Event::Start(Tag::HtmlBlock) => Some((range.start, range.end)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Tidy one frontmatter line: `key:value` → `key: value`, `-   item` → `- item`
+fn reformat_front_matter_line(line: &str) -> String {
+    let line = line.trim_end();
+    if let Some(item) = line.trim_start().strip_prefix('-') {
+        let indent = &line[..line.len() - line.trim_start().len()];
+        if !item.is_empty() && item.starts_with(char::is_whitespace) {
+            return format!("{}- {}", indent, item.trim_start());
+        }
+        return line.to_string();
+    }
+    match line.split_once(':') {
+        Some((key, value))
+            if !key.is_empty()
+                && key.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-'))
+                && !value.starts_with("//") =>
+        {
+            let value = value.trim_start();
+            if value.is_empty() {
+                format!("{}:", key)
+            } else {
+                format!("{}: {}", key, value)
+            }
+        }
+        _ => line.to_string(),
+    }
+}
+
+/// `key: |`, `key: >-` and the like, whose indented lines that follow are literal text
+fn is_block_scalar_start(line: &str) -> bool {
+    line.split_once(':').is_some_and(|(_, value)| {
+        let value = value.trim();
+        value.starts_with('|') || value.starts_with('>')
+    })
+}
+
+/// A line split from its ending ("\n", "\r\n" or "" for the last line)
+fn split_ending(line: &str) -> (&str, &str) {
+    if let Some(text) = line.strip_suffix("\r\n") {
+        (text, "\r\n")
+    } else if let Some(text) = line.strip_suffix('\n') {
+        (text, "\n")
+    } else {
+        (line, "")
+    }
+}
+
+/// Trailing whitespace of a line that isn't a hard line break (two or more spaces before
+/// a following text line), which is normalized to exactly two spaces instead
+fn trim_trailing(text: &str, next_is_text: bool) -> String {
+    let trimmed = text.trim_end();
+    let trailing = &text[trimmed.len()..];
+    if next_is_text && !trimmed.is_empty() && trailing.len() >= 2 && trailing.bytes().all(|b| b == b' ') {
+        format!("{}  ", trimmed)
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn format(content: &str, options: &FormatOptions) -> String {
+    let verbatim = verbatim_ranges(content);
+    let front_matter_end = markdown::frontmatter_range(content).map_or(0, |(_, end)| end);
+    let lines: Vec<(usize, &str)> = content
+        .split_inclusive('\n')
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            Some((start, line))
+        })
+        .collect();
+
+    let mut out = String::with_capacity(content.len());
+    let mut blank_run = 0usize;
+    let mut last_ending = "\n";
+    // Inside a YAML `key: |` / `key: >` block, where indentation and blank lines are content
+    let mut in_block_scalar = false;
+
+    for (i, &(start, line)) in lines.iter().enumerate() {
+        let (text, ending) = split_ending(line);
+        let ending = match (options.normalize_line_endings, ending) {
+            (_, "") => "",
+            (LineEnding::Lf, _) => "\n",
+            (LineEnding::Crlf, _) => "\r\n",
+            (LineEnding::Preserve, ending) => ending,
+        };
+        if !ending.is_empty() {
+            last_ending = ending;
+        }
+
+        // Overlap rather than containment: an indented code block's range starts after
+        // the indentation
+        let in_verbatim = verbatim.iter().any(|&(s, e)| start < e && start + text.len() > s);
+        let in_front_matter = start < front_matter_end;
+        if in_front_matter && !text.is_empty() && !text.starts_with(char::is_whitespace) {
+            in_block_scalar = is_block_scalar_start(text);
+        }
+        // Delimiters and YAML block scalars are left alone apart from trailing spaces
+        let reformat = options.reformat_front_matter
+            && in_front_matter
+            && !(start == 0 || start + line.len() == front_matter_end)
+            && !(in_block_scalar && (text.is_empty() || text.starts_with(char::is_whitespace)));
+        let text = if in_verbatim {
+            text.to_string()
+        } else if in_front_matter {
+            if reformat {
+                reformat_front_matter_line(text)
+            } else if options.trim_trailing_whitespace {
+                text.trim_end().to_string()
+            } else {
+                text.to_string()
+            }
+        } else if options.trim_trailing_whitespace {
+            let next_is_text = lines
+                .get(i + 1)
+                .is_some_and(|&(_, next)| !next.trim().is_empty());
+            trim_trailing(text, next_is_text)
+        } else {
+            text.to_string()
+        };
+
+        let is_blank = !in_verbatim && !in_front_matter && text.trim().is_empty();
+        if is_blank {
+            blank_run += 1;
+            if options.max_blank_lines > 0 && blank_run > options.max_blank_lines as usize {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        // Blank lines dropped from the front matter tidy-up
+        if reformat && text.trim().is_empty() {
+            continue;
+        }
+
+        out.push_str(&text);
+        out.push_str(ending);
+    }
+
+    if options.ensure_final_newline && !out.is_empty() && !out.ends_with('\n') {
+        let ending = match options.normalize_line_endings {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+            LineEnding::Preserve => last_ending,
+        };
+        out.push_str(ending);
+    }
+    out
+}
+
+/// Normalize a note's whitespace and line endings the same way on every platform. Code
+/// blocks and HTML blocks are copied as-is; everything is optional via `options`.
+#[tauri::command]
+pub fn format_markdown(content: String, options: Option<FormatOptions>) -> Result<String, IgneError> {
+    Ok(format(&content, &options.unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(json: &str) -> FormatOptions {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn formats_and_round_trips() {
+        let fm = r#"{"reformatFrontMatter": true}"#;
+        let cases = [
+            // Trailing whitespace and hard line breaks
+            ("{}", "a \nb\n", "a\nb\n"),
+            ("{}", "a  \nb\n", "a  \nb\n"),
+            ("{}", "a    \nb\n", "a  \nb\n"),
+            ("{}", "a  \n\nb\n", "a\n\nb\n"),
+            ("{}", "a\t \nb\n", "a\nb\n"),
+            (r#"{"trimTrailingWhitespace": false}"#, "a \nb\n", "a \nb\n"),
+            // Blank line runs
+            ("{}", "a\n\n\n\nb\n", "a\n\nb\n"),
+            (r#"{"maxBlankLines": 2}"#, "a\n\n\n\nb\n", "a\n\n\nb\n"),
+            (r#"{"maxBlankLines": 0}"#, "a\n\n\n\nb\n", "a\n\n\n\nb\n"),
+            // Final newline
+            ("{}", "", ""),
+            ("{}", "a", "a\n"),
+            ("{}", "a\r\nb", "a\r\nb\r\n"),
+            (r#"{"ensureFinalNewline": false}"#, "a", "a"),
+            // Line endings
+            ("{}", "a\r\nb\nc\r\n", "a\r\nb\nc\r\n"),
+            (r#"{"normalizeLineEndings": "lf"}"#, "a\r\nb\r\n", "a\nb\n"),
+            (r#"{"normalizeLineEndings": "crlf"}"#, "a\nb", "a\r\nb\r\n"),
+            // Code and HTML blocks are copied as-is
+            ("{}", "```\ncode   \n\n\n\n```\n", "```\ncode   \n\n\n\n```\n"),
+            ("{}", "text\n\n    code  \n", "text\n\n    code  \n"),
+            ("{}", "<div>\n  x  \n</div>\n", "<div>\n  x  \n</div>\n"),
+            // Front matter
+            ("{}", "---\ntitle:Note  \n---\nbody\n", "---\ntitle:Note\n---\nbody\n"),
+            (
+                fm,
+                "---\ntitle:Note\ntags:\n  -   a\n\nnote:   x  \n---\nbody\n",
+                "---\ntitle: Note\ntags:\n  - a\nnote: x\n---\nbody\n",
+            ),
+            (
+                fm,
+                "---\ndesc: |\n  line one  \n\n    two\nk:v\n---\n",
+                "---\ndesc: |\n  line one\n\n    two\nk: v\n---\n",
+            ),
+            (fm, "---\nhttps://example.com\n---\n", "---\nhttps://example.com\n---\n"),
+        ];
+        for (json, input, expected) in cases {
+            let options = options(json);
+            let formatted = format(input, &options);
+            assert_eq!(formatted, expected, "{} {:?}", json, input);
+            assert_eq!(format(&formatted, &options), formatted, "second pass: {} {:?}", json, input);
+        }
+    }
+}
@@ -0,0 +1,413 @@
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::error::IgneError;
+use crate::paths::long_path;
+
+/// How a move was carried out
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MoveStrategy {
+    Rename,
+    /// Source and destination are on different filesystems, so the source was copied
+    /// and then removed
+    Copy,
+}
+
+/// Move a file or folder, falling back to copy-then-delete when `fs::rename` can't cross
+/// filesystems (external drives, mounted sync folders). The source is only removed once the
+/// copy has fully succeeded; a partial copy is cleaned up.
+///
+/// An existing destination is an `AlreadyExists` error unless `overwrite` is set. A case-only
+/// rename (`note.md` → `Note.md`) on a case-insensitive filesystem is not a collision.
+pub fn move_path(source: &Path, destination: &Path, overwrite: bool) -> Result<MoveStrategy, IgneError> {
+    let (source, destination) = (&long_path(source), &long_path(destination));
+
+    let renamed = if overwrite || is_same_file(source, destination) {
+        fs::rename(source, destination)
+    } else {
+        rename_no_replace(source, destination)
+    };
+
+    match renamed {
+        Ok(()) => return Ok(MoveStrategy::Rename),
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {}
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+            return Err(destination_exists(destination));
+        }
+        Err(e) => return Err(IgneError::io(e, source)),
+    }
+
+    copy_then_remove(source, destination, overwrite)?;
+    Ok(MoveStrategy::Copy)
+}
+
+/// The cross-filesystem half of [`move_path`]. The destination is created by this call
+/// (`create_new`, `create_dir`), so one that appears meanwhile is never written into, and a
+/// failed copy only cleans up what this call created.
+fn copy_then_remove(source: &Path, destination: &Path, overwrite: bool) -> Result<(), IgneError> {
+    let is_dir = fs::symlink_metadata(source)
+        .map_err(|e| IgneError::io(e, source))?
+        .is_dir();
+
+    // Copying can only replace a plain file; merging into an existing folder is never intended
+    let copied = if is_dir {
+        copy_dir_recursive(source, destination)
+    } else if overwrite && !destination.is_dir() {
+        copy_file_replacing(source, destination)
+    } else {
+        copy_file_new(source, destination)
+    };
+    match copied {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Err(destination_exists(destination)),
+        Err(e) => return Err(IgneError::io(e, destination)),
+    }
+
+    let removed = if is_dir {
+        fs::remove_dir_all(source)
+    } else {
+        fs::remove_file(source)
+    };
+    removed.map_err(|e| IgneError::io(e, source))
+}
+
+fn destination_exists(destination: &Path) -> IgneError {
+    IgneError::already_exists(
+        format!("Destination already exists: {}", destination.display()),
+        destination,
+    )
+}
+
+/// Whether both paths name the same file, e.g. differently-cased names on a
+/// case-insensitive filesystem
+#[cfg(unix)]
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (fs::symlink_metadata(a), fs::symlink_metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Fallback when the filesystem has no atomic no-replace rename. Leaves a small
+/// check-then-rename window, which is the best those filesystems allow.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn rename_checked(source: &Path, destination: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(destination).is_ok() {
+        return Err(io::Error::from(io::ErrorKind::AlreadyExists));
+    }
+    fs::rename(source, destination)
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn c_path(path: &Path) -> io::Result<std::ffi::CString> {
+    use std::os::unix::ffi::OsStrExt;
+
+    std::ffi::CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+/// Rename that fails with `AlreadyExists` instead of replacing the destination
+#[cfg(target_os = "linux")]
+fn rename_no_replace(source: &Path, destination: &Path) -> io::Result<()> {
+    let (from, to) = (c_path(source)?, c_path(destination)?);
+    let result = unsafe {
+        libc::renameat2(
+            libc::AT_FDCWD,
+            from.as_ptr(),
+            libc::AT_FDCWD,
+            to.as_ptr(),
+            libc::RENAME_NOREPLACE,
+        )
+    };
+    if result == 0 {
+        return Ok(());
+    }
+
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        // Kernel or filesystem without RENAME_NOREPLACE support
+        Some(libc::EINVAL) | Some(libc::ENOSYS) => rename_checked(source, destination),
+        _ => Err(err),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn rename_no_replace(source: &Path, destination: &Path) -> io::Result<()> {
+    let (from, to) = (c_path(source)?, c_path(destination)?);
+    let result = unsafe { libc::renamex_np(from.as_ptr(), to.as_ptr(), libc::RENAME_EXCL) };
+    if result == 0 {
+        return Ok(());
+    }
+
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        // Filesystems such as SMB shares and FAT don't support RENAME_EXCL
+        Some(libc::ENOTSUP) | Some(libc::EINVAL) => rename_checked(source, destination),
+        _ => Err(err),
+    }
+}
+
+/// `MoveFileExW` without `MOVEFILE_REPLACE_EXISTING` refuses to replace the destination
+#[cfg(windows)]
+fn rename_no_replace(source: &Path, destination: &Path) -> io::Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::MoveFileExW;
+
+    let wide = |path: &Path| -> Vec<u16> {
+        path.as_os_str().encode_wide().chain(std::iter::once(0)).collect()
+    };
+    let (from, to) = (wide(source), wide(destination));
+
+    if unsafe { MoveFileExW(from.as_ptr(), to.as_ptr(), 0) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn rename_no_replace(source: &Path, destination: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(destination).is_ok() {
+        return Err(io::Error::from(io::ErrorKind::AlreadyExists));
+    }
+    fs::rename(source, destination)
+}
+
+/// Copy a file to `destination`, which must not exist yet; removed again if the copy fails
+fn copy_file_new(source: &Path, destination: &Path) -> io::Result<()> {
+    let mut file = fs::File::options().write(true).create_new(true).open(destination)?;
+    let copied = copy_contents(source, &mut file);
+    if copied.is_err() {
+        drop(file);
+        let _ = fs::remove_file(destination);
+    }
+    copied
+}
+
+/// Copy a file over `destination` through a temporary sibling, so a failed copy leaves
+/// the file that was there untouched
+fn copy_file_replacing(source: &Path, destination: &Path) -> io::Result<()> {
+    let name = destination.file_name().unwrap_or_default().to_string_lossy();
+    let tmp_path = destination.with_file_name(format!(".{}{}", name, crate::ATOMIC_TMP_SUFFIX));
+    // A leftover from an interrupted write, like the ones atomic_write truncates
+    let _ = fs::remove_file(&tmp_path);
+    let copied = copy_file_new(source, &tmp_path).and_then(|_| fs::rename(&tmp_path, destination));
+    if copied.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    copied
+}
+
+/// Copy the bytes, permissions and modification time of `source` into `file`
+fn copy_contents(source: &Path, file: &mut fs::File) -> io::Result<()> {
+    let mut from = fs::File::open(source)?;
+    io::copy(&mut from, file)?;
+    let metadata = from.metadata()?;
+    file.set_modified(metadata.modified()?)?;
+    file.set_permissions(metadata.permissions())
+}
+
+/// Copy a folder to `destination`, which must not exist yet; removed again if the copy fails
+fn copy_dir_recursive(source: &Path, destination: &Path) -> io::Result<()> {
+    fs::create_dir(destination)?;
+    let copied = copy_dir_contents(source, destination);
+    if copied.is_err() {
+        let _ = fs::remove_dir_all(destination);
+    }
+    copied
+}
+
+fn copy_dir_contents(source: &Path, destination: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let target = destination.join(entry.file_name());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            fs::create_dir(&target)?;
+            copy_dir_contents(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else {
+            copy_file_new(&entry.path(), &target)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Recreate a symlink instead of copying what it points to
+#[cfg(unix)]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, destination)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    copy_file_new(source, destination)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("igne-fs-move-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn copies_a_file_across_filesystems() {
+        let dir = temp_dir("copy-file");
+        let (source, destination) = (dir.join("a.md"), dir.join("b.md"));
+        fs::write(&source, "note").unwrap();
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        fs::File::options().write(true).open(&source).unwrap().set_modified(modified).unwrap();
+
+        copy_then_remove(&source, &destination, false).unwrap();
+        assert!(!source.exists());
+        assert_eq!(fs::read_to_string(&destination).unwrap(), "note");
+        assert_eq!(fs::metadata(&destination).unwrap().modified().unwrap(), modified);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn copies_a_folder_across_filesystems() {
+        let dir = temp_dir("copy-folder");
+        let (source, destination) = (dir.join("a"), dir.join("b"));
+        fs::create_dir_all(source.join("nested/deeper")).unwrap();
+        fs::write(source.join("note.md"), "top").unwrap();
+        fs::write(source.join("nested/deeper/note.md"), "deep").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("note.md", source.join("link.md")).unwrap();
+
+        copy_then_remove(&source, &destination, false).unwrap();
+        assert!(!source.exists());
+        assert_eq!(fs::read_to_string(destination.join("note.md")).unwrap(), "top");
+        assert_eq!(fs::read_to_string(destination.join("nested/deeper/note.md")).unwrap(), "deep");
+        #[cfg(unix)]
+        assert_eq!(fs::read_link(destination.join("link.md")).unwrap(), Path::new("note.md"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn copying_never_touches_an_existing_destination() {
+        let dir = temp_dir("copy-existing");
+        fs::write(dir.join("file.md"), "source").unwrap();
+        fs::create_dir(dir.join("folder")).unwrap();
+        fs::write(dir.join("folder/inner.md"), "source").unwrap();
+        fs::write(dir.join("taken.md"), "theirs").unwrap();
+        fs::create_dir(dir.join("taken")).unwrap();
+        fs::write(dir.join("taken/inner.md"), "theirs").unwrap();
+
+        let cases = [
+            ("file.md", "taken.md", false),
+            ("file.md", "taken", false),
+            ("file.md", "taken", true),
+            ("folder", "taken", false),
+            ("folder", "taken", true),
+            ("folder", "taken.md", true),
+        ];
+        for (source, destination, overwrite) in cases {
+            let err = copy_then_remove(&dir.join(source), &dir.join(destination), overwrite).unwrap_err();
+            assert_eq!(err.code(), "ALREADY_EXISTS", "{} -> {}", source, destination);
+        }
+        assert_eq!(fs::read_to_string(dir.join("taken.md")).unwrap(), "theirs");
+        assert_eq!(fs::read_to_string(dir.join("taken/inner.md")).unwrap(), "theirs");
+        assert_eq!(fs::read_dir(dir.join("taken")).unwrap().count(), 1);
+        assert!(dir.join("file.md").exists() && dir.join("folder/inner.md").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn copying_with_overwrite_replaces_a_file() {
+        let dir = temp_dir("copy-overwrite");
+        let (source, destination) = (dir.join("a.md"), dir.join("b.md"));
+        fs::write(&source, "new").unwrap();
+        fs::write(&destination, "old").unwrap();
+
+        copy_then_remove(&source, &destination, true).unwrap();
+        assert!(!source.exists());
+        assert_eq!(fs::read_to_string(&destination).unwrap(), "new");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_failed_copy_leaves_nothing_behind() {
+        let dir = temp_dir("copy-failed");
+        let missing = dir.join("missing.md");
+        fs::write(dir.join("b.md"), "theirs").unwrap();
+
+        assert!(copy_file_new(&missing, &dir.join("a.md")).is_err());
+        assert!(!dir.join("a.md").exists());
+        assert!(copy_file_replacing(&missing, &dir.join("b.md")).is_err());
+        assert_eq!(fs::read_to_string(dir.join("b.md")).unwrap(), "theirs");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn refuses_to_move_onto_an_existing_path() {
+        let dir = temp_dir("no-replace");
+        fs::write(dir.join("a.md"), "mine").unwrap();
+        fs::write(dir.join("b.md"), "theirs").unwrap();
+        fs::create_dir(dir.join("folder")).unwrap();
+        fs::create_dir(dir.join("taken")).unwrap();
+
+        let cases = [("a.md", "b.md"), ("a.md", "taken"), ("folder", "taken"), ("folder", "b.md")];
+        for (source, destination) in cases {
+            let err = move_path(&dir.join(source), &dir.join(destination), false).unwrap_err();
+            assert_eq!(err.code(), "ALREADY_EXISTS", "{} -> {}", source, destination);
+        }
+        assert_eq!(fs::read_to_string(dir.join("a.md")).unwrap(), "mine");
+        assert_eq!(fs::read_to_string(dir.join("b.md")).unwrap(), "theirs");
+        assert!(dir.join("folder").is_dir() && dir.join("taken").is_dir());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn moves_onto_a_free_path_or_replaces_with_overwrite() {
+        let dir = temp_dir("rename");
+        fs::write(dir.join("a.md"), "mine").unwrap();
+        fs::write(dir.join("b.md"), "theirs").unwrap();
+
+        assert_eq!(move_path(&dir.join("a.md"), &dir.join("c.md"), false).unwrap(), MoveStrategy::Rename);
+        assert_eq!(move_path(&dir.join("c.md"), &dir.join("b.md"), true).unwrap(), MoveStrategy::Rename);
+        assert!(!dir.join("a.md").exists() && !dir.join("c.md").exists());
+        assert_eq!(fs::read_to_string(dir.join("b.md")).unwrap(), "mine");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn renames_a_file_to_a_different_case() {
+        let dir = temp_dir("case");
+        fs::write(dir.join("note.md"), "mine").unwrap();
+
+        // On a case-insensitive filesystem `Note.md` already "exists" as the source itself
+        move_path(&dir.join("note.md"), &dir.join("Note.md"), false).unwrap();
+        let names: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, ["Note.md"]);
+        assert_eq!(fs::read_to_string(dir.join("Note.md")).unwrap(), "mine");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
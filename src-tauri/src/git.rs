@@ -0,0 +1,363 @@
+use git2::{DiffFormat, DiffOptions, ErrorCode, IndexAddOption, Repository, Signature, Status, StatusOptions};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::blocking;
+use crate::error::IgneError;
+use crate::paths::vault_relative;
+use crate::scope::{ensure_in_scope, resolve};
+
+const DEFAULT_LOG_LIMIT: usize = 50;
+
+#[derive(Serialize, Clone, Default, Debug)]
+pub struct GitStatus {
+    /// Changes in the index, ready to commit
+    pub staged: Vec<String>,
+    /// Tracked files changed in the working copy since they were staged or committed
+    pub modified: Vec<String>,
+    pub untracked: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct GitCommitInfo {
+    pub id: String,
+    pub author: String,
+    pub email: String,
+    /// Seconds since the epoch
+    pub time: i64,
+    pub message: String,
+}
+
+/// A vault's repository, which may be the vault itself or a folder above it
+struct VaultRepo {
+    repo: Repository,
+    /// Vault path as the frontend passed it, for building the paths handed back
+    vault: PathBuf,
+    /// The vault's folder relative to the repository root, `/`-separated ("" at the root)
+    prefix: String,
+}
+
+impl VaultRepo {
+    fn open(vault_path: &str) -> Result<Self, IgneError> {
+        let vault = PathBuf::from(vault_path);
+        let repo = Repository::discover(&vault).map_err(|e| match e.code() {
+            ErrorCode::NotFound => IgneError::not_found(format!("Not a git repository: {}", vault_path), vault_path),
+            _ => e.into(),
+        })?;
+        let workdir = repo
+            .workdir()
+            .map(resolve)
+            .ok_or_else(|| IgneError::invalid_path(format!("Repository has no working copy: {}", vault_path), vault_path))?;
+        let prefix = vault_relative(&workdir, &vault).unwrap_or_default();
+        Ok(Self { repo, vault, prefix })
+    }
+
+    /// Repository-relative path of a note, or an error for paths outside the vault
+    fn repo_path(&self, path: &str) -> Result<String, IgneError> {
+        let relative = vault_relative(&self.vault, Path::new(path))?;
+        Ok(self.join_prefix(&relative))
+    }
+
+    fn join_prefix(&self, relative: &str) -> String {
+        match (self.prefix.is_empty(), relative.is_empty()) {
+            (true, _) => relative.to_string(),
+            (false, true) => self.prefix.clone(),
+            (false, false) => format!("{}/{}", self.prefix, relative),
+        }
+    }
+
+    /// Absolute path of a repository-relative path, if it's inside the vault
+    fn vault_path(&self, repo_path: &str) -> Option<String> {
+        let relative = if self.prefix.is_empty() {
+            repo_path
+        } else {
+            repo_path.strip_prefix(&self.prefix)?.strip_prefix('/')?
+        };
+        Some(self.vault.join(relative).to_string_lossy().to_string())
+    }
+
+    /// Tree of the HEAD commit; `None` for a repository with no commits yet
+    fn head_tree(&self) -> Result<Option<git2::Tree<'_>>, IgneError> {
+        match self.repo.head() {
+            Ok(head) => Ok(Some(head.peel_to_tree()?)),
+            Err(e) if matches!(e.code(), ErrorCode::UnbornBranch | ErrorCode::NotFound) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Staged, modified and untracked files in the vault. Fails with `NOT_FOUND` when the vault
+/// isn't in a git repository, so the UI can hide its git features.
+#[tauri::command]
+pub async fn git_status(vault_path: String, app: AppHandle) -> Result<GitStatus, IgneError> {
+    ensure_in_scope(&app, Path::new(&vault_path))?;
+    blocking(move || status(&vault_path)).await
+}
+
+fn status(vault_path: &str) -> Result<GitStatus, IgneError> {
+    let repo = VaultRepo::open(vault_path)?;
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    if !repo.prefix.is_empty() {
+        options.pathspec(&repo.prefix);
+    }
+
+    let mut status = GitStatus::default();
+    for entry in repo.repo.statuses(Some(&mut options))?.iter() {
+        let Some(path) = entry.path().and_then(|p| repo.vault_path(p)) else {
+            continue;
+        };
+        let flags = entry.status();
+        if flags.intersects(
+            Status::INDEX_NEW | Status::INDEX_MODIFIED | Status::INDEX_DELETED | Status::INDEX_RENAMED | Status::INDEX_TYPECHANGE,
+        ) {
+            status.staged.push(path.clone());
+        }
+        if flags.intersects(Status::WT_MODIFIED | Status::WT_DELETED | Status::WT_RENAMED | Status::WT_TYPECHANGE) {
+            status.modified.push(path.clone());
+        }
+        if flags.contains(Status::WT_NEW) {
+            status.untracked.push(path);
+        }
+    }
+    Ok(status)
+}
+
+/// Commit `paths`, or every change in the vault when `paths` is omitted, and return the
+/// new commit id. Uses the author from the git config, falling back to "Igne" when there is
+/// none (git itself may not even be installed).
+#[tauri::command]
+pub async fn git_commit(
+    vault_path: String,
+    message: String,
+    paths: Option<Vec<String>>,
+    app: AppHandle,
+) -> Result<String, IgneError> {
+    ensure_in_scope(&app, Path::new(&vault_path))?;
+    if message.trim().is_empty() {
+        return Err(IgneError::invalid_input("Commit message is empty"));
+    }
+    blocking(move || commit(&vault_path, message.trim(), paths)).await
+}
+
+fn commit(vault_path: &str, message: &str, paths: Option<Vec<String>>) -> Result<String, IgneError> {
+    let repo = VaultRepo::open(vault_path)?;
+    let workdir = repo.repo.workdir().map(Path::to_path_buf).unwrap_or_default();
+    let mut index = repo.repo.index()?;
+
+    match paths {
+        Some(paths) => {
+            for path in paths {
+                let repo_path = repo.repo_path(&path)?;
+                if workdir.join(&repo_path).exists() {
+                    index.add_path(Path::new(&repo_path))?;
+                } else {
+                    index.remove_path(Path::new(&repo_path))?;
+                }
+            }
+        }
+        None => {
+            let spec = if repo.prefix.is_empty() { "*".to_string() } else { format!("{}/*", repo.prefix) };
+            index.add_all([&spec], IndexAddOption::DEFAULT, None)?;
+            index.update_all([&spec], None)?;
+        }
+    }
+    index.write()?;
+
+    let tree = repo.repo.find_tree(index.write_tree()?)?;
+    let parent = match repo.repo.head() {
+        Ok(head) => Some(head.peel_to_commit()?),
+        Err(e) if matches!(e.code(), ErrorCode::UnbornBranch | ErrorCode::NotFound) => None,
+        Err(e) => return Err(e.into()),
+    };
+    if parent.as_ref().is_some_and(|parent| parent.tree_id() == tree.id()) {
+        return Err(IgneError::conflict("Nothing to commit", vault_path));
+    }
+
+    let signature = repo
+        .repo
+        .signature()
+        .or_else(|_| Signature::now("Igne", "igne@localhost"))?;
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let id = repo
+        .repo
+        .commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)?;
+    Ok(id.to_string())
+}
+
+/// Commits reachable from HEAD, newest first, limited to those that changed `path` when
+/// it's given. A repository without commits has an empty history.
+#[tauri::command]
+pub async fn git_log(
+    vault_path: String,
+    path: Option<String>,
+    limit: Option<usize>,
+    app: AppHandle,
+) -> Result<Vec<GitCommitInfo>, IgneError> {
+    ensure_in_scope(&app, Path::new(&vault_path))?;
+    blocking(move || log(&vault_path, path.as_deref(), limit)).await
+}
+
+fn log(vault_path: &str, path: Option<&str>, limit: Option<usize>) -> Result<Vec<GitCommitInfo>, IgneError> {
+    let repo = VaultRepo::open(vault_path)?;
+    let file = path.map(|p| repo.repo_path(p)).transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT);
+
+    if repo.head_tree()?.is_none() {
+        return Ok(vec![]);
+    }
+    let mut walk = repo.repo.revwalk()?;
+    walk.push_head()?;
+    walk.set_sorting(git2::Sort::TIME)?;
+
+    let mut commits = vec![];
+    for id in walk {
+        if commits.len() >= limit {
+            break;
+        }
+        let commit = repo.repo.find_commit(id?)?;
+        if let Some(file) = &file {
+            // A commit touches the file when its blob differs from the first parent's
+            let blob = |tree: git2::Tree| tree.get_path(Path::new(file)).ok().map(|entry| entry.id());
+            let current = blob(commit.tree()?);
+            let previous = match commit.parent(0) {
+                Ok(parent) => blob(parent.tree()?),
+                Err(_) => None,
+            };
+            if current == previous {
+                continue;
+            }
+        }
+
+        let author = commit.author();
+        commits.push(GitCommitInfo {
+            id: commit.id().to_string(),
+            author: author.name().unwrap_or_default().to_string(),
+            email: author.email().unwrap_or_default().to_string(),
+            time: commit.time().seconds(),
+            message: commit.message().unwrap_or_default().trim_end().to_string(),
+        });
+    }
+    Ok(commits)
+}
+
+/// Unified diff of a note's working copy against HEAD. New and untracked files diff
+/// against nothing; an unchanged file gives an empty string.
+#[tauri::command]
+pub async fn git_diff_file(vault_path: String, path: String, app: AppHandle) -> Result<String, IgneError> {
+    ensure_in_scope(&app, Path::new(&vault_path))?;
+    ensure_in_scope(&app, Path::new(&path))?;
+    blocking(move || diff_file(&vault_path, &path)).await
+}
+
+fn diff_file(vault_path: &str, path: &str) -> Result<String, IgneError> {
+    let repo = VaultRepo::open(vault_path)?;
+    let repo_path = repo.repo_path(path)?;
+    let head = repo.head_tree()?;
+
+    let mut options = DiffOptions::new();
+    options
+        .pathspec(&repo_path)
+        .disable_pathspec_match(true)
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    let diff = repo.repo.diff_tree_to_workdir(head.as_ref(), Some(&mut options))?;
+
+    let mut patch = String::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin());
+        }
+        patch.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })?;
+    Ok(patch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_repo(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("igne-git-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        Repository::init(&dir).unwrap();
+        dir
+    }
+
+    fn path_string(path: PathBuf) -> String {
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn works_before_the_first_commit() {
+        let dir = temp_repo("unborn");
+        let vault = path_string(dir.clone());
+        let note = path_string(dir.join("note.md"));
+        fs::write(&note, "hello\n").unwrap();
+
+        assert_eq!(status(&vault).unwrap().untracked, [note.as_str()]);
+        assert!(log(&vault, None, None).unwrap().is_empty());
+        assert!(log(&vault, Some(&note), None).unwrap().is_empty());
+        assert_eq!(diff_file(&vault, &note).unwrap().lines().last(), Some("+hello"));
+
+        let id = commit(&vault, "First", None).unwrap();
+        let history = log(&vault, Some(&note), None).unwrap();
+        assert_eq!((history.len(), history[0].id.as_str(), history[0].message.as_str()), (1, id.as_str(), "First"));
+        assert!(status(&vault).unwrap().untracked.is_empty());
+        assert_eq!(diff_file(&vault, &note).unwrap(), "");
+        assert_eq!(commit(&vault, "Again", None).unwrap_err().code(), "CONFLICT");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn only_sees_the_vault_inside_a_larger_repo() {
+        let dir = temp_repo("subfolder");
+        fs::create_dir(dir.join("vault")).unwrap();
+        let vault = path_string(dir.join("vault"));
+        let note = path_string(dir.join("vault").join("note.md"));
+        fs::write(&note, "hello\n").unwrap();
+        fs::write(dir.join("outside.md"), "elsewhere\n").unwrap();
+
+        assert_eq!(status(&vault).unwrap().untracked, [note.as_str()]);
+        commit(&vault, "Vault only", None).unwrap();
+        assert_eq!(status(&path_string(dir.clone())).unwrap().untracked, [path_string(dir.join("outside.md"))]);
+
+        fs::write(&note, "hello\nagain\n").unwrap();
+        assert_eq!(status(&vault).unwrap().modified, [note.as_str()]);
+        assert!(diff_file(&vault, &note).unwrap().contains("\n+again\n"));
+        commit(&vault, "Again", Some(vec![note.clone()])).unwrap();
+        let messages: Vec<String> = log(&vault, Some(&note), None).unwrap().into_iter().map(|c| c.message).collect();
+        assert_eq!(messages, ["Again", "Vault only"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_vault() {
+        let dir = temp_repo("outside");
+        fs::create_dir(dir.join("vault")).unwrap();
+        let vault = path_string(dir.join("vault"));
+        let outside = path_string(dir.join("outside.md"));
+        fs::write(&outside, "elsewhere\n").unwrap();
+
+        assert_eq!(diff_file(&vault, &outside).unwrap_err().code(), "INVALID_PATH");
+        assert_eq!(log(&vault, Some(&outside), None).unwrap_err().code(), "INVALID_PATH");
+        assert_eq!(commit(&vault, "Sneaky", Some(vec![outside])).unwrap_err().code(), "INVALID_PATH");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reports_folders_outside_any_repo_as_not_found() {
+        let dir = std::env::temp_dir().join(format!("igne-git-none-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        // A repository further up (e.g. a checkout containing the temp dir) is fine too
+        if Repository::discover(&dir).is_err() {
+            assert_eq!(status(&path_string(dir.clone())).unwrap_err().code(), "NOT_FOUND");
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,168 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+use crate::atomic_write;
+use crate::error::IgneError;
+use crate::scope::ensure_in_scope;
+use crate::write_lock::with_write_lock;
+
+/// Per-folder directory holding previous versions of the notes next to it
+pub const BACKUP_DIR: &str = ".igne-backups";
+
+#[derive(Serialize, Clone)]
+pub struct VersionEntry {
+    pub timestamp: u64,
+    pub backup_path: String,
+    pub size: u64,
+}
+
+fn backup_dir_for(path: &Path) -> PathBuf {
+    path.parent().unwrap_or(Path::new("")).join(BACKUP_DIR)
+}
+
+fn file_name_of(path: &Path) -> Result<String, IgneError> {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| IgneError::invalid_path(format!("Not a file path: {}", path.display()), path))
+}
+
+/// Backups are named `<file name>.<unix seconds>.bak`, with `-<n>` after the seconds for
+/// the second and later backup within one second. Returns the seconds and `n` (0 for none).
+fn parse_backup_name(backup_name: &str, file_name: &str) -> Option<(u64, u32)> {
+    let stamp = backup_name
+        .strip_prefix(file_name)?
+        .strip_prefix('.')?
+        .strip_suffix(".bak")?;
+    match stamp.split_once('-') {
+        Some((secs, n)) => Some((secs.parse().ok()?, n.parse().ok()?)),
+        None => Some((stamp.parse().ok()?, 0)),
+    }
+}
+
+/// Write a note atomically, first copying the current version into `.igne-backups`
+#[tauri::command]
+pub async fn write_file_safe(path: String, content: String, app: AppHandle) -> Result<(), IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+
+    with_write_lock(&app, &path_obj, || {
+        if path_obj.is_file() {
+            backup_current_version(&path_obj)?;
+        }
+        atomic_write(&path_obj, content.as_bytes())
+    })
+    .await
+}
+
+fn backup_current_version(path: &Path) -> Result<(), IgneError> {
+    let backup_dir = backup_dir_for(path);
+    fs::create_dir_all(&backup_dir).map_err(|e| IgneError::io(e, &backup_dir))?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let file_name = file_name_of(path)?;
+    let mut backup_path = backup_dir.join(format!("{}.{}.bak", file_name, timestamp));
+    // Saves within the same second each keep their own backup
+    let mut n = 1;
+    while backup_path.exists() {
+        backup_path = backup_dir.join(format!("{}.{}-{}.bak", file_name, timestamp, n));
+        n += 1;
+    }
+    fs::copy(path, &backup_path).map_err(|e| IgneError::io(e, &backup_path))?;
+    Ok(())
+}
+
+/// Saved versions of a note, newest first
+#[tauri::command]
+pub fn list_file_versions(path: String, app: AppHandle) -> Result<Vec<VersionEntry>, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+
+    let backup_dir = backup_dir_for(&path_obj);
+    if !backup_dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let file_name = file_name_of(&path_obj)?;
+    let mut versions = vec![];
+
+    for entry in fs::read_dir(&backup_dir).map_err(|e| IgneError::io(e, &backup_dir))?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some((timestamp, n)) = parse_backup_name(&name, &file_name) else {
+            continue;
+        };
+        let version = VersionEntry {
+            timestamp,
+            backup_path: entry.path().to_string_lossy().to_string(),
+            size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+        };
+        versions.push((n, version));
+    }
+
+    versions.sort_by_key(|(n, v)| std::cmp::Reverse((v.timestamp, *n)));
+    Ok(versions.into_iter().map(|(_, version)| version).collect())
+}
+
+/// Replace a note with one of its saved versions
+#[tauri::command]
+pub async fn restore_file_version(path: String, backup_path: String, app: AppHandle) -> Result<(), IgneError> {
+    let path_obj = PathBuf::from(&path);
+    let backup_obj = PathBuf::from(&backup_path);
+    ensure_in_scope(&app, &path_obj)?;
+    ensure_in_scope(&app, &backup_obj)?;
+
+    // Only accept backups that belong to this note
+    let file_name = file_name_of(&path_obj)?;
+    let belongs = backup_obj.parent() == Some(backup_dir_for(&path_obj).as_path())
+        && backup_obj
+            .file_name()
+            .and_then(|n| parse_backup_name(&n.to_string_lossy(), &file_name))
+            .is_some();
+    if !belongs {
+        return Err(IgneError::invalid_path(
+            format!("Not a backup of {}: {}", path, backup_path),
+            &backup_path,
+        ));
+    }
+
+    with_write_lock(&app, &path_obj, || {
+        let data = fs::read(&backup_obj).map_err(|e| IgneError::io(e, &backup_obj))?;
+        atomic_write(&path_obj, &data)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_backup_names_with_and_without_a_counter() {
+        assert_eq!(parse_backup_name("Note.md.1700000000.bak", "Note.md"), Some((1700000000, 0)));
+        assert_eq!(parse_backup_name("Note.md.1700000000-2.bak", "Note.md"), Some((1700000000, 2)));
+        assert_eq!(parse_backup_name("Note.md.1700000000-x.bak", "Note.md"), None);
+        assert_eq!(parse_backup_name("Other.md.1700000000.bak", "Note.md"), None);
+    }
+
+    #[test]
+    fn backups_in_the_same_second_get_their_own_names() {
+        let dir = std::env::temp_dir().join(format!("igne-history-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let note = dir.join("Note.md");
+
+        for content in ["one", "two", "three"] {
+            fs::write(&note, content).unwrap();
+            backup_current_version(&note).unwrap();
+        }
+        let backups = fs::read_dir(dir.join(BACKUP_DIR)).unwrap().count();
+        fs::remove_dir_all(&dir).unwrap();
+
+        // A second boundary may fall between the saves, but no backup is overwritten
+        assert_eq!(backups, 3);
+    }
+}
@@ -0,0 +1,405 @@
+use pulldown_cmark::{Event, LinkType, Parser, Tag, TagEnd};
+use serde::Serialize;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::{atomic_write, blocking};
+use crate::error::IgneError;
+use crate::markdown_ast::parser_options;
+use crate::paths::{long_path, vault_relative};
+use crate::scope::ensure_in_scope;
+use crate::vault_trash::unique_path;
+use crate::write_lock::with_write_lock;
+use crate::{links, vault};
+
+#[derive(Serialize, Clone, Copy)]
+pub struct ImageDimensions {
+    pub width: u32,
+    pub height: u32,
+}
+
+fn is_svg(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().eq_ignore_ascii_case("svg"))
+        .unwrap_or(false)
+}
+
+/// Value of `name="..."` (or single-quoted) inside a tag
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut from = 0;
+    while let Some(rel) = tag[from..].find(name) {
+        let at = from + rel;
+        from = at + name.len();
+        // Must be a whole attribute name, not the tail of another (`stroke-width`)
+        if at > 0 && !tag.as_bytes()[at - 1].is_ascii_whitespace() {
+            continue;
+        }
+        let rest = tag[from..].trim_start().strip_prefix('=')?.trim_start();
+        let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let value = &rest[1..];
+        return value.find(quote).map(|end| &value[..end]);
+    }
+    None
+}
+
+/// Plain or `px` lengths; percentages and other units can't be resolved without a viewport
+fn parse_length(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let number = value.strip_suffix("px").unwrap_or(value);
+    number.parse::<f64>().ok().filter(|n| *n > 0.0)
+}
+
+/// Size of an SVG from its root `width`/`height`, falling back to the `viewBox`
+fn svg_dimensions(content: &str) -> Option<ImageDimensions> {
+    let start = content.find("<svg")?;
+    let end = start + content[start..].find('>')?;
+    let tag = &content[start..end];
+
+    let explicit = attribute(tag, "width")
+        .and_then(parse_length)
+        .zip(attribute(tag, "height").and_then(parse_length));
+
+    let (width, height) = match explicit {
+        Some(size) => size,
+        None => {
+            let view_box: Vec<f64> = attribute(tag, "viewBox")?
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|s| !s.is_empty())
+                .filter_map(|s| s.parse().ok())
+                .collect();
+            match view_box[..] {
+                [_, _, w, h] if w > 0.0 && h > 0.0 => (w, h),
+                _ => return None,
+            }
+        }
+    };
+
+    Some(ImageDimensions {
+        width: width.round() as u32,
+        height: height.round() as u32,
+    })
+}
+
+/// Width and height of an image, read from its header without decoding pixels
+#[tauri::command]
+pub fn get_image_dimensions(path: String, app: AppHandle) -> Result<ImageDimensions, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+
+    if is_svg(&path_obj) {
+        let content = fs::read_to_string(long_path(&path_obj)).map_err(|e| IgneError::io(e, &path))?;
+        return svg_dimensions(&content).ok_or_else(|| {
+            IgneError::invalid_input(format!("SVG has no usable width/height or viewBox: {}", path))
+        });
+    }
+
+    let reader = image::ImageReader::open(&path_obj)
+        .map_err(|e| IgneError::io(e, &path))?
+        .with_guessed_format()
+        .map_err(|e| IgneError::io(e, &path))?;
+    if reader.format().is_none() {
+        return Err(IgneError::invalid_input(format!("Unsupported image format: {}", path)));
+    }
+
+    let (width, height) = reader
+        .into_dimensions()
+        .map_err(|e| IgneError::invalid_input(format!("Could not read image {}: {}", path, e)))?;
+    Ok(ImageDimensions { width, height })
+}
+
+#[derive(Serialize, Clone)]
+pub struct ImageOptimizeResult {
+    pub original_bytes: u64,
+    pub optimized_bytes: u64,
+    pub output_path: String,
+}
+
+const DEFAULT_JPEG_QUALITY: u8 = 82;
+
+fn output_format(format: Option<&str>, source: image::ImageFormat) -> Result<image::ImageFormat, IgneError> {
+    let Some(format) = format else {
+        return match source {
+            image::ImageFormat::Png | image::ImageFormat::Jpeg | image::ImageFormat::WebP => Ok(source),
+            // Formats we can read but shouldn't write back (GIF would lose animation)
+            _ => Err(IgneError::invalid_input(format!(
+                "Can't re-encode {:?} images; pass format \"jpeg\", \"png\" or \"webp\"",
+                source
+            ))),
+        };
+    };
+
+    match format.to_lowercase().as_str() {
+        "jpeg" | "jpg" => Ok(image::ImageFormat::Jpeg),
+        "png" => Ok(image::ImageFormat::Png),
+        "webp" => Ok(image::ImageFormat::WebP),
+        other => Err(IgneError::invalid_input(format!("Unsupported output format: {}", other))),
+    }
+}
+
+fn encode(img: &image::DynamicImage, format: image::ImageFormat, quality: u8) -> Result<Vec<u8>, IgneError> {
+    use image::codecs::jpeg::JpegEncoder;
+    use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+
+    let mut out = Vec::new();
+    let result = match format {
+        // JPEG has no alpha channel
+        image::ImageFormat::Jpeg => img
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut out, quality.clamp(1, 100))),
+        image::ImageFormat::Png => img.write_with_encoder(PngEncoder::new_with_quality(
+            &mut out,
+            CompressionType::Best,
+            FilterType::Adaptive,
+        )),
+        // The image crate only encodes lossless WebP, so quality doesn't apply
+        _ => img.write_to(&mut std::io::Cursor::new(&mut out), format),
+    };
+    result.map_err(|e| IgneError::other(format!("Failed to encode image: {}", e)))?;
+    Ok(out)
+}
+
+/// Decode `bytes` with the EXIF orientation applied. Re-encoding drops the EXIF data, so
+/// a photo taken sideways would otherwise come out rotated.
+fn decode_upright(bytes: &[u8], format: image::ImageFormat) -> image::ImageResult<image::DynamicImage> {
+    use image::ImageDecoder;
+
+    let mut decoder = image::ImageReader::with_format(Cursor::new(bytes), format).into_decoder()?;
+    let orientation = decoder.orientation().unwrap_or(image::metadata::Orientation::NoTransforms);
+    let mut img = image::DynamicImage::from_decoder(decoder)?;
+    img.apply_orientation(orientation);
+    Ok(img)
+}
+
+/// Downscale an attachment to fit `max_width` x `max_height` (aspect ratio kept, never
+/// upscaled) and recompress it. Writes over the original unless `output_path` is given;
+/// converting to another format without an `output_path` writes next to the original
+/// with the new extension, numbered if that name is taken. An in-place result that isn't smaller leaves the file untouched.
+#[tauri::command]
+pub async fn optimize_image(
+    path: String,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+    quality: Option<u8>,
+    format: Option<String>,
+    output_path: Option<String>,
+    app: AppHandle,
+) -> Result<ImageOptimizeResult, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+
+    let (original, optimized, source_format, target_format) = blocking({
+        let path = path.clone();
+        move || {
+            let original = fs::read(long_path(Path::new(&path))).map_err(|e| IgneError::io(e, &path))?;
+            let source_format = image::guess_format(&original)
+                .map_err(|_| IgneError::invalid_input(format!("Unsupported image format: {}", path)))?;
+            let target_format = output_format(format.as_deref(), source_format)?;
+
+            let mut img = decode_upright(&original, source_format)
+                .map_err(|e| IgneError::invalid_input(format!("Could not decode image {}: {}", path, e)))?;
+
+            let (width, height) = (img.width(), img.height());
+            let max_width = max_width.unwrap_or(width).min(width);
+            let max_height = max_height.unwrap_or(height).min(height);
+            if max_width < width || max_height < height {
+                img = img.resize(max_width, max_height, image::imageops::FilterType::Lanczos3);
+            }
+
+            let optimized = encode(&img, target_format, quality.unwrap_or(DEFAULT_JPEG_QUALITY))?;
+            Ok((original, optimized, source_format, target_format))
+        }
+    })
+    .await?;
+
+    let output = match output_path {
+        Some(output) => PathBuf::from(output),
+        None if target_format == source_format => path_obj.clone(),
+        None => unique_path(&path_obj.with_extension(target_format.extensions_str()[0])),
+    };
+    ensure_in_scope(&app, &output)?;
+
+    let original_bytes = original.len() as u64;
+    if output == path_obj && optimized.len() as u64 >= original_bytes {
+        return Ok(ImageOptimizeResult {
+            original_bytes,
+            optimized_bytes: original_bytes,
+            output_path: path,
+        });
+    }
+
+    with_write_lock(&app, &output, || atomic_write(&output, &optimized)).await?;
+
+    Ok(ImageOptimizeResult {
+        original_bytes,
+        optimized_bytes: optimized.len() as u64,
+        output_path: output.to_string_lossy().to_string(),
+    })
+}
+
+/// Extensions an `![[embed]]` must have to count as an image rather than an embedded note
+pub const IMAGE_EXTENSIONS: [&str; 9] = ["png", "jpg", "jpeg", "gif", "webp", "svg", "bmp", "avif", "tiff"];
+
+#[derive(Serialize, Clone)]
+pub struct ImageReference {
+    /// The `![[...]]` or `![alt](url)` text as written in the note
+    pub raw_syntax: String,
+    /// Absolute path of the image when it's an existing local file
+    pub resolved_path: Option<String>,
+    /// `resolved_path` relative to the vault, `/`-separated; `None` for images outside it
+    pub relative_path: Option<String>,
+    pub alt_text: String,
+    /// 1-based line of the reference
+    pub line: u64,
+}
+
+fn has_image_extension(target: &str) -> bool {
+    Path::new(target)
+        .extension()
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str()))
+}
+
+/// Resolves image destinations of one note; the vault is only walked when a bare
+/// `![[name.png]]` isn't next to the note
+struct ImageResolver<'a> {
+    vault: &'a Path,
+    note_dir: &'a Path,
+    vault_files: Option<Vec<PathBuf>>,
+}
+
+impl ImageResolver<'_> {
+    fn existing(path: &Path) -> Option<String> {
+        let path = links::lexical_normalize(path);
+        path.is_file().then(|| path.to_string_lossy().to_string())
+    }
+
+    /// `![alt](dest)`: relative to the note, or to the vault root with a leading `/`
+    fn markdown(&self, dest: &str) -> Option<String> {
+        if dest.contains("://") || dest.starts_with("data:") {
+            return None;
+        }
+        let dest = links::percent_decode(dest);
+        match dest.strip_prefix('/') {
+            Some(rooted) => Self::existing(&self.vault.join(rooted)).or_else(|| Self::existing(Path::new(&dest))),
+            None => Self::existing(&self.note_dir.join(&dest)),
+        }
+    }
+
+    /// `![[target]]`: a path from the vault root or the note's folder, else the file with
+    /// that name closest to the vault root, the way Obsidian resolves embeds
+    fn wikilink(&mut self, target: &str) -> Option<String> {
+        if let Some(found) = Self::existing(&self.note_dir.join(target)) {
+            return Some(found);
+        }
+        if target.contains('/') {
+            return Self::existing(&self.vault.join(target));
+        }
+
+        let vault = self.vault;
+        let files = self
+            .vault_files
+            .get_or_insert_with(|| vault::walk_vault_files(vault).unwrap_or_default());
+        let name = target.to_lowercase();
+        files
+            .iter()
+            .filter(|file| {
+                file.file_name()
+                    .is_some_and(|file_name| file_name.to_string_lossy().to_lowercase() == name)
+            })
+            .min_by_key(|file| file.components().count())
+            .map(|file| file.to_string_lossy().to_string())
+    }
+}
+
+/// An image whose alt text is still being collected
+struct OpenImage {
+    start: usize,
+    end: usize,
+    is_wikilink: bool,
+    /// `![[image.png|alias]]`; without an alias the parser repeats the target as text
+    has_alias: bool,
+    dest: String,
+    alt_text: String,
+}
+
+/// Every image a note embeds, in document order: markdown images (including remote
+/// ones, which have no `resolved_path`) and `![[...]]` embeds of image files
+#[tauri::command]
+pub fn extract_all_images(
+    path: String,
+    vault_path: String,
+    app: AppHandle,
+) -> Result<Vec<ImageReference>, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    let vault_obj = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &path_obj)?;
+    ensure_in_scope(&app, &vault_obj)?;
+
+    let content = fs::read_to_string(long_path(&path_obj)).map_err(|e| IgneError::io(e, &path))?;
+    let note_dir = path_obj.parent().unwrap_or(&vault_obj);
+    let mut resolver = ImageResolver {
+        vault: &vault_obj,
+        note_dir,
+        vault_files: None,
+    };
+
+    let mut open: Vec<OpenImage> = Vec::new();
+    let mut images = Vec::new();
+    for (event, range) in Parser::new_ext(&content, parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Image { link_type, dest_url, .. }) => open.push(OpenImage {
+                start: range.start,
+                end: range.end,
+                is_wikilink: matches!(link_type, LinkType::WikiLink { .. }),
+                has_alias: matches!(link_type, LinkType::WikiLink { has_pothole: true }),
+                dest: dest_url.to_string(),
+                alt_text: String::new(),
+            }),
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(image) = open.last_mut() {
+                    image.alt_text.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Image) => {
+                if let Some(image) = open.pop() {
+                    images.push(image);
+                }
+            }
+            _ => {}
+        }
+    }
+    // Nested images close before the image whose alt text contains them
+    images.sort_by_key(|image| image.start);
+
+    let mut references = Vec::new();
+    let mut line = 1;
+    let mut counted_to = 0;
+    for image in images {
+        let raw_syntax = content[image.start..image.end].to_string();
+        let (resolved_path, alt_text) = if image.is_wikilink {
+            if !has_image_extension(&image.dest) {
+                continue;
+            }
+            let alt_text = if image.has_alias { image.alt_text } else { String::new() };
+            (resolver.wikilink(&image.dest), alt_text)
+        } else {
+            (resolver.markdown(&image.dest), image.alt_text)
+        };
+
+        let relative_path = resolved_path
+            .as_deref()
+            .and_then(|path| vault_relative(&vault_obj, Path::new(path)).ok());
+
+        line += content[counted_to..image.start].matches('\n').count() as u64;
+        counted_to = image.start;
+        references.push(ImageReference {
+            raw_syntax,
+            resolved_path,
+            relative_path,
+            alt_text,
+            line,
+        });
+    }
+    Ok(references)
+}
@@ -0,0 +1,92 @@
+use serde::Serialize;
+use whatlang::Lang;
+
+use crate::error::IgneError;
+use crate::markdown;
+
+/// Fewer letters than this and detection is a coin toss, so the note is "unknown"
+const MIN_LETTERS: usize = 40;
+
+/// ISO 639-1 codes for the languages whatlang knows, as spell checkers and the `lang`
+/// attribute expect. Languages without one keep their ISO 639-3 code.
+const TWO_LETTER_CODES: &[(Lang, &str)] = &[
+    (Lang::Afr, "af"), (Lang::Aka, "ak"), (Lang::Amh, "am"), (Lang::Ara, "ar"),
+    (Lang::Aze, "az"), (Lang::Bel, "be"), (Lang::Ben, "bn"), (Lang::Bul, "bg"),
+    (Lang::Cat, "ca"), (Lang::Ces, "cs"), (Lang::Cmn, "zh"), (Lang::Cym, "cy"),
+    (Lang::Dan, "da"), (Lang::Deu, "de"), (Lang::Ell, "el"), (Lang::Eng, "en"),
+    (Lang::Epo, "eo"), (Lang::Est, "et"), (Lang::Fin, "fi"), (Lang::Fra, "fr"),
+    (Lang::Guj, "gu"), (Lang::Heb, "he"), (Lang::Hin, "hi"), (Lang::Hrv, "hr"),
+    (Lang::Hun, "hu"), (Lang::Hye, "hy"), (Lang::Ind, "id"), (Lang::Ita, "it"),
+    (Lang::Jav, "jv"), (Lang::Jpn, "ja"), (Lang::Kan, "kn"), (Lang::Kat, "ka"),
+    (Lang::Khm, "km"), (Lang::Kor, "ko"), (Lang::Lat, "la"), (Lang::Lav, "lv"),
+    (Lang::Lit, "lt"), (Lang::Mal, "ml"), (Lang::Mar, "mr"), (Lang::Mkd, "mk"),
+    (Lang::Mya, "my"), (Lang::Nep, "ne"), (Lang::Nld, "nl"), (Lang::Nob, "nb"),
+    (Lang::Ori, "or"), (Lang::Pan, "pa"), (Lang::Pes, "fa"), (Lang::Pol, "pl"),
+    (Lang::Por, "pt"), (Lang::Ron, "ro"), (Lang::Rus, "ru"), (Lang::Sin, "si"),
+    (Lang::Slk, "sk"), (Lang::Slv, "sl"), (Lang::Sna, "sn"), (Lang::Spa, "es"),
+    (Lang::Srp, "sr"), (Lang::Swe, "sv"), (Lang::Tam, "ta"), (Lang::Tel, "te"),
+    (Lang::Tgl, "tl"), (Lang::Tha, "th"), (Lang::Tuk, "tk"), (Lang::Tur, "tr"),
+    (Lang::Ukr, "uk"), (Lang::Urd, "ur"), (Lang::Uzb, "uz"), (Lang::Vie, "vi"),
+    (Lang::Yid, "yi"), (Lang::Zul, "zu"),
+];
+
+#[derive(Serialize, Clone)]
+pub struct DetectedLanguage {
+    /// ISO 639-1 code such as "en" (ISO 639-3 when there is none), or "unknown"
+    pub language: String,
+    /// 0 to 1; 0 for "unknown"
+    pub confidence: f64,
+    /// The detector is confident enough to act on without asking
+    pub reliable: bool,
+}
+
+impl DetectedLanguage {
+    fn unknown() -> Self {
+        Self {
+            language: "unknown".to_string(),
+            confidence: 0.0,
+            reliable: false,
+        }
+    }
+}
+
+fn language_code(lang: Lang) -> &'static str {
+    TWO_LETTER_CODES
+        .iter()
+        .find(|(l, _)| *l == lang)
+        .map_or(lang.code(), |(_, code)| code)
+}
+
+/// The note's prose: the body without frontmatter, code blocks or inline code
+fn prose(content: &str) -> String {
+    let body = markdown::body(content);
+    let mut text = String::with_capacity(body.len());
+    let mut last = 0;
+    let mut code = markdown::code_ranges(body);
+    code.sort_unstable();
+    for (start, end) in code {
+        if start >= last {
+            text.push_str(&body[last..start]);
+            text.push('\n');
+        }
+        last = last.max(end);
+    }
+    text.push_str(&body[last.min(body.len())..]);
+    text
+}
+
+/// Most likely language of a note for picking a spell check dictionary. Notes with too
+/// little prose to tell are "unknown" rather than guessed.
+#[tauri::command]
+pub fn detect_language(content: String) -> Result<DetectedLanguage, IgneError> {
+    let text = prose(&content);
+    if text.chars().filter(|c| c.is_alphabetic()).count() < MIN_LETTERS {
+        return Ok(DetectedLanguage::unknown());
+    }
+
+    Ok(whatlang::detect(&text).map_or_else(DetectedLanguage::unknown, |info| DetectedLanguage {
+        language: language_code(info.lang()).to_string(),
+        confidence: info.confidence(),
+        reliable: info.is_reliable(),
+    }))
+}
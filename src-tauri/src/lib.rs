@@ -1,55 +1,228 @@
-use log::{info, debug, error, LevelFilter};
-use serde::Serialize;
+mod app_config;
+mod attachments;
+mod autosave;
+mod bookmarks;
+mod chunked_read;
+mod close_guard;
+mod color_scheme;
+mod crash_reports;
+mod css_snippets;
+mod diff;
+mod directory_size;
+mod disk_space;
+mod env_vars;
+mod error;
+mod file_associations;
+mod file_identity;
+mod file_type;
+mod folder_notes;
+mod formatting;
+mod fs_move;
+mod git;
+mod history;
+mod images;
+mod language;
+mod line_endings;
+mod links;
+mod locale;
+mod logging;
+mod markdown;
+mod markdown_ast;
+mod note_id;
+mod note_kind;
+mod note_links;
+mod note_move;
+mod note_titles;
+mod notifications;
+mod obsidian_import;
+mod os_trash;
+mod paths;
+mod performance_mode;
+mod recent_searches;
+mod relative_time;
+mod scope;
+mod search_index;
+mod snapshots;
+mod spellcheck;
+mod startup;
+mod tags;
+mod thumbnails;
+mod toc;
+mod transclusion;
+mod tree_decorations;
+mod undo_delete;
+mod vault;
+mod vault_search;
+mod vault_trash;
+mod watch_pause;
+mod wikilink_completion;
+mod window_state;
+mod write_lock;
+
+use log::{info, debug, error, warn};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager, State};
 use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri_plugin_opener::OpenerExt;
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
-use notify::{RecommendedWatcher, RecursiveMode, Watcher, Event, EventKind};
-
-/// Initialize logging based on build profile
-fn init_logging() {
-    let is_dev = cfg!(debug_assertions);
-
-    let mut builder = env_logger::Builder::new();
-
-    if is_dev {
-        // Dev mode: verbose logging to stderr
-        builder.filter_level(LevelFilter::Debug);
-        builder.format(|buf, record| {
-            writeln!(
-                buf,
-                "[{}] {} - {}:{} - {}",
-                record.level(),
-                record.target(),
-                record.file().unwrap_or("unknown"),
-                record.line().unwrap_or(0),
-                record.args()
-            )
-        });
-    } else {
-        // Prod mode: only warnings and errors
-        builder.filter_level(LevelFilter::Warn);
-        builder.format(|buf, record| {
-            writeln!(buf, "[{}] {}", record.level(), record.args())
-        });
+use notify::{PollWatcher, RecommendedWatcher, RecursiveMode, Watcher, Event, EventKind};
+use autosave::AutosaveState;
+use chunked_read::ChunkedReads;
+use close_guard::CloseGuard;
+use color_scheme::ColorSchemeState;
+use directory_size::DirectorySizeCache;
+use env_vars::EnvOverrides;
+use error::IgneError;
+use fs_move::{move_path, MoveStrategy};
+use line_endings::{LineStyle, LineStyles};
+use links::RewrittenLink;
+use note_kind::NoteKind;
+use note_links::NoteLinksCache;
+use note_titles::NoteTitleCache;
+use performance_mode::{ModeChanges, PerformanceMode};
+use paths::{long_path, validate_new_path, VaultPath};
+use scope::{ensure_in_scope, ScopeState};
+use search_index::SearchIndexCache;
+use startup::StartupState;
+use watch_pause::{Held, WatchPause};
+use window_state::WindowGeometryKeys;
+use write_lock::{with_write_lock, with_write_locks, WriteLocks};
+
+/// Watchers keyed by (window label, path). Each window owns its watchers and gets their
+/// events, so two vault windows can't stop or hear each other's.
+type WatchKey = (String, String);
+
+/// How a folder is watched. Native uses FSEvents/inotify/ReadDirectoryChangesW; polling
+/// rescans on an interval and is the only option that sees changes on NFS/SMB mounts.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchMode {
+    #[default]
+    Native,
+    Poll,
+    /// Not watched: the vault is in manual performance mode and refreshes on request
+    Off,
+}
+
+const DEFAULT_POLL_INTERVAL_MS: u64 = 1000;
+
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchOptions {
+    #[serde(default)]
+    mode: WatchMode,
+    /// Only used in poll mode; defaults to one second
+    #[serde(alias = "poll_interval_ms")]
+    poll_interval_ms: Option<u64>,
+}
+
+/// Backend of an active watch, as reported by `get_watch_info`
+#[derive(Serialize, Clone)]
+pub struct WatchInfo {
+    pub mode: WatchMode,
+    /// Set in poll mode
+    pub poll_interval_ms: Option<u64>,
+    /// Why native watching was abandoned for polling, e.g. the inotify limit was hit
+    pub degraded: Option<String>,
+}
+
+impl WatchInfo {
+    fn native() -> Self {
+        Self { mode: WatchMode::Native, poll_interval_ms: None, degraded: None }
+    }
+
+    fn poll(interval_ms: u64, degraded: Option<String>) -> Self {
+        Self { mode: WatchMode::Poll, poll_interval_ms: Some(interval_ms), degraded }
+    }
+
+    fn off() -> Self {
+        Self { mode: WatchMode::Off, poll_interval_ms: None, degraded: None }
+    }
+}
+
+struct ActiveWatch {
+    /// Kept alive here; dropping it stops the watch. `None` when watching is off.
+    watcher: Option<Box<dyn Watcher + Send>>,
+    info: WatchInfo,
+    recursive: bool,
+    /// What `watch_directory` was asked for, to set the watch up again when the vault's
+    /// performance mode changes
+    requested: Option<WatchOptions>,
+}
+
+impl ActiveWatch {
+    /// A single non-recursive native watch (one file's folder, the config dir)
+    fn native(watcher: RecommendedWatcher) -> Self {
+        Self {
+            watcher: Some(Box::new(watcher)),
+            info: WatchInfo::native(),
+            recursive: false,
+            requested: None,
+        }
     }
+}
 
-    // Allow RUST_LOG env var to override
-    builder.parse_env("RUST_LOG");
-    builder.init();
+type WatchHandler = Arc<dyn Fn(notify::Result<Event>) + Send + Sync>;
+
+/// `compare_contents` catches edits that keep the mtime, at the cost of reading every file
+/// each round
+fn poll_watcher(handler: &WatchHandler, interval_ms: u64, compare_contents: bool) -> notify::Result<PollWatcher> {
+    let handler = handler.clone();
+    let config = notify::Config::default()
+        .with_poll_interval(Duration::from_millis(interval_ms))
+        .with_compare_contents(compare_contents);
+    PollWatcher::new(move |res| handler(res), config)
+}
+
+/// inotify allows a fixed number of watched folders per user, and adding one past it
+/// fails with ENOSPC ("No space left on device")
+fn is_watch_limit_error(err: &notify::Error) -> bool {
+    match &err.kind {
+        notify::ErrorKind::MaxFilesWatch => true,
+        #[cfg(target_os = "linux")]
+        notify::ErrorKind::Io(e) => e.raw_os_error() == Some(libc::ENOSPC),
+        _ => false,
+    }
+}
 
-    info!("Logging initialized (dev={})", is_dev);
+/// `fs.inotify.max_user_watches`, on Linux
+fn max_user_watches() -> Option<u64> {
+    fs::read_to_string("/proc/sys/fs/inotify/max_user_watches")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+}
+
+fn watch_limit_message() -> String {
+    let limit = max_user_watches()
+        .map(|limit| format!("the system limit of {} watched folders", limit))
+        .unwrap_or_else(|| "the system limit of watched folders".to_string());
+    format!(
+        "This vault has more folders than {} (fs.inotify.max_user_watches), so it is \
+         checked for changes by polling instead. Raise the limit with sysctl to restore \
+         instant updates.",
+        limit
+    )
+}
+
+/// Payload of `watch-degraded`
+#[derive(Serialize, Clone)]
+struct WatchDegraded {
+    path: String,
+    reason: String,
+    max_user_watches: Option<u64>,
 }
 
 /// State for managing file watchers - allows proper cleanup
 pub struct WatcherState {
-    watchers: Arc<Mutex<HashMap<String, RecommendedWatcher>>>,
+    watchers: Arc<Mutex<HashMap<WatchKey, ActiveWatch>>>,
 }
 
 impl WatcherState {
@@ -58,6 +231,29 @@ impl WatcherState {
             watchers: Arc::new(Mutex::new(HashMap::new())),
         }
     }
+
+    /// Whether any window is watching `path`. A watch that's off (manual performance mode)
+    /// doesn't count, since nothing would report changes.
+    pub fn is_watching(&self, path: &str) -> bool {
+        self.watchers.lock().is_ok_and(|watchers| {
+            watchers
+                .iter()
+                .any(|((_, watched), watch)| watched == path && watch.watcher.is_some())
+        })
+    }
+
+    fn remove(&self, key: &WatchKey) {
+        if let Ok(mut watchers) = self.watchers.lock() {
+            watchers.remove(key);
+        }
+    }
+
+    /// Stop every watcher a window started
+    pub fn remove_window(&self, label: &str) {
+        if let Ok(mut watchers) = self.watchers.lock() {
+            watchers.retain(|(window, _), _| window != label);
+        }
+    }
 }
 
 impl Default for WatcherState {
@@ -73,7 +269,66 @@ pub struct FileEntry {
     is_dir: bool,
     size: u64,
     modified: u64,
+    /// Creation time in seconds; 0 where the filesystem doesn't record it
+    created: u64,
+    /// The name isn't valid Unicode, so `name` and `path` only approximate it and other
+    /// commands can't find the file until `sanitize_filename` renames it
+    name_invalid_unicode: bool,
+    /// What the file holds going by its name, so drawings and canvases get their own icon;
+    /// `None` for folders. Listing never reads files, so a drawing saved as plain `.md`
+    /// shows as markdown until `get_note_kind` looks inside.
+    kind: Option<NoteKind>,
     children: Option<Vec<FileEntry>>,
+    /// Entries directly inside a folder, set in recursive mode even past `max_depth`, so
+    /// the tree can show counts and load big folders lazily
+    children_count: Option<u64>,
+}
+
+/// File tree order for `read_directory`. Everything except `FoldersFirst` mixes folders
+/// and files; ties fall back to the name.
+#[derive(Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SortOrder {
+    NameAsc,
+    NameDesc,
+    ModifiedAsc,
+    ModifiedDesc,
+    SizeAsc,
+    SizeDesc,
+    CreatedAsc,
+    CreatedDesc,
+    /// Folders, then files, each alphabetically
+    #[default]
+    FoldersFirst,
+}
+
+fn sort_entries(entries: &mut [FileEntry], order: SortOrder) {
+    use std::cmp::Reverse;
+
+    // Keyed once per entry instead of lowercasing on every comparison
+    entries.sort_by_cached_key(|e| e.name.to_lowercase());
+    match order {
+        SortOrder::NameAsc => {}
+        SortOrder::NameDesc => entries.reverse(),
+        // Stable sorts keep the name order among equal keys
+        SortOrder::ModifiedAsc => entries.sort_by_key(|e| e.modified),
+        SortOrder::ModifiedDesc => entries.sort_by_key(|e| Reverse(e.modified)),
+        SortOrder::SizeAsc => entries.sort_by_key(|e| e.size),
+        SortOrder::SizeDesc => entries.sort_by_key(|e| Reverse(e.size)),
+        SortOrder::CreatedAsc => entries.sort_by_key(|e| e.created),
+        SortOrder::CreatedDesc => entries.sort_by_key(|e| Reverse(e.created)),
+        SortOrder::FoldersFirst => entries.sort_by_key(|e| !e.is_dir),
+    }
+}
+
+/// Seconds since the epoch, or 0 when the platform can't tell
+fn unix_secs(time: std::io::Result<std::time::SystemTime>) -> u64 {
+    time.map(|t| {
+        t.duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    })
+    .unwrap_or(0)
 }
 
 #[derive(Serialize, Clone)]
@@ -92,53 +347,191 @@ fn read_directory(
     path: String,
     recursive: Option<bool>,
     max_depth: Option<u32>,
-) -> Result<Vec<FileEntry>, String> {
+    sort_by: Option<SortOrder>,
+    extensions: Option<Vec<String>>,
+    app: AppHandle,
+) -> Result<Vec<FileEntry>, IgneError> {
     let path = PathBuf::from(&path);
+    ensure_in_scope(&app, &path)?;
+    let path = long_path(&path);
     let recursive = recursive.unwrap_or(true);
-    if recursive {
-        read_dir_recursive(&path, 0, max_depth.unwrap_or(u32::MAX))
+    let order = sort_by.unwrap_or_default();
+    let mut entries = if recursive {
+        read_dir_recursive(&path, 0, max_depth.unwrap_or(u32::MAX), order)?
     } else {
-        read_dir_shallow(&path)
+        read_dir_shallow(&path, order)?
+    };
+
+    if let Some(extensions) = extensions {
+        let extensions: Vec<String> = extensions
+            .iter()
+            .map(|ext| ext.trim_start_matches('.').to_lowercase())
+            .collect();
+        retain_extensions(&mut entries, &extensions);
     }
+    Ok(entries)
 }
 
-fn read_dir_shallow(path: &PathBuf) -> Result<Vec<FileEntry>, String> {
+/// Keep folders (so pickers stay navigable) and files with one of `extensions`, given
+/// lowercase without the dot. Folder `children_count`s still count every entry.
+fn retain_extensions(entries: &mut Vec<FileEntry>, extensions: &[String]) {
+    entries.retain_mut(|entry| {
+        if let Some(children) = &mut entry.children {
+            retain_extensions(children, extensions);
+        }
+        entry.is_dir
+            || Path::new(&entry.name)
+                .extension()
+                .is_some_and(|ext| extensions.contains(&ext.to_string_lossy().to_lowercase()))
+    });
+}
+
+/// Entries `read_directory` would list in `path`, without stat-ing them
+fn count_tree_children(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .map(|dir| {
+            dir.flatten()
+                .filter(|entry| {
+                    let name = entry.file_name();
+                    name != vault_trash::TRASH_DIR && name != history::BACKUP_DIR
+                })
+                .count() as u64
+        })
+        .unwrap_or(0)
+}
+
+#[derive(Serialize, Clone)]
+pub struct DirectoryPage {
+    entries: Vec<FileEntry>,
+    /// Entries in the whole folder
+    total: u64,
+}
+
+/// One page of a folder's direct entries, for virtualized trees over huge flat folders.
+/// The whole folder is sorted first so pages stay stable; `limit` defaults to the rest.
+#[tauri::command]
+fn read_directory_page(
+    path: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    sort_by: Option<SortOrder>,
+    app: AppHandle,
+) -> Result<DirectoryPage, IgneError> {
+    let path = PathBuf::from(&path);
+    ensure_in_scope(&app, &path)?;
+    let entries = read_dir_shallow(&long_path(&path), sort_by.unwrap_or_default())?;
+    let total = entries.len() as u64;
+    let entries = entries
+        .into_iter()
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+    Ok(DirectoryPage { entries, total })
+}
+
+/// Entries per `scan-entry` event
+const SCAN_BATCH_SIZE: usize = 200;
+
+#[derive(Serialize, Clone)]
+struct ScanBatch {
+    root: String,
+    entries: Vec<FileEntry>,
+}
+
+#[derive(Serialize, Clone)]
+struct ScanComplete {
+    root: String,
+    total: u64,
+}
+
+/// Walk a folder tree on a background thread, emitting `scan-entry` batches as entries
+/// are found and `scan-complete` at the end. Each folder's entries arrive together,
+/// filtered and sorted like `read_directory`, and always after the folder itself.
+#[tauri::command]
+fn scan_directory_streaming(path: String, app: AppHandle, window: tauri::WebviewWindow) -> Result<(), IgneError> {
+    let root = PathBuf::from(&path);
+    ensure_in_scope(&app, &root)?;
+    // Read the top level up front so a bad root is reported to the caller
+    let first_level = read_dir_shallow(&long_path(&root), SortOrder::FoldersFirst)?;
+
+    let label = window.label().to_string();
+    std::thread::spawn(move || stream_tree(&app, &label, &path, first_level));
+
+    Ok(())
+}
+
+/// Emit the tree under `root` below its already-read `first_level` as `scan-entry`
+/// batches and a final `scan-complete` to window `label`. Returns the number of entries.
+fn stream_tree(app: &AppHandle, label: &str, root: &str, first_level: Vec<FileEntry>) -> u64 {
+    let mut batch = Vec::with_capacity(SCAN_BATCH_SIZE);
+    let mut total = 0u64;
+    // Symlinked folders can form cycles
+    let mut visited = std::collections::HashSet::from([normalize_for_compare(Path::new(root))]);
+    let mut pending = vec![first_level];
+
+    while let Some(entries) = pending.pop() {
+        for entry in entries.iter().rev().filter(|e| e.is_dir) {
+            let dir = PathBuf::from(&entry.path);
+            if visited.insert(normalize_for_compare(&dir)) {
+                if let Ok(children) = read_dir_shallow(&dir, SortOrder::FoldersFirst) {
+                    pending.push(children);
+                }
+            }
+        }
+
+        for entry in entries {
+            batch.push(entry);
+            total += 1;
+            if batch.len() == SCAN_BATCH_SIZE {
+                let entries = std::mem::replace(&mut batch, Vec::with_capacity(SCAN_BATCH_SIZE));
+                let _ = app.emit_to(label, "scan-entry", ScanBatch { root: root.to_string(), entries });
+            }
+        }
+    }
+
+    if !batch.is_empty() {
+        let _ = app.emit_to(label, "scan-entry", ScanBatch { root: root.to_string(), entries: batch });
+    }
+    let _ = app.emit_to(label, "scan-complete", ScanComplete { root: root.to_string(), total });
+    total
+}
+
+fn read_dir_shallow(path: &PathBuf, order: SortOrder) -> Result<Vec<FileEntry>, IgneError> {
     let mut entries = vec![];
-    let dir = fs::read_dir(path).map_err(|e| e.to_string())?;
+    let dir = fs::read_dir(path).map_err(|e| IgneError::io(e, path))?;
 
     for entry in dir {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let file_name = entry.file_name().to_string_lossy().to_string();
+        let entry = entry?;
+        let raw_name = entry.file_name();
+        let file_name = raw_name.to_string_lossy().to_string();
+        // The in-vault trash and note backups have their own commands, not the file tree
+        if file_name == vault_trash::TRASH_DIR || file_name == history::BACKUP_DIR {
+            continue;
+        }
         let file_path = entry.path();
-        let metadata = entry.metadata().map_err(|e| e.to_string())?;
+        let metadata = entry.metadata().map_err(|e| IgneError::io(e, entry.path()))?;
         let is_dir = metadata.is_dir();
 
         let size = metadata.len();
-        let modified = metadata
-            .modified()
-            .map(|t| {
-                t.duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs()
-            })
-            .unwrap_or(0);
+        let modified = unix_secs(metadata.modified());
+        let created = unix_secs(metadata.created());
 
+        let kind = (!is_dir).then(|| note_kind::from_name(&file_name));
         entries.push(FileEntry {
             name: file_name,
             path: file_path.to_string_lossy().to_string(),
             is_dir,
             size,
             modified,
+            created,
+            name_invalid_unicode: raw_name.to_str().is_none(),
+            kind,
             children: None,
+            children_count: None,
         });
     }
 
-    // Sort: folders first, then alphabetically
-    entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
-        (true, false) => std::cmp::Ordering::Less,
-        (false, true) => std::cmp::Ordering::Greater,
-        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-    });
+    sort_entries(&mut entries, order);
 
     Ok(entries)
 }
@@ -147,103 +540,610 @@ fn read_dir_recursive(
     path: &PathBuf,
     depth: u32,
     max_depth: u32,
-) -> Result<Vec<FileEntry>, String> {
+    order: SortOrder,
+) -> Result<Vec<FileEntry>, IgneError> {
     if depth > max_depth {
         return Ok(vec![]);
     }
 
     let mut entries = vec![];
 
-    let dir = fs::read_dir(path).map_err(|e| e.to_string())?;
+    let dir = fs::read_dir(path).map_err(|e| IgneError::io(e, path))?;
 
     for entry in dir {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let file_name = entry.file_name().to_string_lossy().to_string();
+        let entry = entry?;
+        let raw_name = entry.file_name();
+        let file_name = raw_name.to_string_lossy().to_string();
+        // The in-vault trash and note backups have their own commands, not the file tree
+        if file_name == vault_trash::TRASH_DIR || file_name == history::BACKUP_DIR {
+            continue;
+        }
 
         let file_path = entry.path();
-        let metadata = entry.metadata().map_err(|e| e.to_string())?;
+        let metadata = entry.metadata().map_err(|e| IgneError::io(e, entry.path()))?;
         let is_dir = metadata.is_dir();
 
         let size = metadata.len();
-        let modified = metadata
-            .modified()
-            .map(|t| {
-                t.duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs()
-            })
-            .unwrap_or(0);
-
-        let children = if is_dir {
-            Some(read_dir_recursive(&file_path, depth + 1, max_depth).unwrap_or_default())
+        let modified = unix_secs(metadata.modified());
+        let created = unix_secs(metadata.created());
+
+        let (children, children_count) = if !is_dir {
+            (None, None)
+        } else if depth < max_depth {
+            let children = read_dir_recursive(&file_path, depth + 1, max_depth, order).unwrap_or_default();
+            let count = children.len() as u64;
+            (Some(children), Some(count))
         } else {
-            None
+            (Some(vec![]), Some(count_tree_children(&file_path)))
         };
 
+        let kind = (!is_dir).then(|| note_kind::from_name(&file_name));
         entries.push(FileEntry {
             name: file_name,
             path: file_path.to_string_lossy().to_string(),
             is_dir,
             size,
             modified,
+            created,
+            name_invalid_unicode: raw_name.to_str().is_none(),
+            kind,
             children,
+            children_count,
         });
     }
 
-    // Sort: folders first, then alphabetically
-    entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
-        (true, false) => std::cmp::Ordering::Less,
-        (false, true) => std::cmp::Ordering::Greater,
-        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-    });
+    sort_entries(&mut entries, order);
 
     Ok(entries)
 }
 
 #[tauri::command]
-fn read_file(path: String) -> Result<String, String> {
-    fs::read_to_string(&path).map_err(|e| e.to_string())
+fn read_file(
+    path: String,
+    max_bytes: Option<u64>,
+    lossy: Option<bool>,
+    app: AppHandle,
+    line_styles: State<'_, LineStyles>,
+) -> Result<String, IgneError> {
+    let content = read_text(&app, &path, max_bytes, lossy.unwrap_or(false))?;
+    line_styles.record(Path::new(&path), line_endings::detect(&content));
+    Ok(content)
+}
+
+/// Text of a file with its line-break style
+#[derive(Serialize, Clone)]
+pub struct StyledText {
+    pub content: String,
+    #[serde(flatten)]
+    pub style: LineStyle,
+}
+
+/// `read_file` plus the file's dominant line ending, whether it mixes endings and whether
+/// it ends with a newline, so the editor can show them and save them back unchanged
+#[tauri::command]
+fn read_file_with_style(
+    path: String,
+    max_bytes: Option<u64>,
+    lossy: Option<bool>,
+    app: AppHandle,
+    line_styles: State<'_, LineStyles>,
+) -> Result<StyledText, IgneError> {
+    let content = read_text(&app, &path, max_bytes, lossy.unwrap_or(false))?;
+    let style = line_endings::detect(&content);
+    line_styles.record(Path::new(&path), style);
+    Ok(StyledText { content, style })
+}
+
+/// The file behind `path` as it's named on disk (see `paths::find_on_disk`), else `path`
+/// itself so the read fails with `NOT_FOUND` for it
+fn on_disk(path: &str) -> PathBuf {
+    paths::find_on_disk(Path::new(path)).unwrap_or_else(|| PathBuf::from(path))
+}
+
+/// Read a text file for the editor. Invalid UTF-8 fails with `INVALID_UTF8` pointing at the
+/// first bad byte, unless `lossy` asks for replacement characters instead.
+fn read_text(app: &AppHandle, path: &str, max_bytes: Option<u64>, lossy: bool) -> Result<String, IgneError> {
+    ensure_in_scope(app, Path::new(path))?;
+    let target = long_path(&on_disk(path));
+    check_read_size(&target, path, max_bytes.unwrap_or(DEFAULT_MAX_TEXT_READ))?;
+    let file_type = file_type::detect(&target).map_err(|e| IgneError::io(e, path))?;
+    if file_type.is_binary {
+        return Err(IgneError::binary_file(&file_type.mime, path));
+    }
+    let bytes = fs::read(target).map_err(|e| IgneError::io(e, path))?;
+    match String::from_utf8(bytes) {
+        Ok(content) => Ok(content),
+        Err(e) if lossy => Ok(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+        Err(e) => {
+            let offset = e.utf8_error().valid_up_to();
+            let line = e.as_bytes()[..offset].iter().filter(|b| **b == b'\n').count() + 1;
+            Err(IgneError::invalid_utf8(path, offset as u64, line as u64))
+        }
+    }
+}
+
+/// Read limits so a stray multi-gigabyte file can't hang the app; callers pass their own
+/// `max_bytes` (0 for no limit) to open a file anyway
+const DEFAULT_MAX_TEXT_READ: u64 = 50 * 1024 * 1024;
+const DEFAULT_MAX_BINARY_READ: u64 = 200 * 1024 * 1024;
+
+fn check_read_size(target: &Path, path: &str, limit: u64) -> Result<(), IgneError> {
+    if limit == 0 {
+        return Ok(());
+    }
+    let size = fs::metadata(target).map_err(|e| IgneError::io(e, path))?.len();
+    if size > limit {
+        return Err(IgneError::file_too_large(path, size, limit));
+    }
+    Ok(())
 }
 
+/// Write a text file. `line_ending` ("lf", "crlf" or "preserve" for the style recorded
+/// when the file was read) and `ensure_trailing_newline` are applied first; without them
+/// the content is written as given.
 #[tauri::command]
-fn write_file(path: String, content: String) -> Result<(), String> {
-    fs::write(&path, content).map_err(|e| e.to_string())
+async fn write_file(
+    path: String,
+    content: String,
+    line_ending: Option<formatting::LineEnding>,
+    ensure_trailing_newline: Option<bool>,
+    app: AppHandle,
+    line_styles: State<'_, LineStyles>,
+) -> Result<(), IgneError> {
+    ensure_in_scope(&app, Path::new(&path))?;
+    let path_obj = match paths::find_on_disk(Path::new(&path)) {
+        Some(existing) => existing,
+        None => validate_new_path(Path::new(&path))?,
+    };
+    let content = line_endings::apply(
+        content,
+        line_ending,
+        ensure_trailing_newline,
+        line_styles.get(Path::new(&path)),
+    );
+    with_write_lock(&app, &path_obj, || {
+        atomic_write(&path_obj, content.as_bytes())?;
+        if is_markdown_file(&path) {
+            snapshots::record_snapshot_logged(&app, &path_obj, content.as_bytes());
+        }
+        Ok(())
+    })
+    .await?;
+    line_styles.record(Path::new(&path), line_endings::detect(&content));
+    Ok(())
 }
 
+/// Whether the app can save to `path`. Read-only files are `Ok(false)` so the editor can
+/// show a read-only indicator; a file that doesn't exist yet is writable if its folder is.
 #[tauri::command]
-fn file_exists(path: String) -> bool {
-    std::path::Path::new(&path).exists()
+fn check_path_writable(path: String, app: AppHandle) -> Result<bool, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+    let target = long_path(&path_obj);
+
+    match fs::metadata(&target) {
+        Ok(metadata) if metadata.is_dir() => return Ok(vault::probe_writable(&target)),
+        Ok(metadata) if metadata.permissions().readonly() => return Ok(false),
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(target.parent().is_some_and(vault::probe_writable));
+        }
+        Err(e) => return Err(IgneError::io(e, &path)),
+    }
+
+    // Permission bits miss ACLs and read-only mounts; opening without truncating is harmless
+    match fs::OpenOptions::new().write(true).open(&target) {
+        Ok(_) => Ok(true),
+        Err(e) if matches!(
+            e.kind(),
+            std::io::ErrorKind::PermissionDenied | std::io::ErrorKind::ReadOnlyFilesystem
+        ) => Ok(false),
+        Err(e) => Err(IgneError::io(e, &path)),
+    }
+}
+
+/// Suffix of the temp files `atomic_write` renames into place
+const ATOMIC_TMP_SUFFIX: &str = ".igne-tmp";
+
+/// Files the app keeps for itself (trash, backups, in-flight temp files) that the file tree
+/// and watcher should ignore
+fn is_internal_path(path: &Path) -> bool {
+    vault_trash::is_in_vault_trash(path)
+        || path.components().any(|c| c.as_os_str() == history::BACKUP_DIR)
+        || path.to_string_lossy().ends_with(ATOMIC_TMP_SUFFIX)
+}
+
+/// Write via a temp file in the same directory and rename over the target, so a crash
+/// mid-write never leaves a truncated note behind
+fn atomic_write(path: &Path, data: &[u8]) -> Result<(), IgneError> {
+    let target = long_path(path);
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| IgneError::invalid_path(format!("Not a file path: {}", path.display()), path))?;
+    let tmp_path = target.with_file_name(format!(".{}{}", file_name.to_string_lossy(), ATOMIC_TMP_SUFFIX));
+
+    let write_tmp = || -> std::io::Result<()> {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(data)?;
+        file.sync_all()
+    };
+    if let Err(e) = write_tmp().and_then(|_| fs::rename(&tmp_path, &target)) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(IgneError::io(e, path));
+    }
+
+    Ok(())
+}
+
+/// Run blocking work (a vault walk, an image decode, git) on the blocking pool so an async
+/// command doesn't hold up a runtime thread
+async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> Result<T, IgneError> + Send + 'static,
+) -> Result<T, IgneError> {
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| IgneError::other(format!("Background task failed: {}", e)))?
 }
 
 #[tauri::command]
-fn rename_file(old_path: String, new_path: String) -> Result<(), String> {
-    fs::rename(&old_path, &new_path).map_err(|e| e.to_string())
+fn file_exists(path: String, app: AppHandle) -> bool {
+    // Out-of-scope paths report as missing rather than leaking their existence
+    ensure_in_scope(&app, Path::new(&path)).is_ok() && paths::find_on_disk(Path::new(&path)).is_some()
+}
+
+/// Minimal view of `<app_data_dir>/vaults.json` (owned by the frontend VaultsStore)
+#[derive(Deserialize)]
+struct VaultsRegistryFile {
+    #[serde(default)]
+    vaults: Vec<VaultRegistryEntry>,
+    /// Path of the vault opened last; v1 registries call it `lastOpenedVault`
+    #[serde(default, rename = "lastOpened", alias = "lastOpenedVault")]
+    last_opened: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct VaultRegistryEntry {
+    path: String,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+}
+
+fn read_vault_registry(app: &AppHandle) -> Option<VaultsRegistryFile> {
+    let app_data = app.path().app_data_dir().ok()?;
+    let content = fs::read_to_string(app_data.join("vaults.json")).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Read the registered vault roots. Entries without a `type` predate v2 and are vaults.
+fn registered_vault_roots(app: &AppHandle) -> Vec<PathBuf> {
+    let Some(registry) = read_vault_registry(app) else {
+        return vec![];
+    };
+
+    registry
+        .vaults
+        .into_iter()
+        .filter(|v| matches!(v.kind.as_deref(), None | Some("vault") | Some("folder")))
+        .map(|v| PathBuf::from(v.path))
+        .collect()
+}
+
+/// The vault the frontend will reopen: the registry's last opened entry, when it's still
+/// a registered vault or folder
+fn last_opened_vault(app: &AppHandle) -> Option<PathBuf> {
+    let registry = read_vault_registry(app)?;
+    let last = registry.last_opened?;
+    registry
+        .vaults
+        .iter()
+        .any(|v| v.path == last && matches!(v.kind.as_deref(), None | Some("vault") | Some("folder")))
+        .then(|| PathBuf::from(last))
+}
+
+/// The innermost registered vault holding `path`
+fn containing_vault(app: &AppHandle, path: &Path) -> Option<PathBuf> {
+    let resolved = scope::resolve(path);
+    registered_vault_roots(app)
+        .into_iter()
+        .filter(|root| resolved.starts_with(scope::resolve(root)))
+        .max_by_key(|root| root.components().count())
+}
+
+/// Canonicalize when possible so `a/../b` and symlinks compare equal
+fn normalize_for_compare(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Refuse destructive operations on the vault root, app data dir, home dir or `.obsidian`.
+/// `force` only lifts the `.obsidian` restriction (e.g. for a "reset vault config" action).
+fn check_protected_path(app: &AppHandle, path: &Path, force: bool) -> Result<(), IgneError> {
+    let target = normalize_for_compare(path);
+
+    if registered_vault_roots(app)
+        .iter()
+        .any(|root| normalize_for_compare(root) == target)
+    {
+        return Err(IgneError::protected(format!("Refusing to modify vault root: {}", path.display()), path));
+    }
+
+    if let Ok(app_data) = app.path().app_data_dir() {
+        if normalize_for_compare(&app_data) == target {
+            return Err(IgneError::protected(format!("Refusing to modify app data directory: {}", path.display()), path));
+        }
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        if normalize_for_compare(&home) == target {
+            return Err(IgneError::protected(format!("Refusing to modify home directory: {}", path.display()), path));
+        }
+    }
+
+    if !force && target.file_name().map(|n| n == ".obsidian").unwrap_or(false) {
+        return Err(IgneError::protected(format!("Refusing to modify vault config directory: {}", path.display()), path));
+    }
+
+    Ok(())
+}
+
+/// Reject moving a directory into itself or one of its descendants
+fn check_not_into_descendant(source: &Path, destination: &Path) -> Result<(), IgneError> {
+    if !long_path(source).is_dir() {
+        return Ok(());
+    }
+
+    let source = normalize_for_compare(source);
+    // The destination usually doesn't exist yet, so resolve through its parent
+    let destination = match (destination.parent(), destination.file_name()) {
+        (Some(parent), Some(name)) => normalize_for_compare(parent).join(name),
+        _ => normalize_for_compare(destination),
+    };
+
+    if destination != source && destination.starts_with(&source) {
+        return Err(IgneError::invalid_path(
+            format!(
+                "Cannot move a folder into itself: {} -> {}",
+                source.display(),
+                destination.display()
+            ),
+            &destination,
+        ));
+    }
+
+    Ok(())
+}
+
+/// The destination to move to. Only a name that actually changes is validated (and has
+/// trailing dots and spaces stripped), so notes with names created elsewhere can still be
+/// moved between folders.
+fn validate_renamed(source: &Path, destination: &Path) -> Result<PathBuf, IgneError> {
+    if source.file_name() == destination.file_name() {
+        return Ok(destination.to_path_buf());
+    }
+    validate_new_path(destination)
 }
 
 #[tauri::command]
-fn delete_file(path: String) -> Result<(), String> {
-    if PathBuf::from(&path).is_dir() {
-        fs::remove_dir_all(&path).map_err(|e| e.to_string())
-    } else {
-        fs::remove_file(&path).map_err(|e| e.to_string())
+async fn rename_file(
+    old_path: String,
+    new_path: String,
+    force: Option<bool>,
+    overwrite: Option<bool>,
+    app: AppHandle,
+) -> Result<MoveStrategy, IgneError> {
+    ensure_in_scope(&app, Path::new(&old_path))?;
+    ensure_in_scope(&app, Path::new(&new_path))?;
+    check_protected_path(&app, Path::new(&old_path), force.unwrap_or(false))?;
+    check_not_into_descendant(Path::new(&old_path), Path::new(&new_path))?;
+    let new = &validate_renamed(Path::new(&old_path), Path::new(&new_path))?;
+    let old = Path::new(&old_path);
+    let strategy = with_write_locks(&app, &[old, new], || move_path(old, new, overwrite.unwrap_or(false))).await?;
+    bookmarks::path_moved(&app, old, new).await;
+    Ok(strategy)
+}
+
+/// Rename a file or folder whose name isn't valid Unicode (listed with
+/// `name_invalid_unicode`) to a close valid name, given the lossy path the listing
+/// reported. Returns the new path; a path that's already valid is returned unchanged.
+#[tauri::command]
+async fn sanitize_filename(path: String, app: AppHandle) -> Result<String, IgneError> {
+    ensure_in_scope(&app, Path::new(&path))?;
+    let Some(source) = paths::find_lossy_entry(Path::new(&path))? else {
+        if long_path(Path::new(&path)).exists() {
+            return Ok(path);
+        }
+        return Err(IgneError::not_found(format!("File not found: {}", path), &path));
+    };
+    let name = source.file_name().map(paths::utf8_approximation).unwrap_or_default();
+    let destination = source.with_file_name(&name);
+    if long_path(&destination).exists() {
+        return Err(IgneError::already_exists(
+            format!("Can't rename to {}: a file with that name exists", name),
+            &destination,
+        ));
     }
+
+    check_protected_path(&app, &source, false)?;
+    with_write_locks(&app, &[&source, &destination], || move_path(&source, &destination, false)).await?;
+    Ok(destination.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-fn create_directory(path: String) -> Result<(), String> {
-    fs::create_dir_all(&path).map_err(|e| e.to_string())
+async fn delete_file(path: String, force: Option<bool>, app: AppHandle) -> Result<(), IgneError> {
+    delete_path(&app, &path, force.unwrap_or(false)).await
 }
 
+async fn delete_path(app: &AppHandle, path: &str, force: bool) -> Result<(), IgneError> {
+    ensure_in_scope(app, Path::new(path))?;
+    check_protected_path(app, Path::new(path), force)?;
+    let target = long_path(Path::new(path));
+    with_write_lock(app, Path::new(path), || {
+        if target.is_dir() {
+            fs::remove_dir_all(&target).map_err(|e| IgneError::io(e, path))
+        } else {
+            fs::remove_file(&target).map_err(|e| IgneError::io(e, path))
+        }
+    })
+    .await?;
+    bookmarks::path_deleted(app, Path::new(path)).await;
+    Ok(())
+}
+
+/// Delete a folder unless one of `open_files` (the notes open in editors) is inside it,
+/// in which case a conflict error lists them so the user can be asked first
 #[tauri::command]
-fn move_file(source: String, destination: String) -> Result<(), String> {
-    fs::rename(&source, &destination).map_err(|e| e.to_string())
+async fn safe_delete_directory(path: String, open_files: Vec<String>, app: AppHandle) -> Result<(), IgneError> {
+    let dir = Path::new(&path);
+    ensure_in_scope(&app, dir)?;
+    if !long_path(dir).is_dir() {
+        return Err(IgneError::not_a_directory(format!("Not a directory: {}", path), &path));
+    }
+
+    let dir = normalize_for_compare(dir);
+    let open_inside: Vec<&str> = open_files
+        .iter()
+        .filter(|file| normalize_for_compare(Path::new(file)).starts_with(&dir))
+        .map(String::as_str)
+        .collect();
+    if !open_inside.is_empty() {
+        return Err(IgneError::conflict(
+            format!("Folder contains open notes: {}", open_inside.join(", ")),
+            &path,
+        ));
+    }
+
+    delete_path(&app, &path, false).await
+}
+
+/// Outcome of one item of `delete_files` or `move_files`, keyed by the source path
+#[derive(Serialize, Clone)]
+pub struct BatchItemResult {
+    pub path: String,
+    pub error: Option<IgneError>,
+}
+
+/// Delete several files or folders in one call. Every path is attempted even when an
+/// earlier one fails, and each gets its own result in input order.
+#[tauri::command]
+async fn delete_files(paths: Vec<String>, app: AppHandle) -> Result<Vec<BatchItemResult>, IgneError> {
+    let mut results = Vec::with_capacity(paths.len());
+    let mut deleted: Vec<PathBuf> = vec![];
+
+    for path in paths {
+        // Selecting a folder and something inside it is fine: the item went with the folder
+        let already_gone = deleted.iter().any(|dir| Path::new(&path).starts_with(dir));
+        let error = if already_gone {
+            None
+        } else {
+            delete_path(&app, &path, false).await.err()
+        };
+        if error.is_none() {
+            deleted.push(PathBuf::from(&path));
+        }
+        results.push(BatchItemResult { path, error });
+    }
+
+    Ok(results)
+}
+
+#[tauri::command]
+fn create_directory(path: String, app: AppHandle) -> Result<(), IgneError> {
+    ensure_in_scope(&app, Path::new(&path))?;
+    let mut target = long_path(Path::new(&path));
+    if !target.exists() {
+        target = long_path(&validate_new_path(Path::new(&path))?);
+    }
+    fs::create_dir_all(&target).map_err(|e| IgneError::io(e, &path))
+}
+
+#[tauri::command]
+async fn move_file(
+    source: String,
+    destination: String,
+    force: Option<bool>,
+    overwrite: Option<bool>,
+    app: AppHandle,
+) -> Result<MoveStrategy, IgneError> {
+    move_checked(&app, &source, &destination, force.unwrap_or(false), overwrite.unwrap_or(false)).await
+}
+
+async fn move_checked(
+    app: &AppHandle,
+    source: &str,
+    destination: &str,
+    force: bool,
+    overwrite: bool,
+) -> Result<MoveStrategy, IgneError> {
+    let (from, to) = (Path::new(source), Path::new(destination));
+    ensure_in_scope(app, from)?;
+    ensure_in_scope(app, to)?;
+    check_protected_path(app, from, force)?;
+    check_not_into_descendant(from, to)?;
+    let to = &validate_renamed(from, to)?;
+    let strategy = with_write_locks(app, &[from, to], || move_path(from, to, overwrite)).await?;
+    bookmarks::path_moved(app, from, to).await;
+    Ok(strategy)
+}
+
+/// Move several files or folders, given as `(source, destination)` pairs, in one call.
+/// Every move is attempted even when an earlier one fails; existing destinations are
+/// never overwritten.
+#[tauri::command]
+async fn move_files(
+    moves: Vec<(String, String)>,
+    app: AppHandle,
+) -> Result<Vec<BatchItemResult>, IgneError> {
+    let mut results = Vec::with_capacity(moves.len());
+    for (source, destination) in moves {
+        let error = move_checked(&app, &source, &destination, false, false).await.err();
+        results.push(BatchItemResult { path: source, error });
+    }
+    Ok(results)
+}
+
+/// Move a note and rewrite its relative attachment/markdown links so they still resolve
+/// from the new location. Returns the links that were rewritten.
+#[tauri::command]
+async fn move_note_fix_links(
+    source: String,
+    destination: String,
+    vault_root: String,
+    app: AppHandle,
+) -> Result<Vec<RewrittenLink>, IgneError> {
+    let source_path = PathBuf::from(&source);
+    let destination_path = PathBuf::from(&destination);
+    ensure_in_scope(&app, &source_path)?;
+    ensure_in_scope(&app, &destination_path)?;
+    check_protected_path(&app, &source_path, false)?;
+
+    // Both files stay locked from the read until the rewritten content lands, so a save
+    // racing the move can't be lost
+    let rewritten = with_write_locks(&app, &[&source_path, &destination_path], || {
+        let content =
+            fs::read_to_string(long_path(&source_path)).map_err(|e| IgneError::io(e, &source_path))?;
+
+        let old_dir = source_path.parent().unwrap_or(Path::new(""));
+        let new_dir = destination_path.parent().unwrap_or(Path::new(""));
+        let (new_content, rewritten) =
+            links::rewrite_relative_links(&content, old_dir, new_dir, Path::new(&vault_root));
+
+        move_path(&source_path, &destination_path, false)?;
+        if !rewritten.is_empty() {
+            atomic_write(&destination_path, new_content.as_bytes())?;
+        }
+
+        Ok(rewritten)
+    })
+    .await?;
+    bookmarks::path_moved(&app, &source_path, &destination_path).await;
+    Ok(rewritten)
 }
 
 /// Get file metadata without reading content
 #[tauri::command]
-fn stat_path(path: String) -> Result<FileMetadata, String> {
+fn stat_path(path: String, app: AppHandle) -> Result<FileMetadata, IgneError> {
     let path_obj = PathBuf::from(&path);
-    let metadata = fs::metadata(&path_obj);
+    ensure_in_scope(&app, &path_obj)?;
+    let metadata = fs::metadata(long_path(&path_obj));
 
     let name = path_obj
         .file_name()
@@ -285,72 +1185,628 @@ fn stat_path(path: String) -> Result<FileMetadata, String> {
 
 /// Read binary file (for images, etc.)
 #[tauri::command]
-fn read_file_binary(path: String) -> Result<Vec<u8>, String> {
-    fs::read(&path).map_err(|e| e.to_string())
+fn read_file_binary(path: String, max_bytes: Option<u64>, app: AppHandle) -> Result<Vec<u8>, IgneError> {
+    ensure_in_scope(&app, Path::new(&path))?;
+    let target = long_path(&on_disk(&path));
+    check_read_size(&target, &path, max_bytes.unwrap_or(DEFAULT_MAX_BINARY_READ))?;
+    fs::read(target).map_err(|e| IgneError::io(e, &path))
 }
 
 /// Write binary file (for images, etc.)
 #[tauri::command]
-fn write_file_binary(path: String, data: Vec<u8>) -> Result<(), String> {
-    fs::write(&path, data).map_err(|e| e.to_string())
+async fn write_file_binary(path: String, data: Vec<u8>, app: AppHandle) -> Result<(), IgneError> {
+    ensure_in_scope(&app, Path::new(&path))?;
+    let path_obj = match paths::find_on_disk(Path::new(&path)) {
+        Some(existing) => existing,
+        None => validate_new_path(Path::new(&path))?,
+    };
+    with_write_lock(&app, &path_obj, || atomic_write(&path_obj, &data)).await
 }
 
-/// Watch a directory for changes and emit events to the frontend
-/// This is more efficient than polling and provides real-time updates
+/// Show a file or folder selected in Finder / Explorer / the Linux file manager
+#[tauri::command]
+fn reveal_in_file_manager(path: String, app: AppHandle) -> Result<(), IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+    if !long_path(&path_obj).exists() {
+        return Err(IgneError::not_found(format!("Path does not exist: {}", path), &path));
+    }
+
+    if let Err(e) = app.opener().reveal_item_in_dir(&path_obj) {
+        debug!("Opener reveal failed for {}, using platform fallback: {}", path, e);
+        reveal_fallback(&app, &path_obj).map_err(|e| IgneError::io(e, &path))?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn reveal_fallback(app: &AppHandle, path: &Path) -> std::io::Result<()> {
+    env_vars::command(app, "open").arg("-R").arg(path).spawn().map(|_| ())
+}
+
+#[cfg(target_os = "windows")]
+fn reveal_fallback(app: &AppHandle, path: &Path) -> std::io::Result<()> {
+    let mut select = std::ffi::OsString::from("/select,");
+    select.push(path);
+    env_vars::command(app, "explorer").arg(select).spawn().map(|_| ())
+}
+
+/// No portable way to select an item, so open the containing folder
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn reveal_fallback(app: &AppHandle, path: &Path) -> std::io::Result<()> {
+    let dir = if path.is_dir() { path } else { path.parent().unwrap_or(path) };
+    env_vars::command(app, "xdg-open").arg(dir).spawn().map(|_| ())
+}
+
+/// Set the title bar text of a window, e.g. "My Note • Igne" while the note is unsaved
+#[tauri::command]
+fn set_window_title(label: String, title: String, app: AppHandle) -> Result<(), IgneError> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| IgneError::invalid_input(format!("No window with label: {}", label)))?;
+    window
+        .set_title(&title)
+        .map_err(|e| IgneError::other(format!("Failed to set window title: {}", e)))
+}
+
+/// Number on the macOS dock icon, e.g. for due reminders; `None` or 0 clears it. A no-op
+/// on other platforms.
+#[tauri::command]
+fn set_badge_count(count: Option<u32>, app: AppHandle) -> Result<(), IgneError> {
+    #[cfg(target_os = "macos")]
+    {
+        // The dock tile belongs to the app, so any window can set it
+        if let Some(window) = app.webview_windows().into_values().next() {
+            window
+                .set_badge_count(count.filter(|c| *c > 0).map(i64::from))
+                .map_err(|e| IgneError::other(format!("Failed to set badge count: {}", e)))?;
+        }
+    }
+    #[cfg(not(target_os = "macos"))]
+    let _ = (count, app);
+    Ok(())
+}
+
+/// Title of a window with no note open
+const APP_TITLE: &str = "Igne";
+
+const MAIN_WINDOW: &str = "main";
+
+/// Label of the window focused last, so menu items and the global shortcut act on it
+/// rather than always on "main"
+struct FocusedWindow(Mutex<String>);
+
+impl FocusedWindow {
+    fn new() -> Self {
+        Self(Mutex::new(MAIN_WINDOW.to_string()))
+    }
+}
+
+/// The focused window's label, falling back to "main" when it has since closed
+fn focused_window_label(app: &AppHandle) -> String {
+    let label = app
+        .state::<FocusedWindow>()
+        .0
+        .lock()
+        .map(|label| label.clone())
+        .unwrap_or_else(|_| MAIN_WINDOW.to_string());
+    if app.get_webview_window(&label).is_some() {
+        label
+    } else {
+        MAIN_WINDOW.to_string()
+    }
+}
+
+static NEXT_WINDOW_ID: AtomicU32 = AtomicU32::new(1);
+
+/// What a new window should open, read by the frontend from `window.__IGNE_INIT__`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WindowInit {
+    vault_path: Option<String>,
+    file: Option<String>,
+}
+
+/// Open another app window, e.g. for a second vault side by side. Returns the new
+/// window's label. Async so window creation doesn't deadlock on Windows.
+#[tauri::command]
+async fn open_new_window(
+    vault_path: Option<String>,
+    file: Option<String>,
+    app: AppHandle,
+) -> Result<String, IgneError> {
+    for path in vault_path.iter().chain(file.iter()) {
+        ensure_in_scope(&app, Path::new(path))?;
+    }
+    let geometry_key = vault_path.as_ref().map(|vault| format!("vault:{}", vault));
+    build_window(&app, &WindowInit { vault_path, file }, (1200.0, 800.0), geometry_key)
+}
+
+/// Create an app window with a fresh `window-<n>` label and return the label. The window
+/// reopens with the geometry last saved under `geometry_key`, if any.
+fn build_window(
+    app: &AppHandle,
+    init: &WindowInit,
+    (width, height): (f64, f64),
+    geometry_key: Option<String>,
+) -> Result<String, IgneError> {
+    let label = loop {
+        let label = format!("window-{}", NEXT_WINDOW_ID.fetch_add(1, Ordering::Relaxed));
+        if app.get_webview_window(&label).is_none() {
+            break label;
+        }
+    };
+    let init = serde_json::to_string(init)?;
+
+    let builder = tauri::WebviewWindowBuilder::new(app, &label, tauri::WebviewUrl::App("index.html".into()))
+        .title(APP_TITLE)
+        .inner_size(width, height)
+        .visible(false)
+        .initialization_script(format!("window.__IGNE_INIT__ = {};", init));
+    #[cfg(target_os = "macos")]
+    let builder = builder
+        .hidden_title(true)
+        .title_bar_style(tauri::TitleBarStyle::Overlay)
+        .traffic_light_position(tauri::LogicalPosition::new(20.0, 16.0));
+
+    let window = builder
+        .build()
+        .map_err(|e| IgneError::other(format!("Failed to open window: {}", e)))?;
+    if let Some(key) = geometry_key {
+        window_state::restore(app, &window, &key);
+    }
+    let _ = window.show();
+    Ok(label)
+}
+
+/// Windows showing a single popped-out note, by note path, so opening the same note
+/// again focuses its window
+struct NoteWindows(Mutex<HashMap<PathBuf, String>>);
+
+impl NoteWindows {
+    fn new() -> Self {
+        Self(Mutex::new(HashMap::new()))
+    }
+
+    fn remove_window(&self, label: &str) {
+        if let Ok(mut windows) = self.0.lock() {
+            windows.retain(|_, window| window != label);
+        }
+    }
+}
+
+/// Pop a note out into its own window, or focus the window already showing it. The
+/// window's init payload carries the note and the vault containing it. Returns the
+/// window's label.
+#[tauri::command]
+async fn open_note_window(
+    path: String,
+    app: AppHandle,
+    note_windows: State<'_, NoteWindows>,
+) -> Result<String, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+    if !long_path(&path_obj).is_file() {
+        return Err(IgneError::not_found(format!("Note not found: {}", path), &path));
+    }
+
+    let key = normalize_for_compare(&path_obj);
+    let existing = note_windows.0.lock()?.get(&key).cloned();
+    if let Some(window) = existing.and_then(|label| app.get_webview_window(&label)) {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        return Ok(window.label().to_string());
+    }
+
+    let vault_path = containing_vault(&app, &path_obj).map(|root| root.to_string_lossy().to_string());
+    let geometry_key = format!("note:{}", key.display());
+    let label = build_window(&app, &WindowInit { vault_path, file: Some(path) }, (800.0, 700.0), Some(geometry_key))?;
+    note_windows.0.lock()?.insert(key, label.clone());
+    Ok(label)
+}
+
+/// Point a window at the note being edited: the title becomes "Note — Vault" (with a
+/// leading dot while unsaved), and on macOS the titlebar gets the file's proxy icon and
+/// the close button shows the edited state. `None` resets to the plain app title.
+/// Cheap enough to call on every tab switch; the last call wins.
+#[tauri::command]
+fn set_active_document(
+    window_label: String,
+    path: Option<String>,
+    dirty: bool,
+    app: AppHandle,
+) -> Result<(), IgneError> {
+    let window = app
+        .get_webview_window(&window_label)
+        .ok_or_else(|| IgneError::invalid_input(format!("No window with label: {}", window_label)))?;
+    let path = path.map(PathBuf::from);
+    if let Some(path) = &path {
+        ensure_in_scope(&app, path)?;
+    }
+
+    let title = match &path {
+        Some(path) => {
+            let note = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let vault = containing_vault(&app, path).and_then(|root| root.file_name().map(|n| n.to_string_lossy().to_string()));
+            let title = match vault {
+                Some(vault) => format!("{} — {}", note, vault),
+                None => note,
+            };
+            // macOS shows unsaved changes in the close button instead
+            if dirty && !cfg!(target_os = "macos") {
+                format!("• {}", title)
+            } else {
+                title
+            }
+        }
+        None => APP_TITLE.to_string(),
+    };
+    window
+        .set_title(&title)
+        .map_err(|e| IgneError::other(format!("Failed to set window title: {}", e)))?;
+
+    #[cfg(target_os = "macos")]
+    set_represented_file(&window, path, dirty)?;
+
+    Ok(())
+}
+
+/// Proxy icon and edited state of the macOS titlebar. AppKit must be used from the main
+/// thread, so this is queued there.
+#[cfg(target_os = "macos")]
+fn set_represented_file(
+    window: &tauri::WebviewWindow,
+    path: Option<PathBuf>,
+    dirty: bool,
+) -> Result<(), IgneError> {
+    use objc2_app_kit::NSWindow;
+    use objc2_foundation::NSString;
+
+    let target = window.clone();
+    window
+        .run_on_main_thread(move || {
+            let Ok(ns_window) = target.ns_window() else {
+                return;
+            };
+            // An empty filename removes the proxy icon
+            let file = path
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+            unsafe {
+                let ns_window = &*(ns_window as *const NSWindow);
+                ns_window.setRepresentedFilename(&NSString::from_str(&file));
+                ns_window.setDocumentEdited(dirty);
+            }
+        })
+        .map_err(|e| IgneError::other(format!("Failed to update window: {}", e)))
+}
+
+/// Payload of `fs-change`: the watched folder and the changed paths under it
+#[derive(Serialize, Clone)]
+struct FsChangeEvent {
+    root: String,
+    changes: Vec<VaultPath>,
+}
+
+/// Emit `fs-change` for `paths` under the watched folder `root` to one window
+fn emit_fs_change(app: &AppHandle, label: &str, root: &str, paths: &[PathBuf]) {
+    let changes = paths
+        .iter()
+        .filter_map(|p| VaultPath::new(Path::new(root), p).ok())
+        .collect();
+    let _ = app.emit_to(label, "fs-change", FsChangeEvent { root: root.to_string(), changes });
+}
+
+/// Payload of `watch-lost`, sent when a watched folder disappears
+#[derive(Serialize, Clone)]
+struct WatchLost {
+    path: String,
+    reason: String,
+}
+
+/// Watch a directory for changes and emit events to the calling window
+/// This is more efficient than polling and provides real-time updates.
+/// If the folder is deleted or unmounted the watcher is dropped and `watch-lost` is
+/// emitted; calling this again once the folder is back resumes watching. The vault's
+/// performance mode can turn native watching into slow polling or switch it off.
 #[tauri::command]
 fn watch_directory(
     path: String,
+    options: Option<WatchOptions>,
     app: AppHandle,
-    watcher_state: State<'_, WatcherState>,
-) -> Result<(), String> {
+    window: tauri::WebviewWindow,
+) -> Result<(), IgneError> {
     let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
 
     if !path_obj.exists() || !path_obj.is_dir() {
-        return Err(format!("Path does not exist or is not a directory: {}", path));
+        return Err(IgneError::not_a_directory(
+            format!("Path does not exist or is not a directory: {}", path),
+            &path,
+        ));
     }
 
-    // Check if we're already watching this path
-    {
-        let watchers = watcher_state.watchers.lock().map_err(|e| e.to_string())?;
-        if watchers.contains_key(&path) {
-            // Already watching, no-op
-            return Ok(());
+    start_watch(&app, window.label(), &path, options.unwrap_or_default())
+}
+
+/// Set up the watch `watch_directory` asked for, in the form the vault's performance
+/// mode allows
+fn start_watch(app: &AppHandle, label: &str, path: &str, requested: WatchOptions) -> Result<(), IgneError> {
+    let path_obj = PathBuf::from(path);
+    let path = path.to_string();
+    // Watching the same path again from the same window replaces the old watcher, which
+    // is dropped on insert below, so a reloaded page never keeps a stale one
+    let key = (label.to_string(), path.clone());
+    let performance = performance_mode::mode_for(app, &path_obj);
+    let options = performance_mode::watch_options(performance, requested);
+    // Reduced mode is for slow or metered shares, where reading every file each round
+    // would cost more than the watch saves
+    let compare_contents = performance != PerformanceMode::Reduced;
+
+    let handler_app = app.clone();
+    let path_for_emit = path.clone();
+    let handler_label = key.0.clone();
+    let key_for_lost = key.clone();
+    let lost = AtomicBool::new(false);
+    // Anything cached before this watcher existed may have missed changes
+    app.state::<NoteTitleCache>().invalidate(&path);
+
+    let handler: WatchHandler = Arc::new(move |res: Result<Event, notify::Error>| {
+        let (app, label) = (&handler_app, handler_label.as_str());
+        // The folder was deleted or its drive ejected: the watcher is dead, so drop it
+        // and tell the window once. Watching the path again recovers when it's back.
+        let gone = match &res {
+            Ok(event) => {
+                matches!(event.kind, EventKind::Remove(_)) && !Path::new(&path_for_emit).is_dir()
+            }
+            Err(_) => !Path::new(&path_for_emit).is_dir(),
+        };
+        if gone {
+            if !lost.swap(true, Ordering::SeqCst) {
+                let reason = match &res {
+                    Err(e) => e.to_string(),
+                    Ok(_) => "Directory was removed".to_string(),
+                };
+                warn!("Lost watch on {}: {}", path_for_emit, reason);
+                let _ = app.emit_to(
+                    label,
+                    "watch-lost",
+                    WatchLost { path: path_for_emit.clone(), reason },
+                );
+                // Not dropped from its own callback thread
+                let (app, key) = (app.clone(), key_for_lost.clone());
+                std::thread::spawn(move || app.state::<WatcherState>().remove(&key));
+            }
+            return;
+        }
+
+        if let Ok(event) = res {
+            // Our own saves still change titles and links, so the caches are dropped
+            // before the self-write filter below
+            if event.paths.iter().any(|p| !is_internal_path(p)) {
+                app.state::<NoteTitleCache>().invalidate(&path_for_emit);
+                app.state::<NoteLinksCache>().invalidate_under(Path::new(&path_for_emit));
+                app.state::<DirectorySizeCache>().invalidate_containing(&event.paths);
+            }
+
+            // Only report paths changed by someone else: our own bookkeeping files and
+            // saves would trigger self-inflicted reloads that drop the cursor or unsaved edits
+            let write_locks = app.state::<WriteLocks>();
+            let external: Vec<PathBuf> = event
+                .paths
+                .iter()
+                .filter(|p| !is_internal_path(p) && !write_locks.is_self_write(p))
+                .cloned()
+                .collect();
+            if external.is_empty() {
+                return;
+            }
+
+            // Filter for relevant events (create, modify, remove, rename)
+            match event.kind {
+                EventKind::Create(_) |
+                EventKind::Modify(_) |
+                EventKind::Remove(_) |
+                EventKind::Any => {
+                    // During a bulk write the change waits for resume
+                    if app.state::<WatchPause>().hold(label, Held::Tree(path_for_emit.clone()), &external) {
+                        return;
+                    }
+                    // Emit what changed to the window that asked for it
+                    emit_fs_change(app, label, &path_for_emit, &external);
+                }
+                _ => {}
+            }
+        }
+    });
+
+    let (watcher, info): (Option<Box<dyn Watcher + Send>>, WatchInfo) = match options.mode {
+        WatchMode::Native => {
+            let forward = handler.clone();
+            let mut native = RecommendedWatcher::new(move |res| forward(res), notify::Config::default())?;
+            match native.watch(&path_obj, RecursiveMode::Recursive) {
+                Ok(()) => (Some(Box::new(native)), WatchInfo::native()),
+                // Too many folders for inotify: poll this root rather than leave parts of
+                // it silently unwatched
+                Err(e) if is_watch_limit_error(&e) => {
+                    drop(native);
+                    let reason = watch_limit_message();
+                    warn!("Watching {} natively failed ({}); polling instead", path, e);
+                    let mut poll = poll_watcher(&handler, DEFAULT_POLL_INTERVAL_MS, compare_contents)?;
+                    poll.watch(&path_obj, RecursiveMode::Recursive)?;
+                    let _ = app.emit_to(
+                        label,
+                        "watch-degraded",
+                        WatchDegraded {
+                            path: path.clone(),
+                            reason: reason.clone(),
+                            max_user_watches: max_user_watches(),
+                        },
+                    );
+                    (Some(Box::new(poll)), WatchInfo::poll(DEFAULT_POLL_INTERVAL_MS, Some(reason)))
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        WatchMode::Poll => {
+            let interval_ms = options.poll_interval_ms.unwrap_or(DEFAULT_POLL_INTERVAL_MS).max(100);
+            let mut poll = poll_watcher(&handler, interval_ms, compare_contents)?;
+            poll.watch(&path_obj, RecursiveMode::Recursive)?;
+            (Some(Box::new(poll)), WatchInfo::poll(interval_ms, None))
+        }
+        WatchMode::Off => (None, WatchInfo::off()),
+    };
+
+    // Store the watcher in state so it stays alive and can be cleaned up
+    let watcher_state = app.state::<WatcherState>();
+    let mut watchers = watcher_state.watchers.lock()?;
+    let watch = ActiveWatch { watcher, info, recursive: true, requested: Some(requested) };
+    watchers.insert(key, watch);
+
+    Ok(())
+}
+
+/// Set up every folder watch under `root` again, e.g. after its performance mode changed
+fn restart_watches_under(app: &AppHandle, root: &Path) {
+    let root = scope::resolve(root);
+    let restarts: Vec<(WatchKey, WatchOptions)> = match app.state::<WatcherState>().watchers.lock() {
+        Ok(watchers) => watchers
+            .iter()
+            .filter(|((_, path), _)| scope::resolve(Path::new(path)).starts_with(&root))
+            .filter_map(|(key, watch)| Some((key.clone(), watch.requested?)))
+            .collect(),
+        Err(_) => return,
+    };
+
+    for ((label, path), requested) in restarts {
+        if let Err(e) = start_watch(app, &label, &path, requested) {
+            warn!("Failed to restart watching {}: {}", path, e);
         }
     }
+}
 
-    let path_for_emit = path.clone();
-    let path_for_key = path.clone();
+/// Watch the JSON config files in the app data dir (settings, vault registry, ...) and
+/// emit `config-changed` with the file name, so other windows pick up edits. Self-writes
+/// aren't suppressed here: another window's save is exactly what listeners want to hear.
+#[tauri::command]
+fn watch_app_config(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    watcher_state: State<'_, WatcherState>,
+) -> Result<(), IgneError> {
+    let config_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| IgneError::other(format!("Failed to get app data dir: {}", e)))?;
+    fs::create_dir_all(&config_dir).map_err(|e| IgneError::io(e, &config_dir))?;
+
+    let key = (window.label().to_string(), config_dir.to_string_lossy().to_string());
+    if watcher_state.watchers.lock()?.contains_key(&key) {
+        return Ok(());
+    }
 
-    // Create a watcher with debouncing to avoid excessive events
+    let app_for_emit = app.clone();
+    let label = key.0.clone();
     let mut watcher: RecommendedWatcher = Watcher::new(
         move |res: Result<Event, notify::Error>| {
-            if let Ok(event) = res {
-                // Filter for relevant events (create, modify, remove, rename)
-                match event.kind {
-                    EventKind::Create(_) |
-                    EventKind::Modify(_) |
-                    EventKind::Remove(_) |
-                    EventKind::Any => {
-                        // Emit the path that changed
-                        let _ = app.emit("fs-change", path_for_emit.clone());
+            let Ok(event) = res else {
+                return;
+            };
+            if !matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            ) {
+                return;
+            }
+            // Temp files from atomic writes settle as a rename onto the .json file
+            for path in &event.paths {
+                if path.extension().is_some_and(|ext| ext == "json") {
+                    if let Some(name) = path.file_name() {
+                        let _ = app_for_emit.emit_to(
+                            label.as_str(),
+                            "config-changed",
+                            name.to_string_lossy().to_string(),
+                        );
                     }
-                    _ => {}
                 }
             }
         },
-        notify::Config::default()
-            .with_poll_interval(Duration::from_secs(1))
-            .with_compare_contents(true),
-    ).map_err(|e| e.to_string())?;
+        notify::Config::default(),
+    )?;
+    watcher.watch(&config_dir, RecursiveMode::NonRecursive)?;
 
-    // Watch the directory recursively
-    watcher.watch(&path_obj, RecursiveMode::Recursive)
-        .map_err(|e| e.to_string())?;
+    watcher_state.watchers.lock()?.insert(key, ActiveWatch::native(watcher));
+    Ok(())
+}
 
-    // Store the watcher in state so it stays alive and can be cleaned up
-    let mut watchers = watcher_state.watchers.lock().map_err(|e| e.to_string())?;
-    watchers.insert(path_for_key, watcher);
+/// Payload of `fs-file-change`
+#[derive(Serialize, Clone)]
+struct FileChangeEvent {
+    path: String,
+}
+
+fn emit_file_change(app: &AppHandle, label: &str, path: &str) {
+    let _ = app.emit_to(label, "fs-file-change", FileChangeEvent { path: path.to_string() });
+}
+
+/// Watch a single file (e.g. the open note) by watching its folder non-recursively and
+/// emitting `fs-file-change` only for that file. Stop with `unwatch_directory(path)`.
+#[tauri::command]
+fn watch_file(
+    path: String,
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    watcher_state: State<'_, WatcherState>,
+) -> Result<(), IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+
+    let parent = path_obj
+        .parent()
+        .filter(|parent| parent.is_dir())
+        .ok_or_else(|| IgneError::not_a_directory(format!("Parent folder does not exist: {}", path), &path))?
+        .to_path_buf();
+    let key = (window.label().to_string(), path.clone());
+    if watcher_state.watchers.lock()?.contains_key(&key) {
+        return Ok(());
+    }
+
+    let target = scope::resolve(&path_obj);
+    let path_for_emit = path.clone();
+    let label = key.0.clone();
+    let mut watcher: RecommendedWatcher = Watcher::new(
+        move |res: Result<Event, notify::Error>| {
+            let Ok(event) = res else {
+                return;
+            };
+            if !matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) | EventKind::Any
+            ) {
+                return;
+            }
 
+            let write_locks = app.state::<WriteLocks>();
+            let touches_target = event
+                .paths
+                .iter()
+                .any(|p| scope::resolve(p) == target && !write_locks.is_self_write(p));
+            if !touches_target {
+                return;
+            }
+            let held = Held::File(path_for_emit.clone());
+            if !app.state::<WatchPause>().hold(&label, held, std::slice::from_ref(&target)) {
+                emit_file_change(&app, &label, &path_for_emit);
+            }
+        },
+        notify::Config::default(),
+    )?;
+    watcher.watch(&parent, RecursiveMode::NonRecursive)?;
+
+    watcher_state.watchers.lock()?.insert(key, ActiveWatch::native(watcher));
     Ok(())
 }
 
@@ -358,12 +1814,13 @@ fn watch_directory(
 #[tauri::command]
 fn unwatch_directory(
     path: String,
+    window: tauri::WebviewWindow,
     watcher_state: State<'_, WatcherState>,
-) -> Result<(), String> {
-    let mut watchers = watcher_state.watchers.lock().map_err(|e| e.to_string())?;
+) -> Result<(), IgneError> {
+    let mut watchers = watcher_state.watchers.lock()?;
 
     // Remove the watcher - it will be dropped and stop watching
-    if watchers.remove(&path).is_some() {
+    if watchers.remove(&(window.label().to_string(), path)).is_some() {
         Ok(())
     } else {
         // Not an error if we weren't watching - idempotent
@@ -371,14 +1828,111 @@ fn unwatch_directory(
     }
 }
 
-/// Stop all watchers (useful for cleanup)
+/// Stop all of the calling window's watchers (useful for cleanup); other windows keep theirs
 #[tauri::command]
-fn unwatch_all(watcher_state: State<'_, WatcherState>) -> Result<(), String> {
-    let mut watchers = watcher_state.watchers.lock().map_err(|e| e.to_string())?;
-    watchers.clear();
+fn unwatch_all(window: tauri::WebviewWindow, watcher_state: State<'_, WatcherState>) -> Result<(), IgneError> {
+    watcher_state.remove_window(window.label());
     Ok(())
 }
 
+/// Backend watching `path` for the calling window (or, failing that, any window), so
+/// settings can warn about native watching on a network mount. `None` when unwatched.
+#[tauri::command]
+fn get_watch_info(
+    path: String,
+    window: tauri::WebviewWindow,
+    watcher_state: State<'_, WatcherState>,
+) -> Result<Option<WatchInfo>, IgneError> {
+    let watchers = watcher_state.watchers.lock()?;
+    let own = watchers.get(&(window.label().to_string(), path.clone()));
+    Ok(own
+        .or_else(|| watchers.iter().find(|((_, watched), _)| *watched == path).map(|(_, w)| w))
+        .map(|watch| watch.info.clone()))
+}
+
+#[derive(Serialize, Clone)]
+pub struct WatcherDiagnostic {
+    pub window: String,
+    pub path: String,
+    #[serde(flatten)]
+    pub info: WatchInfo,
+    /// Folders the native backend watches for this entry (inotify needs one watch per
+    /// folder); `None` when polling
+    pub watch_count: Option<u64>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct WatcherDiagnostics {
+    /// Linux only
+    pub max_user_watches: Option<u64>,
+    pub total_native_watches: u64,
+    pub watches: Vec<WatcherDiagnostic>,
+}
+
+/// Folders under `root`, itself included, as a recursive native watch would register them
+fn count_watched_folders(root: &Path) -> u64 {
+    let mut count = 0;
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        count += 1;
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                stack.push(entry.path());
+            }
+        }
+    }
+    count
+}
+
+/// Every active watch with its backend, folder count and any degradation, plus the
+/// inotify limit, for the settings/debug page
+#[tauri::command]
+fn get_watcher_diagnostics(watcher_state: State<'_, WatcherState>) -> Result<WatcherDiagnostics, IgneError> {
+    let entries: Vec<(WatchKey, WatchInfo, bool)> = watcher_state
+        .watchers
+        .lock()?
+        .iter()
+        .map(|(key, watch)| (key.clone(), watch.info.clone(), watch.recursive))
+        .collect();
+
+    // Counted outside the lock, since walking a large vault takes a while
+    let mut watches: Vec<WatcherDiagnostic> = entries
+        .into_iter()
+        .map(|((window, path), info, recursive)| {
+            let watch_count = match info.mode {
+                WatchMode::Poll | WatchMode::Off => None,
+                WatchMode::Native if recursive => Some(count_watched_folders(Path::new(&path))),
+                WatchMode::Native => Some(1),
+            };
+            WatcherDiagnostic { window, path, info, watch_count }
+        })
+        .collect();
+    watches.sort_by(|a, b| (&a.window, &a.path).cmp(&(&b.window, &b.path)));
+
+    Ok(WatcherDiagnostics {
+        max_user_watches: max_user_watches(),
+        total_native_watches: watches.iter().filter_map(|w| w.watch_count).sum(),
+        watches,
+    })
+}
+
+/// Active watchers (folders, single files and the app config dir) as `"<window>: <path>"`,
+/// sorted, for spotting leaked watchers in a debug panel
+#[tauri::command]
+fn list_watched_paths(watcher_state: State<'_, WatcherState>) -> Result<Vec<String>, IgneError> {
+    let mut paths: Vec<String> = watcher_state
+        .watchers
+        .lock()?
+        .keys()
+        .map(|(window, path)| format!("{}: {}", window, path))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
 #[tauri::command]
 fn get_app_data_dir(app: AppHandle) -> String {
     // Get the app's data directory for storing settings, vault registry, etc.
@@ -390,8 +1944,8 @@ fn get_app_data_dir(app: AppHandle) -> String {
 
 /// Get the default vault path (~/Documents/Igne)
 #[tauri::command]
-fn get_default_vault_path() -> Result<String, String> {
-    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+fn get_default_vault_path() -> Result<String, IgneError> {
+    let home = dirs::home_dir().ok_or_else(|| IgneError::other("Could not find home directory"))?;
     let vault_path = home.join("Documents").join("Igne");
     Ok(vault_path.to_string_lossy().to_string())
 }
@@ -399,17 +1953,27 @@ fn get_default_vault_path() -> Result<String, String> {
 /// Ensure the default vault exists, creating it if necessary
 /// Returns the vault path
 #[tauri::command]
-fn ensure_default_vault() -> Result<String, String> {
-    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+fn ensure_default_vault(scope: State<'_, ScopeState>) -> Result<String, IgneError> {
+    let vault_path = create_default_vault()?;
+
+    // The frontend opens it before it's in the vault registry
+    scope.grant(&vault_path);
+
+    Ok(vault_path.to_string_lossy().to_string())
+}
+
+/// `~/Documents/Igne`, created with starter settings and a welcome note the first time
+fn create_default_vault() -> Result<PathBuf, IgneError> {
+    let home = dirs::home_dir().ok_or_else(|| IgneError::other("Could not find home directory"))?;
     let vault_path = home.join("Documents").join("Igne");
 
     // Create vault directory if it doesn't exist
     if !vault_path.exists() {
-        fs::create_dir_all(&vault_path).map_err(|e| e.to_string())?;
+        fs::create_dir_all(&vault_path).map_err(|e| IgneError::io(e, &vault_path))?;
 
         // Create .obsidian directory
         let obsidian_path = vault_path.join(".obsidian");
-        fs::create_dir_all(&obsidian_path).map_err(|e| e.to_string())?;
+        fs::create_dir_all(&obsidian_path).map_err(|e| IgneError::io(e, &obsidian_path))?;
 
         // Create app.json
         let app_config = r#"{
@@ -420,7 +1984,7 @@ fn ensure_default_vault() -> Result<String, String> {
   "strictLineBreaks": false,
   "vimMode": false
 }"#;
-        fs::write(obsidian_path.join("app.json"), app_config).map_err(|e| e.to_string())?;
+        fs::write(obsidian_path.join("app.json"), app_config)?;
 
         // Create appearance.json
         let appearance_config = r##"{
@@ -429,7 +1993,7 @@ fn ensure_default_vault() -> Result<String, String> {
   "accentColor": "#a78bfa",
   "translucency": false
 }"##;
-        fs::write(obsidian_path.join("appearance.json"), appearance_config).map_err(|e| e.to_string())?;
+        fs::write(obsidian_path.join("appearance.json"), appearance_config)?;
 
         // Create Welcome.md
         let welcome_content = r#"# Welcome to Igne
@@ -459,10 +2023,10 @@ Start writing! Create your first note with **Cmd+N** or edit this one.
 
 *This is your default vault. You can open other vaults anytime from the vault switcher.*
 "#;
-        fs::write(vault_path.join("Welcome.md"), welcome_content).map_err(|e| e.to_string())?;
+        fs::write(vault_path.join("Welcome.md"), welcome_content)?;
     }
 
-    Ok(vault_path.to_string_lossy().to_string())
+    Ok(vault_path)
 }
 
 /// Check if a path is a markdown file
@@ -478,9 +2042,13 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(
             tauri_plugin_window_state::Builder::new()
                 .with_state_flags(tauri_plugin_window_state::StateFlags::all())
+                // Secondary window labels are reused across launches for unrelated
+                // windows; their geometry is saved by note or vault in `window_state`
+                .with_filter(|label| !label.starts_with("window-"))
                 .build()
         )
         .plugin(
@@ -489,17 +2057,36 @@ pub fn run() {
                     debug!("GlobalShortcut handler: shortcut={:?}, state={:?}", shortcut, event.state());
                     if event.state() == ShortcutState::Pressed {
                         info!("Global shortcut Cmd+Option+N pressed - bringing window to focus");
-                        if let Some(window) = app.get_webview_window("main") {
+                        let label = focused_window_label(app);
+                        if let Some(window) = app.get_webview_window(&label) {
                             let _ = window.show();
                             let _ = window.unminimize();
                             let _ = window.set_focus();
                         }
-                        let _ = app.emit("global-quick-capture", ());
+                        let _ = app.emit_to(label.as_str(), "global-quick-capture", ());
                     }
                 })
                 .build(),
         )
+        .manage(StartupState::new())
         .manage(WatcherState::new())
+        .manage(ScopeState::new())
+        .manage(WriteLocks::new())
+        .manage(WatchPause::new())
+        .manage(ChunkedReads::new())
+        .manage(AutosaveState::new())
+        .manage(NoteTitleCache::new())
+        .manage(NoteLinksCache::new())
+        .manage(LineStyles::new())
+        .manage(DirectorySizeCache::new())
+        .manage(CloseGuard::new())
+        .manage(FocusedWindow::new())
+        .manage(NoteWindows::new())
+        .manage(WindowGeometryKeys::new())
+        .manage(ColorSchemeState::new())
+        .manage(EnvOverrides::new())
+        .manage(ModeChanges::new())
+        .manage(SearchIndexCache::new())
         .menu(|app| {
             // macOS App menu (with About, Hide, Quit)
             #[cfg(target_os = "macos")]
@@ -592,57 +2179,233 @@ pub fn run() {
         })
         .on_menu_event(|app, event| {
             let event_id = event.id().as_ref();
+            let label = focused_window_label(app);
+            let target = label.as_str();
             match event_id {
                 "new_file" => {
-                    let _ = app.emit("menu-new-file", ());
+                    let _ = app.emit_to(target, "menu-new-file", ());
                 }
                 "open_file" => {
-                    let _ = app.emit("menu-open-file", ());
+                    let _ = app.emit_to(target, "menu-open-file", ());
                 }
                 "save_file" => {
-                    let _ = app.emit("menu-save-file", ());
+                    let _ = app.emit_to(target, "menu-save-file", ());
                 }
                 "close_tab" => {
-                    let _ = app.emit("menu-close-tab", ());
+                    let _ = app.emit_to(target, "menu-close-tab", ());
                 }
                 "quick_switcher" => {
-                    let _ = app.emit("menu-quick-switcher", ());
+                    let _ = app.emit_to(target, "menu-quick-switcher", ());
                 }
                 "settings" => {
-                    let _ = app.emit("menu-settings", ());
+                    let _ = app.emit_to(target, "menu-settings", ());
                 }
                 _ => {}
             }
         })
+        // A reloading page re-registers what it needs, so drop the watchers of the page
+        // that's going away
+        .on_page_load(|webview, payload| {
+            if payload.event() == tauri::webview::PageLoadEvent::Started {
+                webview.app_handle().state::<WatcherState>().remove_window(webview.label());
+            }
+        })
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::Focused(true) => {
+                if let Ok(mut focused) = window.app_handle().state::<FocusedWindow>().0.lock() {
+                    *focused = window.label().to_string();
+                }
+            }
+            // Save pending edits when the user switches away from the app
+            tauri::WindowEvent::Focused(false) => {
+                let app = window.app_handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    autosave::flush_all(&app).await;
+                });
+            }
+            // Ask before closing a window with unsaved notes
+            tauri::WindowEvent::CloseRequested { api, .. }
+                if close_guard::on_close_requested(window.app_handle(), window.label()) =>
+            {
+                api.prevent_close();
+            }
+            tauri::WindowEvent::CloseRequested { .. } => window_state::remember(window.app_handle(), window.label()),
+            tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+                window_state::schedule_save(window.app_handle(), window.label())
+            }
+            tauri::WindowEvent::ThemeChanged(theme) => color_scheme::on_theme_changed(window.app_handle(), *theme),
+            tauri::WindowEvent::Destroyed => {
+                let app = window.app_handle();
+                app.state::<WindowGeometryKeys>().remove_window(window.label());
+                app.state::<CloseGuard>().remove_window(window.label());
+                app.state::<WatcherState>().remove_window(window.label());
+                app.state::<NoteWindows>().remove_window(window.label());
+                app.state::<ChunkedReads>().remove_window(window.label());
+            }
+            _ => {}
+        })
         .invoke_handler(tauri::generate_handler![
             read_directory,
+            read_directory_page,
+            scan_directory_streaming,
             read_file,
+            read_file_with_style,
+            chunked_read::read_file_chunked,
+            chunked_read::cancel_chunked_read,
             write_file,
             file_exists,
+            check_path_writable,
             stat_path,
+            relative_time::relative_time,
+            relative_time::relative_times,
             read_file_binary,
             write_file_binary,
             rename_file,
+            sanitize_filename,
             delete_file,
+            delete_files,
+            safe_delete_directory,
+            undo_delete::delete_file_with_backup,
+            undo_delete::restore_deleted,
             create_directory,
+            folder_notes::create_folder_note,
             move_file,
+            move_files,
+            move_note_fix_links,
+            note_move::move_note,
+            note_move::rename_folder,
+            paths::to_vault_relative,
+            paths::to_absolute,
+            paths::safe_join,
+            bookmarks::get_bookmarks,
+            bookmarks::add_bookmark,
+            bookmarks::remove_bookmark,
+            css_snippets::list_css_snippets,
+            css_snippets::set_css_snippet_enabled,
+            reveal_in_file_manager,
+            set_window_title,
+            set_badge_count,
+            notifications::show_notification,
+            open_new_window,
+            open_note_window,
+            window_state::get_window_state,
+            window_state::set_window_state,
+            color_scheme::get_color_scheme,
+            set_active_document,
+            close_guard::set_window_dirty,
             watch_directory,
             unwatch_directory,
+            watch_app_config,
+            watch_file,
             unwatch_all,
+            list_watched_paths,
+            get_watch_info,
+            get_watcher_diagnostics,
             get_app_data_dir,
             get_default_vault_path,
-            ensure_default_vault
+            env_vars::get_env_var,
+            env_vars::set_env_var,
+            ensure_default_vault,
+            scope::pick_folder,
+            scope::pick_file,
+            app_config::read_app_config,
+            app_config::write_app_config,
+            app_config::append_session_log,
+            diff::compute_diff,
+            vault_trash::trash_to_vault,
+            vault_trash::restore_from_vault_trash,
+            vault_trash::list_vault_trash,
+            os_trash::list_recently_trashed,
+            os_trash::restore_trashed,
+            vault::vault_stats,
+            attachments::list_attachments,
+            vault::validate_vault,
+            vault::is_vault_directory,
+            vault::find_normalization_conflicts,
+            disk_space::get_disk_space,
+            file_associations::register_file_associations,
+            file_associations::check_file_association,
+            file_type::detect_file_type,
+            note_kind::get_note_kind,
+            file_identity::get_inode,
+            note_id::get_or_create_note_id,
+            directory_size::get_directory_size,
+            directory_size::get_folder_size,
+            history::write_file_safe,
+            history::list_file_versions,
+            history::restore_file_version,
+            markdown_ast::parse_markdown_to_ast,
+            markdown_ast::strip_markdown,
+            formatting::format_markdown,
+            note_titles::list_note_titles,
+            note_titles::get_note_title,
+            wikilink_completion::autocomplete_wikilink,
+            note_links::get_note_links,
+            tree_decorations::get_tree_decorations,
+            images::get_image_dimensions,
+            images::optimize_image,
+            images::extract_all_images,
+            thumbnails::get_thumbnail,
+            thumbnails::get_thumbnail_cache_size,
+            thumbnails::clear_thumbnail_cache,
+            obsidian_import::import_obsidian_settings,
+            toc::get_toc,
+            toc::read_section,
+            transclusion::expand_transclusions,
+            snapshots::list_snapshots,
+            snapshots::get_snapshot,
+            snapshots::restore_snapshot,
+            snapshots::snapshot_store_size,
+            snapshots::prune_snapshots,
+            watch_pause::pause_watching,
+            watch_pause::resume_watching,
+            logging::get_recent_logs,
+            logging::get_log_file_path,
+            logging::set_log_level,
+            startup::get_startup_timings,
+            crash_reports::get_unreported_crashes,
+            crash_reports::mark_crashes_reported,
+            autosave::update_dirty_buffer,
+            autosave::clear_dirty_buffer,
+            autosave::flush_dirty_buffers,
+            autosave::debounced_write_file,
+            spellcheck::spell_check_text,
+            language::detect_language,
+            locale::get_available_languages,
+            locale::get_locale,
+            locale::set_locale,
+            tags::get_all_tags,
+            tags::normalize_tags,
+            recent_searches::save_recent_search,
+            recent_searches::get_recent_searches,
+            git::git_status,
+            git::git_commit,
+            git::git_log,
+            git::git_diff_file,
+            search_index::build_search_index,
+            search_index::query_search_index,
+            performance_mode::get_performance_mode,
+            performance_mode::set_performance_mode,
+            performance_mode::refresh_vault,
+            vault_search::count_vault_matches,
+            vault_search::find_and_replace
         ])
         .setup(|app| {
             // Initialize logging first
-            init_logging();
+            logging::init(app.path().app_data_dir().ok());
+            crash_reports::install_panic_hook(app.handle());
+            undo_delete::purge_expired(app.handle());
             info!("Igne app starting...");
 
             // Show the main window (it starts hidden to prevent flash while restoring state)
-            if let Some(window) = app.get_webview_window("main") {
+            if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
                 let _ = window.show();
             }
 
+            // List, stream and index the vault in the background while the window loads
+            startup::start(app.handle());
+            color_scheme::watch(app.handle());
+
             // Global shortcut: Cmd+Option+N (⌘+⌥+N) for quick capture
             #[cfg(desktop)]
             {
@@ -669,11 +2432,12 @@ pub fn run() {
 
                 // Check if this looks like a file path
                 let path = PathBuf::from(arg);
-                if path.exists() && path.is_file() && is_markdown_file(arg) {
+                if path.exists() && path.is_file() && note_kind::detect(&path).opens_in_app() {
                     let absolute_path = path.canonicalize()
                         .unwrap_or(path)
                         .to_string_lossy()
                         .to_string();
+                    app.state::<ScopeState>().grant(Path::new(&absolute_path));
 
                     // Emit event to frontend after a short delay to ensure it's ready
                     let app_handle = app.handle().clone();
@@ -686,11 +2450,30 @@ pub fn run() {
                 }
             }
 
+            autosave::start_scheduler(app.handle().clone());
+
             Ok(())
         })
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|_app, _event| {
+            // Quitting (menu, Cmd+Q or closing the last window) asks about unsaved notes
+            // in every window, then writes the pending edits that weren't discarded
+            if let tauri::RunEvent::ExitRequested { api, .. } = &_event {
+                if close_guard::on_exit_requested(_app) {
+                    api.prevent_exit();
+                    return;
+                }
+                // Exiting destroys the windows without `CloseRequested`
+                for label in _app.webview_windows().keys() {
+                    window_state::remember(_app, label);
+                }
+                tauri::async_runtime::block_on(autosave::flush_all(_app));
+            }
+            if let tauri::RunEvent::Exit = &_event {
+                color_scheme::stop(_app);
+            }
+
             // Handle files opened while app is already running
             // Note: On macOS, file association events come through RunEvent::Opened
             // On Linux, file associations are handled via CLI args at startup
@@ -700,8 +2483,13 @@ pub fn run() {
                     for url in urls {
                         if let Ok(path) = url.to_file_path() {
                             let path_str = path.to_string_lossy().to_string();
-                            if is_markdown_file(&path_str) {
-                                let _ = _app.emit("open-standalone-file", path_str);
+                            if note_kind::detect(&path).opens_in_app() {
+                                _app.state::<ScopeState>().grant(&path);
+                                let _ = _app.emit_to(
+                                    focused_window_label(_app).as_str(),
+                                    "open-standalone-file",
+                                    path_str,
+                                );
                             }
                         }
                     }
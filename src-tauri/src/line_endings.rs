@@ -0,0 +1,140 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::formatting::LineEnding;
+use crate::scope::resolve;
+
+/// Line-break style of a text file as read from disk
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineStyle {
+    /// "lf" or "crlf", whichever is more common; `None` when there are no line breaks
+    pub line_ending: Option<&'static str>,
+    /// Both endings occur. Saves only normalize these when asked for "lf" or "crlf".
+    pub mixed: bool,
+    pub trailing_newline: bool,
+}
+
+/// Styles recorded by the last read or write of each file, for `line_ending: "preserve"`
+pub struct LineStyles {
+    styles: Mutex<HashMap<PathBuf, LineStyle>>,
+}
+
+impl LineStyles {
+    pub fn new() -> Self {
+        Self {
+            styles: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, path: &Path) -> Option<LineStyle> {
+        self.styles.lock().ok()?.get(&resolve(path)).copied()
+    }
+
+    pub fn record(&self, path: &Path, style: LineStyle) {
+        if let Ok(mut styles) = self.styles.lock() {
+            styles.insert(resolve(path), style);
+        }
+    }
+}
+
+impl Default for LineStyles {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn detect(content: &str) -> LineStyle {
+    let crlf = content.matches("\r\n").count();
+    let lf = content.matches('\n').count() - crlf;
+    let line_ending = match (lf, crlf) {
+        (0, 0) => None,
+        (lf, crlf) if crlf > lf => Some("crlf"),
+        _ => Some("lf"),
+    };
+    LineStyle {
+        line_ending,
+        mixed: lf > 0 && crlf > 0,
+        trailing_newline: content.ends_with('\n'),
+    }
+}
+
+fn convert(content: &str, ending: &str) -> String {
+    let lf = content.replace("\r\n", "\n");
+    if ending == "\r\n" {
+        lf.replace('\n', "\r\n")
+    } else {
+        lf
+    }
+}
+
+/// Apply a save's line-ending options to `content`. `Preserve` restores the `recorded`
+/// style: its ending unless the file had mixed endings, and its trailing newline unless
+/// `trailing_newline` says otherwise. `trailing_newline` adds (`true`) or strips
+/// (`false`) one final line break; `None` leaves it to the content or the recorded style.
+pub fn apply(
+    content: String,
+    line_ending: Option<LineEnding>,
+    trailing_newline: Option<bool>,
+    recorded: Option<LineStyle>,
+) -> String {
+    let (ending, trailing_newline) = match line_ending {
+        Some(LineEnding::Lf) => (Some("\n"), trailing_newline),
+        Some(LineEnding::Crlf) => (Some("\r\n"), trailing_newline),
+        Some(LineEnding::Preserve) => match recorded {
+            Some(style) => (
+                match style.line_ending {
+                    Some("crlf") if !style.mixed => Some("\r\n"),
+                    Some("lf") if !style.mixed => Some("\n"),
+                    _ => None,
+                },
+                trailing_newline.or(Some(style.trailing_newline)),
+            ),
+            None => (None, trailing_newline),
+        },
+        None => (None, trailing_newline),
+    };
+
+    let mut content = match ending {
+        Some(ending) => convert(&content, ending),
+        None => content,
+    };
+    match trailing_newline {
+        Some(true) if !content.is_empty() && !content.ends_with('\n') => {
+            let ending = ending.unwrap_or(if detect(&content).line_ending == Some("crlf") { "\r\n" } else { "\n" });
+            content.push_str(ending);
+        }
+        Some(false) => {
+            // Only the final line break: blank lines at the end are content
+            let trimmed = content
+                .strip_suffix("\r\n")
+                .or_else(|| content.strip_suffix('\n'))
+                .map(str::len);
+            if let Some(trimmed) = trimmed {
+                content.truncate(trimmed);
+            }
+        }
+        _ => {}
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stripping_the_trailing_newline_keeps_blank_lines() {
+        assert_eq!(apply("a\n\n".into(), None, Some(false), None), "a\n");
+        assert_eq!(apply("a\r\n\r\n".into(), None, Some(false), None), "a\r\n");
+        assert_eq!(apply("a".into(), None, Some(false), None), "a");
+    }
+
+    #[test]
+    fn preserve_restores_the_recorded_style() {
+        let recorded = detect("one\r\ntwo\r\n");
+        let saved = apply("one\ntwo".into(), Some(LineEnding::Preserve), None, Some(recorded));
+        assert_eq!(saved, "one\r\ntwo\r\n");
+    }
+}
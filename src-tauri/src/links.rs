@@ -0,0 +1,254 @@
+use serde::Serialize;
+use std::path::{Component, Path, PathBuf};
+
+use crate::markdown::fenced_code_ranges;
+
+/// A markdown link/embed destination found in note content: `[text](dest)` or `![alt](dest)`
+pub struct MarkdownLink {
+    /// Byte range of the destination inside the content (excluding `<` `>`)
+    pub start: usize,
+    pub end: usize,
+    pub angle_brackets: bool,
+}
+
+/// Find inline markdown link destinations, skipping fenced code blocks
+pub fn find_markdown_links(content: &str) -> Vec<MarkdownLink> {
+    let fenced = fenced_code_ranges(content);
+    let bytes = content.as_bytes();
+    let mut links = Vec::new();
+    let mut from = 0;
+
+    while let Some(rel) = content[from..].find("](") {
+        let open = from + rel + 2;
+        from = open;
+
+        if fenced.iter().any(|&(s, e)| open >= s && open < e) {
+            continue;
+        }
+
+        // Skip leading whitespace inside the parens
+        let mut i = open;
+        while i < bytes.len() && bytes[i] == b' ' {
+            i += 1;
+        }
+
+        if i < bytes.len() && bytes[i] == b'<' {
+            let Some(close) = content[i + 1..].find('>') else {
+                continue;
+            };
+            links.push(MarkdownLink {
+                start: i + 1,
+                end: i + 1 + close,
+                angle_brackets: true,
+            });
+            from = i + 1 + close;
+            continue;
+        }
+
+        // Plain destination: ends at whitespace or the unbalanced closing paren
+        let start = i;
+        let mut depth = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'(' => depth += 1,
+                b')' if depth == 0 => break,
+                b')' => depth -= 1,
+                b' ' | b'\t' | b'\n' | b'\r' => break,
+                _ => {}
+            }
+            i += 1;
+        }
+        if i > start {
+            links.push(MarkdownLink {
+                start,
+                end: i,
+                angle_brackets: false,
+            });
+        }
+        from = i;
+    }
+
+    links
+}
+
+/// Whether a link destination points outside the vault (URL, mail, anchor-only, absolute)
+pub fn is_external_link(dest: &str) -> bool {
+    dest.is_empty()
+        || dest.starts_with('#')
+        || dest.starts_with('/')
+        || dest.contains("://")
+        || dest.starts_with("mailto:")
+        || dest.starts_with("data:")
+}
+
+/// Decode `%XX` escapes (links to files with spaces are usually written as `%20`)
+pub fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = (
+                (bytes[i + 1] as char).to_digit(16),
+                (bytes[i + 2] as char).to_digit(16),
+            );
+            if let (Some(hi), Some(lo)) = hex {
+                out.push((hi * 16 + lo) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// Resolve `.` and `..` without touching the filesystem
+pub fn lexical_normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// Relative path from directory `from` to `to`, using forward slashes
+pub fn relative_path(from: &Path, to: &Path) -> String {
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = to.components().collect();
+
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut parts: Vec<String> = vec!["..".to_string(); from.len() - common];
+    parts.extend(to[common..].iter().map(|c| c.as_os_str().to_string_lossy().to_string()));
+    parts.join("/")
+}
+
+/// Find the markdown link/embed destinations in `content` that `relink` moves somewhere
+/// else. `relink` gets each local destination's path, percent-decoded and without its
+/// `#fragment`, and returns the path it should now read, if any. Returns the byte ranges to
+/// replace and their new destinations, fragment and encoding kept.
+pub fn relink_markdown_links(
+    content: &str,
+    mut relink: impl FnMut(&str) -> Option<String>,
+) -> Vec<(usize, usize, String)> {
+    let mut edits = vec![];
+
+    for link in find_markdown_links(content) {
+        let dest = &content[link.start..link.end];
+        if is_external_link(dest) {
+            continue;
+        }
+
+        let (path_part, fragment) = match dest.find('#') {
+            Some(i) => (&dest[..i], &dest[i..]),
+            None => (dest, ""),
+        };
+        let Some(mut new_dest) = relink(&percent_decode(path_part)) else {
+            continue;
+        };
+        if !link.angle_brackets {
+            new_dest = new_dest.replace(' ', "%20");
+        }
+        new_dest.push_str(fragment);
+
+        if new_dest != dest {
+            edits.push((link.start, link.end, new_dest));
+        }
+    }
+
+    edits
+}
+
+#[derive(Serialize, Clone)]
+pub struct RewrittenLink {
+    pub original: String,
+    pub rewritten: String,
+}
+
+/// Rewrite relative markdown link/embed destinations in `content` so they resolve
+/// from `new_dir` instead of `old_dir`. Wikilinks resolve by name and are untouched.
+pub fn rewrite_relative_links(
+    content: &str,
+    old_dir: &Path,
+    new_dir: &Path,
+    vault_root: &Path,
+) -> (String, Vec<RewrittenLink>) {
+    let new_dir = lexical_normalize(new_dir);
+    let edits = relink_markdown_links(content, |decoded| {
+        let target = lexical_normalize(&old_dir.join(decoded));
+        // Vault-relative links (Obsidian's "absolute path in vault" style) don't depend on
+        // the note's location, so leave them alone
+        if !target.exists() && vault_root.join(decoded).exists() {
+            return None;
+        }
+        Some(relative_path(&new_dir, &target))
+    });
+
+    let mut result = String::with_capacity(content.len());
+    let mut rewritten = vec![];
+    let mut last = 0;
+    for (start, end, new_dest) in edits {
+        result.push_str(&content[last..start]);
+        result.push_str(&new_dest);
+        last = end;
+        rewritten.push(RewrittenLink {
+            original: content[start..end].to_string(),
+            rewritten: new_dest,
+        });
+    }
+    result.push_str(&content[last..]);
+    (result, rewritten)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn rewrites_relative_links_for_the_new_folder() {
+        let vault = std::env::temp_dir().join(format!("igne-links-{}", std::process::id()));
+        fs::create_dir_all(vault.join("notes/img")).unwrap();
+        fs::create_dir_all(vault.join("assets")).unwrap();
+        fs::write(vault.join("notes/img/a.png"), "").unwrap();
+        fs::write(vault.join("notes/Other Note.md"), "").unwrap();
+        fs::write(vault.join("assets/b.png"), "").unwrap();
+        let (old_dir, new_dir) = (vault.join("notes"), vault.join("archive/2024"));
+
+        let cases = [
+            ("![](img/a.png)", "![](../../notes/img/a.png)"),
+            ("![alt](./img/a.png \"title\")", "![alt](../../notes/img/a.png \"title\")"),
+            ("[x](../assets/b.png)", "[x](../../assets/b.png)"),
+            ("[x](<Other Note.md>)", "[x](<../../notes/Other Note.md>)"),
+            ("[x](Other%20Note.md#Heading)", "[x](../../notes/Other%20Note.md#Heading)"),
+            // Vault-rooted links don't depend on the note's folder
+            ("[x](assets/b.png)", "[x](assets/b.png)"),
+            (
+                "[w](https://example.com/a.png) [m](mailto:a@b.c) [h](#heading)",
+                "[w](https://example.com/a.png) [m](mailto:a@b.c) [h](#heading)",
+            ),
+            ("[[Other Note]] ![[a.png]] [[img/a.png]]", "[[Other Note]] ![[a.png]] [[img/a.png]]"),
+            ("```\n![](img/a.png)\n```", "```\n![](img/a.png)\n```"),
+        ];
+        for (content, expected) in cases {
+            let (rewritten, links) = rewrite_relative_links(content, &old_dir, &new_dir, &vault);
+            assert_eq!(rewritten, expected);
+            assert_eq!(links.len(), usize::from(content != expected), "{}", content);
+        }
+
+        let (_, links) = rewrite_relative_links("[x](img/a.png#x)", &old_dir, &new_dir, &vault);
+        assert_eq!(links[0].original, "img/a.png#x");
+        assert_eq!(links[0].rewritten, "../../notes/img/a.png#x");
+        fs::remove_dir_all(&vault).unwrap();
+    }
+}
@@ -0,0 +1,92 @@
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+use crate::app_config::{load_settings, settings_path, update_settings};
+use crate::error::IgneError;
+
+/// Key in `<app_data_dir>/settings.json` holding the chosen UI language
+const SETTINGS_KEY: &str = "locale";
+
+/// Used when neither the settings nor the OS name a bundled language
+const DEFAULT_LOCALE: &str = "en";
+
+/// UI languages the frontend bundles translations for, as (code, English name, native
+/// name). A translation is added here when it ships.
+const LANGUAGES: &[(&str, &str, &str)] = &[("en", "English", "English")];
+
+#[derive(Serialize, Clone)]
+pub struct LanguageInfo {
+    /// BCP-47 tag, e.g. "en" or "pt-BR"
+    pub code: String,
+    /// Name in English
+    pub name: String,
+    /// Name in the language itself, for the language picker
+    pub native_name: String,
+}
+
+/// Payload of `locale-changed`
+#[derive(Serialize, Clone)]
+struct LocaleChanged {
+    locale: String,
+}
+
+/// The bundled language for a BCP-47 tag: the exact tag ignoring case (`_` accepted for
+/// `-`, as POSIX locales write it), else its language subtag, so "de-AT" finds "de"
+fn bundled(tag: &str) -> Option<&'static str> {
+    let tag = tag.replace('_', "-");
+    let language = tag.split('-').next().unwrap_or_default();
+    let find = |wanted: &str| {
+        LANGUAGES
+            .iter()
+            .map(|(code, _, _)| *code)
+            .find(|code| code.eq_ignore_ascii_case(wanted))
+    };
+    find(&tag).or_else(|| find(language))
+}
+
+#[tauri::command]
+pub fn get_available_languages() -> Result<Vec<LanguageInfo>, IgneError> {
+    Ok(LANGUAGES
+        .iter()
+        .map(|(code, name, native_name)| LanguageInfo {
+            code: code.to_string(),
+            name: name.to_string(),
+            native_name: native_name.to_string(),
+        })
+        .collect())
+}
+
+/// The UI language: the one saved by `set_locale`, else the closest bundled match for the
+/// OS language, else English
+#[tauri::command]
+pub fn get_locale(app: AppHandle) -> String {
+    let saved = settings_path(&app)
+        .ok()
+        .and_then(|path| load_settings(&path).ok()?.remove(SETTINGS_KEY))
+        .and_then(|locale| bundled(locale.as_str()?));
+    saved
+        .or_else(|| sys_locale::get_locales().find_map(|tag| bundled(&tag)))
+        .unwrap_or(DEFAULT_LOCALE)
+        .to_string()
+}
+
+/// Save the UI language, one of `get_available_languages`, and emit `locale-changed` to
+/// every window so they re-render in it
+#[tauri::command]
+pub async fn set_locale(code: String, app: AppHandle) -> Result<(), IgneError> {
+    let locale = LANGUAGES
+        .iter()
+        .map(|(code, _, _)| *code)
+        .find(|available| available.eq_ignore_ascii_case(&code))
+        .ok_or_else(|| IgneError::invalid_input(format!("Language not available: {}", code)))?;
+
+    update_settings(&app, |settings| {
+        settings.insert(SETTINGS_KEY.into(), Value::from(locale));
+        Ok(())
+    })
+    .await?;
+
+    let _ = app.emit("locale-changed", LocaleChanged { locale: locale.to_string() });
+    Ok(())
+}
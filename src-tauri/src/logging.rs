@@ -0,0 +1,219 @@
+use log::{info, LevelFilter};
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use tauri::AppHandle;
+
+use crate::app_config::{load_settings, update_settings, SETTINGS_FILE};
+use crate::error::IgneError;
+
+/// Key in `<app_data_dir>/settings.json` holding the level chosen with `set_log_level`
+const SETTINGS_KEY: &str = "logLevel";
+
+const LOG_DIR: &str = "logs";
+const LOG_FILE: &str = "igne.log";
+/// Rotate once the current file passes this size
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// `igne.log` plus `igne.log.1` and `igne.log.2`
+const KEPT_FILES: usize = 3;
+
+/// Folder of the log files, set once logging is initialized
+static LOG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Appends to `igne.log`, shifting it to `igne.log.1` (and so on) when it gets too big
+struct RotatingFile {
+    dir: PathBuf,
+    file: Option<File>,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let file = File::options().create(true).append(true).open(dir.join(LOG_FILE))?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            file: Some(file),
+            size,
+        })
+    }
+
+    fn rotated(&self, index: usize) -> PathBuf {
+        match index {
+            0 => self.dir.join(LOG_FILE),
+            n => self.dir.join(format!("{}.{}", LOG_FILE, n)),
+        }
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+        let _ = fs::remove_file(self.rotated(KEPT_FILES - 1));
+        for index in (0..KEPT_FILES - 1).rev() {
+            let _ = fs::rename(self.rotated(index), self.rotated(index + 1));
+        }
+        self.file = Some(File::options().create(true).append(true).open(self.rotated(0))?);
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size >= MAX_LOG_BYTES {
+            self.rotate()?;
+        }
+        let file = self
+            .file
+            .as_mut()
+            .ok_or_else(|| io::Error::other("log file is not open"))?;
+        let written = file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Log output: the rotating file, plus stderr in dev builds
+struct LogTarget {
+    file: Option<RotatingFile>,
+    stderr: bool,
+}
+
+impl Write for LogTarget {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.stderr {
+            let _ = io::stderr().write_all(buf);
+        }
+        if let Some(file) = self.file.as_mut() {
+            // A full disk shouldn't take logging to stderr down with it
+            let _ = file.write_all(buf);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(file) = self.file.as_mut() {
+            file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// `logLevel` saved in `<app_data_dir>/settings.json` by `set_log_level`
+fn saved_level(app_data: &Path) -> Option<LevelFilter> {
+    let mut settings = load_settings(&app_data.join(SETTINGS_FILE)).ok()?;
+    LevelFilter::from_str(settings.remove(SETTINGS_KEY)?.as_str()?).ok()
+}
+
+/// Initialize logging based on build profile. Logs go to `<app_data_dir>/logs/igne.log`
+/// and, in dev, to stderr too. The level is `RUST_LOG` when set, then the saved
+/// `logLevel`, then debug in dev and warn in production.
+pub fn init(app_data: Option<PathBuf>) {
+    let is_dev = cfg!(debug_assertions);
+
+    let mut builder = env_logger::Builder::new();
+
+    if is_dev {
+        builder.format(|buf, record| {
+            writeln!(
+                buf,
+                "[{}] {} - {}:{} - {}",
+                record.level(),
+                record.target(),
+                record.file().unwrap_or("unknown"),
+                record.line().unwrap_or(0),
+                record.args()
+            )
+        });
+    } else {
+        builder.format(|buf, record| {
+            writeln!(buf, "[{}] {} - {}", buf.timestamp_seconds(), record.level(), record.args())
+        });
+    }
+
+    let file = app_data.as_ref().and_then(|dir| {
+        let log_dir = dir.join(LOG_DIR);
+        let file = RotatingFile::open(&log_dir).ok()?;
+        let _ = LOG_PATH.set(log_dir);
+        Some(file)
+    });
+    builder.target(env_logger::Target::Pipe(Box::new(LogTarget {
+        stderr: is_dev || file.is_none(),
+        file,
+    })));
+
+    // Everything passes the logger's own filter unless RUST_LOG narrows it; the level
+    // users pick is applied through `log::set_max_level` so it can change at runtime
+    builder.filter_level(LevelFilter::Trace);
+    let from_env = std::env::var("RUST_LOG").is_ok();
+    builder.parse_env("RUST_LOG");
+    let logger = builder.build();
+
+    let level = if from_env {
+        logger.filter()
+    } else {
+        app_data
+            .as_deref()
+            .and_then(saved_level)
+            .unwrap_or(if is_dev { LevelFilter::Debug } else { LevelFilter::Warn })
+    };
+
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(level);
+    }
+
+    info!("Logging initialized (dev={}, level={})", is_dev, level);
+}
+
+fn log_file() -> Result<PathBuf, IgneError> {
+    LOG_PATH
+        .get()
+        .map(|dir| dir.join(LOG_FILE))
+        .ok_or_else(|| IgneError::other("File logging is not available"))
+}
+
+/// Last `lines` lines of the current log file
+#[tauri::command]
+pub fn get_recent_logs(lines: u32) -> Result<String, IgneError> {
+    let path = log_file()?;
+    let content = fs::read(&path).map_err(|e| IgneError::io(e, &path))?;
+    let content = String::from_utf8_lossy(&content);
+
+    let all: Vec<&str> = content.lines().collect();
+    let start = all.len().saturating_sub(lines as usize);
+    Ok(all[start..].join("\n"))
+}
+
+/// Path of the current log file, for a "Reveal logs" button
+#[tauri::command]
+pub fn get_log_file_path() -> Result<String, IgneError> {
+    Ok(log_file()?.to_string_lossy().to_string())
+}
+
+/// Change the log level without restarting ("error", "warn", "info", "debug", "trace" or
+/// "off") and remember it in settings for the next launch
+#[tauri::command]
+pub async fn set_log_level(level: String, app: AppHandle) -> Result<(), IgneError> {
+    let filter = LevelFilter::from_str(&level)
+        .map_err(|_| IgneError::invalid_input(format!("Unknown log level: {}", level)))?;
+
+    update_settings(&app, |settings| {
+        settings.insert(SETTINGS_KEY.into(), Value::from(filter.as_str().to_lowercase()));
+        Ok(())
+    })
+    .await?;
+
+    log::set_max_level(filter);
+    info!("Log level set to {}", filter);
+    Ok(())
+}
@@ -0,0 +1,253 @@
+/// Byte offsets of a leading YAML frontmatter block: (inner start, inner end, block end)
+fn frontmatter_bounds(content: &str) -> Option<(usize, usize, usize)> {
+    let rest = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))?;
+    let inner_start = content.len() - rest.len();
+
+    let mut offset = inner_start;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return Some((inner_start, offset, offset + line.len()));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Byte range of a leading YAML frontmatter block, delimiters included
+pub fn frontmatter_range(content: &str) -> Option<(usize, usize)> {
+    frontmatter_bounds(content).map(|(_, _, end)| (0, end))
+}
+
+/// Byte range of the frontmatter block's inner text, without the `---` delimiters
+pub fn frontmatter_inner_range(content: &str) -> Option<(usize, usize)> {
+    frontmatter_bounds(content).map(|(start, end, _)| (start, end))
+}
+
+/// Inner text of the frontmatter block, without the `---` delimiters
+pub fn frontmatter(content: &str) -> Option<&str> {
+    frontmatter_inner_range(content).map(|(start, end)| &content[start..end])
+}
+
+/// Content after the frontmatter block
+pub fn body(content: &str) -> &str {
+    match frontmatter_range(content) {
+        Some((_, end)) => &content[end..],
+        None => content,
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    let s = s.trim();
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .or_else(|| s.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))
+        .unwrap_or(s)
+}
+
+/// Scalar frontmatter value for `key`, e.g. `title: "My note"`
+pub fn frontmatter_value<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    frontmatter(content)?
+        .lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+        .map(unquote)
+        .filter(|value| !value.is_empty())
+}
+
+/// List frontmatter value for `key`, accepting `key: [a, b]`, `key: a, b` and block lists
+pub fn frontmatter_list(content: &str, key: &str) -> Vec<String> {
+    let Some(fm) = frontmatter(content) else {
+        return vec![];
+    };
+
+    let mut lines = fm.lines();
+    while let Some(line) = lines.next() {
+        let Some(value) = line.strip_prefix(key).and_then(|r| r.strip_prefix(':')) else {
+            continue;
+        };
+        let value = value.trim();
+
+        if value.is_empty() {
+            // Block list on the following indented `- item` lines
+            return lines
+                .map(str::trim)
+                .take_while(|l| l.starts_with('-'))
+                .map(|l| unquote(&l[1..]).to_string())
+                .filter(|v| !v.is_empty())
+                .collect();
+        }
+
+        let inline = value
+            .strip_prefix('[')
+            .and_then(|v| v.strip_suffix(']'))
+            .unwrap_or(value);
+        return inline
+            .split(',')
+            .map(|v| unquote(v).to_string())
+            .filter(|v| !v.is_empty())
+            .collect();
+    }
+
+    vec![]
+}
+
+/// Byte ranges covered by fenced code blocks
+pub fn fenced_code_ranges(content: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+    let mut fence_start: Option<usize> = None;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            match fence_start.take() {
+                Some(start) => ranges.push((start, offset + line.len())),
+                None => fence_start = Some(offset),
+            }
+        }
+        offset += line.len();
+    }
+    if let Some(start) = fence_start {
+        ranges.push((start, content.len()));
+    }
+
+    ranges
+}
+
+/// Byte ranges covered by fenced code blocks and inline code spans
+pub fn code_ranges(content: &str) -> Vec<(usize, usize)> {
+    let mut ranges = fenced_code_ranges(content);
+    let fenced = ranges.clone();
+    let in_fence = |pos: usize| fenced.iter().any(|&(s, e)| pos >= s && pos < e);
+
+    let mut from = 0;
+    while let Some(rel) = content[from..].find('`') {
+        let start = from + rel;
+        if in_fence(start) {
+            from = start + 1;
+            continue;
+        }
+        // Inline code doesn't span blank lines; an unmatched backtick is literal
+        let Some(end_rel) = content[start + 1..].find('`') else {
+            break;
+        };
+        let end = start + 1 + end_rel + 1;
+        if content[start..end].contains("\n\n") {
+            from = start + 1;
+            continue;
+        }
+        ranges.push((start, end));
+        from = end;
+    }
+
+    ranges
+}
+
+fn in_ranges(ranges: &[(usize, usize)], pos: usize) -> bool {
+    ranges.iter().any(|&(s, e)| pos >= s && pos < e)
+}
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '/')
+}
+
+/// Inline `#tags` outside code (without the `#`). Like Obsidian, a tag must follow
+/// whitespace or start a line and can't be purely numeric.
+pub fn inline_tags(content: &str) -> Vec<String> {
+    let code = code_ranges(content);
+    let start = frontmatter_range(content).map(|(_, end)| end).unwrap_or(0);
+    let mut tags = Vec::new();
+
+    let mut prev: Option<char> = None;
+    for (i, c) in content[start..].char_indices() {
+        let pos = start + i;
+        if c == '#' && prev.map(char::is_whitespace).unwrap_or(true) && !in_ranges(&code, pos) {
+            let tag: String = content[pos + 1..].chars().take_while(|&c| is_tag_char(c)).collect();
+            let tag = tag.trim_end_matches('/');
+            if !tag.is_empty() && !tag.chars().all(|c| c.is_ascii_digit()) {
+                tags.push(tag.to_string());
+            }
+        }
+        prev = Some(c);
+    }
+
+    tags
+}
+
+/// Tags declared in frontmatter (`tags:` or `tag:`), with any leading `#` removed
+pub fn frontmatter_tags(content: &str) -> Vec<String> {
+    let mut tags = frontmatter_list(content, "tags");
+    tags.extend(frontmatter_list(content, "tag"));
+    tags.into_iter()
+        .flat_map(|t| {
+            // `tags: a b` is also accepted by Obsidian
+            t.split_whitespace().map(|s| s.trim_start_matches('#').to_string()).collect::<Vec<_>>()
+        })
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Alternative names declared in frontmatter (`aliases:` or `alias:`)
+pub fn frontmatter_aliases(content: &str) -> Vec<String> {
+    let mut aliases = frontmatter_list(content, "aliases");
+    aliases.extend(frontmatter_list(content, "alias"));
+    aliases
+}
+
+/// All tags in a note: frontmatter first, then inline
+pub fn extract_tags(content: &str) -> Vec<String> {
+    let mut tags = frontmatter_tags(content);
+    tags.extend(inline_tags(content));
+    tags
+}
+
+/// A `[[target#heading|alias]]` link or `![[embed]]`
+pub struct Wikilink {
+    /// Note name or path, empty for same-note `[[#Heading]]` links
+    pub target: String,
+    /// Byte range of `target` inside the content
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Wikilinks and embeds outside code
+pub fn extract_wikilinks(content: &str) -> Vec<Wikilink> {
+    let code = code_ranges(content);
+    let mut links = Vec::new();
+    let mut from = 0;
+
+    while let Some(rel) = content[from..].find("[[") {
+        let open = from + rel;
+        let Some(close_rel) = content[open + 2..].find("]]") else {
+            break;
+        };
+        let close = open + 2 + close_rel;
+        from = close + 2;
+
+        let inner = &content[open + 2..close];
+        if in_ranges(&code, open) || inner.contains('\n') || inner.is_empty() {
+            continue;
+        }
+
+        let link = inner.split_once('|').map(|(l, _)| l).unwrap_or(inner);
+        let target = link.split_once('#').map(|(t, _)| t).unwrap_or(link);
+        let start = open + 2 + (target.len() - target.trim_start().len());
+
+        links.push(Wikilink {
+            target: target.trim().to_string(),
+            start,
+            end: start + target.trim().len(),
+        });
+    }
+
+    links
+}
+
+/// Words in the note body (frontmatter excluded), counting runs that contain a letter or digit
+pub fn count_words(content: &str) -> usize {
+    body(content)
+        .split_whitespace()
+        .filter(|w| w.chars().any(char::is_alphanumeric))
+        .count()
+}
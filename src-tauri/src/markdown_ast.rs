@@ -0,0 +1,270 @@
+use pulldown_cmark::{CodeBlockKind, Event, LinkType, Options, Parser, Tag, TagEnd};
+use serde_json::{json, Map, Value};
+
+use crate::error::IgneError;
+
+/// Markdown flavour used by notes: GFM plus footnotes, math, wikilinks and YAML frontmatter
+pub fn parser_options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_GFM
+        | Options::ENABLE_MATH
+        | Options::ENABLE_WIKILINKS
+        | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+}
+
+/// Maps byte offsets to UTF-16 offsets, which is what editor positions in JS use
+struct Utf16Offsets(Vec<usize>);
+
+impl Utf16Offsets {
+    fn new(content: &str) -> Self {
+        let mut offsets = vec![0; content.len() + 1];
+        let mut utf16 = 0;
+        for (i, c) in content.char_indices() {
+            offsets[i] = utf16;
+            utf16 += c.len_utf16();
+        }
+        offsets[content.len()] = utf16;
+        Self(offsets)
+    }
+
+    fn get(&self, byte: usize) -> usize {
+        self.0[byte]
+    }
+}
+
+fn node(kind: &str, range: &std::ops::Range<usize>, offsets: &Utf16Offsets) -> Map<String, Value> {
+    let mut node = Map::new();
+    node.insert("type".into(), json!(kind));
+    node.insert("start".into(), json!(offsets.get(range.start)));
+    node.insert("end".into(), json!(offsets.get(range.end)));
+    node
+}
+
+fn container(tag: Tag, range: &std::ops::Range<usize>, offsets: &Utf16Offsets) -> Map<String, Value> {
+    let mut n = match tag {
+        Tag::Paragraph => node("Paragraph", range, offsets),
+        Tag::Heading { level, id, .. } => {
+            let mut n = node("Heading", range, offsets);
+            n.insert("level".into(), json!(level as u8));
+            if let Some(id) = id {
+                n.insert("id".into(), json!(id.as_ref()));
+            }
+            n
+        }
+        Tag::BlockQuote(kind) => {
+            let mut n = node("BlockQuote", range, offsets);
+            // GitHub-style `> [!NOTE]` alerts, the closest match to Obsidian callouts
+            n.insert("kind".into(), json!(kind.map(|k| format!("{:?}", k).to_lowercase())));
+            n
+        }
+        Tag::CodeBlock(kind) => {
+            let mut n = node("Code", range, offsets);
+            let language = match kind {
+                CodeBlockKind::Fenced(info) => info.split_whitespace().next().map(str::to_string),
+                CodeBlockKind::Indented => None,
+            };
+            n.insert("language".into(), json!(language));
+            n
+        }
+        Tag::HtmlBlock => node("HtmlBlock", range, offsets),
+        Tag::List(start) => {
+            let mut n = node("List", range, offsets);
+            n.insert("ordered".into(), json!(start.is_some()));
+            if let Some(number) = start {
+                n.insert("startNumber".into(), json!(number));
+            }
+            n
+        }
+        Tag::Item => node("ListItem", range, offsets),
+        Tag::FootnoteDefinition(label) => {
+            let mut n = node("FootnoteDefinition", range, offsets);
+            n.insert("label".into(), json!(label.as_ref()));
+            n
+        }
+        Tag::DefinitionList => node("DefinitionList", range, offsets),
+        Tag::DefinitionListTitle => node("DefinitionTitle", range, offsets),
+        Tag::DefinitionListDefinition => node("Definition", range, offsets),
+        Tag::Table(alignments) => {
+            let mut n = node("Table", range, offsets);
+            let alignments: Vec<String> = alignments
+                .iter()
+                .map(|a| format!("{:?}", a).to_lowercase())
+                .collect();
+            n.insert("alignments".into(), json!(alignments));
+            n
+        }
+        Tag::TableHead => node("TableHead", range, offsets),
+        Tag::TableRow => node("TableRow", range, offsets),
+        Tag::TableCell => node("TableCell", range, offsets),
+        Tag::Emphasis => node("Emphasis", range, offsets),
+        Tag::Strong => node("Strong", range, offsets),
+        Tag::Strikethrough => node("Strikethrough", range, offsets),
+        Tag::Superscript => node("Superscript", range, offsets),
+        Tag::Subscript => node("Subscript", range, offsets),
+        Tag::Link { link_type, dest_url, title, .. } => {
+            let mut n = node("Link", range, offsets);
+            n.insert("url".into(), json!(dest_url.as_ref()));
+            n.insert("title".into(), json!(title.as_ref()));
+            n.insert("wikilink".into(), json!(matches!(link_type, LinkType::WikiLink { .. })));
+            n
+        }
+        Tag::Image { link_type, dest_url, title, .. } => {
+            let mut n = node("Image", range, offsets);
+            n.insert("url".into(), json!(dest_url.as_ref()));
+            n.insert("title".into(), json!(title.as_ref()));
+            n.insert("wikilink".into(), json!(matches!(link_type, LinkType::WikiLink { .. })));
+            n
+        }
+        Tag::MetadataBlock(_) => node("Frontmatter", range, offsets),
+    };
+    n.insert("children".into(), json!([]));
+    n
+}
+
+fn leaf(kind: &str, value: &str, range: &std::ops::Range<usize>, offsets: &Utf16Offsets) -> Value {
+    let mut n = node(kind, range, offsets);
+    n.insert("value".into(), json!(value));
+    Value::Object(n)
+}
+
+/// Concatenated text of a node's descendants (image alt text, code block contents)
+fn plain_text(node: &Map<String, Value>) -> String {
+    let mut text = String::new();
+    if let Some(Value::String(value)) = node.get("value") {
+        text.push_str(value);
+    }
+    if let Some(Value::Array(children)) = node.get("children") {
+        for child in children.iter().filter_map(Value::as_object) {
+            text.push_str(&plain_text(child));
+        }
+    }
+    text
+}
+
+fn push_child(stack: &mut [Map<String, Value>], child: Value) {
+    if let Some(Value::Array(children)) = stack.last_mut().and_then(|n| n.get_mut("children")) {
+        children.push(child);
+    }
+}
+
+/// Build a JSON tree from the parser's event stream. Every node has `type`, `start` and
+/// `end` (UTF-16 offsets); containers have `children`, leaves have `value`.
+pub fn markdown_to_ast(content: &str) -> Value {
+    let offsets = Utf16Offsets::new(content);
+    let mut stack = vec![node("Document", &(0..content.len()), &offsets)];
+    stack[0].insert("children".into(), json!([]));
+
+    for (event, range) in Parser::new_ext(content, parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(tag) => stack.push(container(tag, &range, &offsets)),
+            Event::End(_) => {
+                let Some(mut finished) = stack.pop() else {
+                    continue;
+                };
+                match finished.get("type").and_then(Value::as_str) {
+                    Some("Image") => {
+                        let alt = plain_text(&finished);
+                        finished.insert("alt".into(), json!(alt));
+                    }
+                    Some("Code") | Some("Frontmatter") => {
+                        let value = plain_text(&finished);
+                        finished.remove("children");
+                        finished.insert("value".into(), json!(value));
+                    }
+                    _ => {}
+                }
+                push_child(&mut stack, Value::Object(finished));
+            }
+            Event::Text(text) => push_child(&mut stack, leaf("Text", &text, &range, &offsets)),
+            Event::Code(code) => push_child(&mut stack, leaf("InlineCode", &code, &range, &offsets)),
+            Event::InlineMath(math) => push_child(&mut stack, leaf("InlineMath", &math, &range, &offsets)),
+            Event::DisplayMath(math) => push_child(&mut stack, leaf("DisplayMath", &math, &range, &offsets)),
+            Event::Html(html) | Event::InlineHtml(html) => {
+                push_child(&mut stack, leaf("Html", &html, &range, &offsets))
+            }
+            Event::FootnoteReference(label) => {
+                let mut n = node("FootnoteReference", &range, &offsets);
+                n.insert("label".into(), json!(label.as_ref()));
+                push_child(&mut stack, Value::Object(n));
+            }
+            Event::SoftBreak => push_child(&mut stack, Value::Object(node("SoftBreak", &range, &offsets))),
+            Event::HardBreak => push_child(&mut stack, Value::Object(node("HardBreak", &range, &offsets))),
+            Event::Rule => push_child(&mut stack, Value::Object(node("Rule", &range, &offsets))),
+            Event::TaskListMarker(checked) => {
+                let mut n = node("TaskMarker", &range, &offsets);
+                n.insert("checked".into(), json!(checked));
+                push_child(&mut stack, Value::Object(n));
+            }
+        }
+    }
+
+    Value::Object(stack.swap_remove(0))
+}
+
+/// Parse note content into a JSON AST so the editor doesn't need its own markdown parser
+#[tauri::command]
+pub fn parse_markdown_to_ast(content: String) -> Result<Value, IgneError> {
+    Ok(markdown_to_ast(&content))
+}
+
+/// End the current line, dropping trailing spaces
+fn end_line(text: &mut String) {
+    text.truncate(text.trim_end_matches([' ', '\t']).len());
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+}
+
+/// End the current block with a blank line
+fn end_block(text: &mut String) {
+    end_line(text);
+    if !text.is_empty() && !text.ends_with("\n\n") {
+        text.push('\n');
+    }
+}
+
+/// The text of a note without markdown syntax, blocks separated by blank lines. Code
+/// keeps its contents, links and wikilinks their display text, images their alt text;
+/// frontmatter and HTML tags are dropped.
+pub fn markdown_to_text(content: &str) -> String {
+    let mut text = String::with_capacity(content.len());
+    let mut in_frontmatter = false;
+
+    for event in Parser::new_ext(content, parser_options()) {
+        match event {
+            Event::Start(Tag::MetadataBlock(_)) => in_frontmatter = true,
+            Event::End(TagEnd::MetadataBlock(_)) => in_frontmatter = false,
+            Event::Text(value) | Event::Code(value) | Event::InlineMath(value) | Event::DisplayMath(value)
+                if !in_frontmatter =>
+            {
+                text.push_str(&value)
+            }
+            Event::SoftBreak | Event::HardBreak => text.push('\n'),
+            Event::End(TagEnd::Item | TagEnd::TableRow | TagEnd::TableHead) => end_line(&mut text),
+            Event::End(TagEnd::TableCell) => text.push(' '),
+            Event::End(
+                TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::CodeBlock
+                | TagEnd::BlockQuote(_)
+                | TagEnd::List(_)
+                | TagEnd::Table
+                | TagEnd::FootnoteDefinition
+                | TagEnd::HtmlBlock,
+            )
+            | Event::Rule => end_block(&mut text),
+            _ => {}
+        }
+    }
+
+    text.trim().to_string()
+}
+
+/// Plain text of note content for search indexing, previews and plain-text export
+#[tauri::command]
+pub fn strip_markdown(content: String) -> Result<String, IgneError> {
+    Ok(markdown_to_text(&content))
+}
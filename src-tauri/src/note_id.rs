@@ -0,0 +1,76 @@
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::atomic_write;
+use crate::error::IgneError;
+use crate::markdown;
+use crate::paths::long_path;
+use crate::scope::ensure_in_scope;
+use crate::write_lock::with_write_lock;
+
+/// `content` with `id: <id>` as a frontmatter key: filled into an empty `id:` line when
+/// there is one, else added as the last key, creating the frontmatter when the note has
+/// none. Everything else is copied unchanged.
+fn insert_id(content: &str, id: &str) -> String {
+    let newline = if content.starts_with("---\r\n") || content.contains("\r\n") { "\r\n" } else { "\n" };
+    let line = format!("id: {}{}", id, newline);
+
+    let Some((inner_start, inner_end)) = markdown::frontmatter_inner_range(content) else {
+        return format!("---{nl}{}---{nl}{}", line, content, nl = newline);
+    };
+    let inner = &content[inner_start..inner_end];
+
+    // The first `id:` line is the one `frontmatter_value` reads, so an empty one is replaced
+    let mut offset = inner_start;
+    for existing in inner.split_inclusive('\n') {
+        let text = existing.trim_end_matches(['\r', '\n']);
+        if text.strip_prefix("id").is_some_and(|rest| rest.starts_with(':')) {
+            let ending = &existing[text.len()..];
+            return format!("{}id: {}{}{}", &content[..offset], id, ending, &content[offset + existing.len()..]);
+        }
+        offset += existing.len();
+    }
+
+    let separator = if inner.is_empty() || inner.ends_with('\n') { "" } else { newline };
+    format!("{}{}{}{}", &content[..inner_end], separator, line, &content[inner_end..])
+}
+
+/// The note's stable id from its frontmatter `id:` field. A note without one gets a new
+/// UUID written into its frontmatter, so references to it can survive renames and moves.
+#[tauri::command]
+pub async fn get_or_create_note_id(path: String, app: AppHandle) -> Result<String, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+
+    with_write_lock(&app, &path_obj, || {
+        let content = fs::read_to_string(long_path(&path_obj)).map_err(|e| IgneError::io(e, &path))?;
+        if let Some(id) = markdown::frontmatter_value(&content, "id") {
+            return Ok(id.to_string());
+        }
+
+        let id = Uuid::new_v4().to_string();
+        atomic_write(&path_obj, insert_id(&content, &id).as_bytes())?;
+        Ok(id)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_an_id_key() {
+        assert_eq!(insert_id("Body\n", "abc"), "---\nid: abc\n---\nBody\n");
+        assert_eq!(insert_id("---\ntitle: A\n---\nBody", "abc"), "---\ntitle: A\nid: abc\n---\nBody");
+    }
+
+    #[test]
+    fn fills_an_empty_id_key() {
+        assert_eq!(insert_id("---\nid:\ntags: [a]\n---\n", "abc"), "---\nid: abc\ntags: [a]\n---\n");
+        assert_eq!(insert_id("---\r\nid: \"\"\r\n---\r\n", "abc"), "---\r\nid: abc\r\n---\r\n");
+        assert_eq!(insert_id("---\nidea: x\n---\n", "abc"), "---\nidea: x\nid: abc\n---\n");
+    }
+}
@@ -0,0 +1,125 @@
+use serde::Serialize;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::attachments::{AUDIO_EXTENSIONS, VIDEO_EXTENSIONS};
+use crate::error::IgneError;
+use crate::images::IMAGE_EXTENSIONS;
+use crate::is_markdown_file;
+use crate::paths::long_path;
+use crate::scope::ensure_in_scope;
+
+/// How much of a markdown file `detect` reads to find the Excalidraw frontmatter key
+const HEAD_BYTES: u64 = 1024;
+
+/// What a file is to the app, beyond its extension
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteKind {
+    Markdown,
+    /// Obsidian's `.canvas` JSON boards
+    Canvas,
+    /// Excalidraw drawings: plugin notes (`excalidraw-plugin` in the frontmatter, usually
+    /// named `.excalidraw.md`) and plain `.excalidraw` JSON files
+    Excalidraw,
+    /// Images, audio, video and PDFs, which notes embed
+    Attachment,
+    Other,
+}
+
+impl NoteKind {
+    /// Opened as a document in a tab, e.g. when passed on the command line. Canvases and
+    /// drawings join once the app has viewers for them.
+    pub fn opens_in_app(self) -> bool {
+        self == NoteKind::Markdown
+    }
+}
+
+/// The kind going by the file name alone, which can't tell a drawing that was renamed to
+/// plain `.md` from a note
+pub fn from_name(name: &str) -> NoteKind {
+    let lower = name.to_lowercase();
+    if lower.ends_with(".excalidraw.md") || lower.ends_with(".excalidraw") {
+        return NoteKind::Excalidraw;
+    }
+    if is_markdown_file(&lower) {
+        return NoteKind::Markdown;
+    }
+    let ext = lower.rsplit_once('.').map_or("", |(_, ext)| ext);
+    if ext == "canvas" {
+        NoteKind::Canvas
+    } else if ext == "pdf"
+        || IMAGE_EXTENSIONS.contains(&ext)
+        || AUDIO_EXTENSIONS.contains(&ext)
+        || VIDEO_EXTENSIONS.contains(&ext)
+    {
+        NoteKind::Attachment
+    } else {
+        NoteKind::Other
+    }
+}
+
+/// Whether the start of a note has `excalidraw-plugin` in its frontmatter. The head may end
+/// inside the frontmatter, so the closing `---` isn't required.
+fn has_excalidraw_key(head: &str) -> bool {
+    let mut lines = head.trim_start_matches('\u{feff}').lines();
+    if lines.next().map(str::trim_end) != Some("---") {
+        return false;
+    }
+    lines
+        .take_while(|line| line.trim_end() != "---")
+        .any(|line| line.starts_with("excalidraw-plugin:"))
+}
+
+/// The kind of a note whose content is already read
+pub fn from_content(name: &str, content: &str) -> NoteKind {
+    match from_name(name) {
+        NoteKind::Markdown if has_excalidraw_key(content) => NoteKind::Excalidraw,
+        kind => kind,
+    }
+}
+
+/// The kind of the file at `path`, reading the start of markdown files for the Excalidraw
+/// frontmatter key
+pub fn detect(path: &Path) -> NoteKind {
+    let kind = from_name(&path.to_string_lossy());
+    if kind != NoteKind::Markdown {
+        return kind;
+    }
+    let mut head = vec![];
+    let read = File::open(path).and_then(|file| file.take(HEAD_BYTES).read_to_end(&mut head));
+    if read.is_ok() && has_excalidraw_key(&String::from_utf8_lossy(&head)) {
+        NoteKind::Excalidraw
+    } else {
+        kind
+    }
+}
+
+/// The kind of one file including what `detect` finds inside it, for the file about to
+/// be opened; the file tree only goes by names
+#[tauri::command]
+pub fn get_note_kind(path: String, app: AppHandle) -> Result<NoteKind, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+    Ok(detect(&long_path(&path_obj)))
+}
+
+/// The part of a note that's meant to be read: for Excalidraw plugin notes the frontmatter,
+/// text elements and element links, without the drawing's JSON under `# Drawing`
+pub fn readable_text<'a>(name: &str, content: &'a str) -> &'a str {
+    if from_content(name, content) != NoteKind::Excalidraw {
+        return content;
+    }
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if matches!(line.trim(), "# Drawing" | "## Drawing") {
+            // The drawing sits in a `%%` comment opened on the line before
+            let before = content[..offset].trim_end();
+            return before.strip_suffix("%%").unwrap_or(before);
+        }
+        offset += line.len();
+    }
+    content
+}
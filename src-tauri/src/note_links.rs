@@ -0,0 +1,152 @@
+use pulldown_cmark::{Event, LinkType, Parser, Tag};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::{AppHandle, State};
+
+use crate::error::IgneError;
+use crate::markdown_ast::parser_options;
+use crate::scope::ensure_in_scope;
+use crate::{links, markdown, note_kind};
+
+#[derive(Serialize, Clone, Default)]
+pub struct NoteLinks {
+    /// `[[wikilink]]` targets and relative markdown link destinations
+    pub links: Vec<String>,
+    /// `![[embed]]` targets and local image destinations
+    pub embeds: Vec<String>,
+    pub external_urls: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+/// What one parse of a note yields: its links, plus what the file tree shows next to it
+#[derive(Clone, Default)]
+pub struct NoteSummary {
+    pub links: NoteLinks,
+    /// Unchecked `- [ ]` tasks
+    pub open_tasks: u64,
+    /// The frontmatter block, delimiters included, for the `markdown::frontmatter_*` lookups
+    pub frontmatter: Option<String>,
+}
+
+/// Note summaries keyed by path and checked against the file's mtime; the directory
+/// watcher also drops entries under a root whenever it reports a change there
+pub struct NoteLinksCache {
+    entries: Mutex<HashMap<PathBuf, (SystemTime, NoteSummary)>>,
+}
+
+impl NoteLinksCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn invalidate_under(&self, root: &Path) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.retain(|path, _| !path.starts_with(root));
+        }
+    }
+
+    /// The note's links, parsed again only when its mtime changed
+    pub fn get(&self, path: &Path) -> Result<NoteLinks, IgneError> {
+        Ok(self.summary(path)?.links)
+    }
+
+    /// The note's whole summary, parsed again only when its mtime changed
+    pub fn summary(&self, path: &Path) -> Result<NoteSummary, IgneError> {
+        let modified = fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map_err(|e| IgneError::io(e, path))?;
+        if let Some((cached_at, summary)) = self.entries.lock()?.get(path) {
+            if *cached_at == modified {
+                return Ok(summary.clone());
+            }
+        }
+
+        let content = fs::read_to_string(path).map_err(|e| IgneError::io(e, path))?;
+        let summary = summarize(note_kind::readable_text(&path.to_string_lossy(), &content));
+        self.entries.lock()?.insert(path.to_path_buf(), (modified, summary.clone()));
+        Ok(summary)
+    }
+}
+
+impl Default for NoteLinksCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_url(dest: &str) -> bool {
+    dest.contains("://") || dest.starts_with("mailto:")
+}
+
+fn push_unique(list: &mut Vec<String>, value: String) {
+    if !value.is_empty() && !list.contains(&value) {
+        list.push(value);
+    }
+}
+
+/// Wikilink destinations carry the heading (`Note#Heading`); only the note is a link target
+fn wikilink_target(dest: &str) -> String {
+    dest.split_once('#').map(|(t, _)| t).unwrap_or(dest).trim().to_string()
+}
+
+fn summarize(content: &str) -> NoteSummary {
+    let mut summary = NoteLinks::default();
+    let mut open_tasks = 0;
+
+    for event in Parser::new_ext(content, parser_options()) {
+        let (is_embed, link_type, dest) = match event {
+            Event::Start(Tag::Link { link_type, dest_url, .. }) => (false, link_type, dest_url),
+            Event::Start(Tag::Image { link_type, dest_url, .. }) => (true, link_type, dest_url),
+            Event::TaskListMarker(false) => {
+                open_tasks += 1;
+                continue;
+            }
+            _ => continue,
+        };
+
+        if is_url(&dest) {
+            push_unique(&mut summary.external_urls, dest.to_string());
+            continue;
+        }
+
+        let target = match link_type {
+            LinkType::WikiLink { .. } => wikilink_target(&dest),
+            // `#heading` links stay within the note
+            _ if dest.starts_with('#') => continue,
+            _ => links::percent_decode(&dest),
+        };
+        if is_embed {
+            push_unique(&mut summary.embeds, target);
+        } else {
+            push_unique(&mut summary.links, target);
+        }
+    }
+
+    for tag in markdown::extract_tags(content) {
+        push_unique(&mut summary.tags, tag);
+    }
+    NoteSummary {
+        links: summary,
+        open_tasks,
+        frontmatter: markdown::frontmatter_range(content).map(|(start, end)| content[start..end].to_string()),
+    }
+}
+
+/// Outgoing links, embeds, external URLs and tags of a note in one parse, cached until
+/// the file changes
+#[tauri::command]
+pub fn get_note_links(
+    path: String,
+    app: AppHandle,
+    cache: State<'_, NoteLinksCache>,
+) -> Result<NoteLinks, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+    cache.get(&path_obj)
+}
@@ -0,0 +1,473 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::error::IgneError;
+use crate::paths::{long_path, normalize_relative, slash_relative, vault_relative};
+use crate::scope::ensure_in_scope;
+use crate::vault::walk_vault_files;
+use crate::watch_pause;
+use crate::write_lock::with_write_lock;
+use crate::{atomic_write, blocking, is_markdown_file, links, markdown, move_checked};
+
+#[derive(Serialize, Clone, Default)]
+pub struct MoveReport {
+    pub links_updated: u64,
+    /// Notes whose content was rewritten
+    pub files_touched: Vec<String>,
+}
+
+/// Where `path` ends up when `from` (a file or folder) moves to `to`
+fn relocate(path: &str, from: &str, to: &str) -> String {
+    match path.strip_prefix(from) {
+        Some("") => to.to_string(),
+        Some(rest) if rest.starts_with('/') => format!("{}{}", to, rest),
+        _ => path.to_string(),
+    }
+}
+
+fn parent_dir(relative: &str) -> &str {
+    relative.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+fn file_name(relative: &str) -> &str {
+    relative.rsplit_once('/').map_or(relative, |(_, name)| name)
+}
+
+/// The vault's files as vault-relative paths, e.g. either side of a move
+pub struct VaultFiles {
+    files: Vec<String>,
+    lowercase: HashSet<String>,
+    /// Indexes into `files` by lowercased file name, so resolving a wikilink only looks at
+    /// files that can match
+    by_name: HashMap<String, Vec<usize>>,
+}
+
+impl VaultFiles {
+    fn new(files: Vec<String>) -> Self {
+        let lowercase = files.iter().map(|f| f.to_lowercase()).collect();
+        let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, file) in files.iter().enumerate() {
+            by_name.entry(file_name(file).to_lowercase()).or_default().push(i);
+        }
+        Self { files, lowercase, by_name }
+    }
+
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    pub fn scan(vault: &Path) -> Result<Self, IgneError> {
+        Ok(Self::new(
+            walk_vault_files(vault)?
+                .iter()
+                .filter_map(|file| slash_relative(vault, file))
+                .collect(),
+        ))
+    }
+
+    fn contains(&self, relative: &str) -> bool {
+        self.lowercase.contains(&relative.to_lowercase())
+    }
+
+    /// Resolve a wikilink target from a note in `source_dir` like Obsidian: the file whose
+    /// path ends with the target (`.md` optional), preferring the note's own folder, then
+    /// the shortest path
+    pub fn resolve_wikilink(&self, target: &str, source_dir: &str) -> Option<String> {
+        let target = target.trim_start_matches('/').to_lowercase();
+        for name in [format!("{}.md", target), target] {
+            let suffix = format!("/{}", name);
+            let local = join(source_dir, &name).to_lowercase();
+            let candidates = self.by_name.get(file_name(&name)).map_or(&[][..], Vec::as_slice);
+            let matches = candidates.iter().map(|&i| &self.files[i]).filter(|f| {
+                let f = f.to_lowercase();
+                f == name || f.ends_with(&suffix)
+            });
+            let best = matches.min_by_key(|f| (f.to_lowercase() != local, f.matches('/').count(), f.len()));
+            if let Some(found) = best {
+                return Some(found.clone());
+            }
+        }
+        None
+    }
+
+    /// Resolve a markdown link destination: relative to the note, else from the vault root
+    /// (Obsidian's "absolute path in vault" style, flagged by the `bool`)
+    fn resolve_markdown(&self, decoded: &str, source_dir: &str) -> Option<(String, bool)> {
+        if let Some(relative) = normalize_relative(&join(source_dir, decoded)).filter(|r| self.contains(r)) {
+            return Some((relative, false));
+        }
+        normalize_relative(decoded)
+            .filter(|rooted| self.contains(rooted))
+            .map(|rooted| (rooted, true))
+    }
+
+    /// Resolve a target from `NoteLinks::links`, which doesn't say whether it was written as
+    /// a wikilink or a markdown link: a markdown path when one exists, else a wikilink
+    pub fn resolve_link(&self, target: &str, source_dir: &str) -> Option<String> {
+        self.resolve_markdown(target, source_dir)
+            .map(|(relative, _)| relative)
+            .or_else(|| self.resolve_wikilink(target, source_dir))
+    }
+
+    /// Shortest wikilink text that resolves to `target` from `source_dir`: the bare name
+    /// when unambiguous, else the full vault path
+    fn link_text(&self, target: &str, source_dir: &str, keep_extension: bool) -> String {
+        let stem = match target.strip_suffix(".md") {
+            Some(stem) if !keep_extension => stem,
+            _ => target,
+        };
+        let name = stem.rsplit_once('/').map_or(stem, |(_, name)| name);
+        if self.resolve_wikilink(name, source_dir).as_deref() == Some(target) {
+            name.to_string()
+        } else {
+            stem.to_string()
+        }
+    }
+}
+
+/// Rewrite the links in `content`, the note at `before` that is now at `after`, so each
+/// still points at the file it did before `moved.0` (a note or folder) went to `moved.1`.
+/// Returns the new content and how many links changed.
+fn rewrite_links(
+    content: &str,
+    before: &str,
+    after: &str,
+    moved: (&str, &str),
+    files: (&VaultFiles, &VaultFiles),
+) -> (String, u64) {
+    let (old_files, new_files) = files;
+    let (dir_before, dir_after) = (parent_dir(before), parent_dir(after));
+    let relocate = |target: String| relocate(&target, moved.0, moved.1);
+
+    let mut edits: Vec<(usize, usize, String)> = vec![];
+
+    for link in markdown::extract_wikilinks(content) {
+        if link.target.is_empty() {
+            continue;
+        }
+        let Some(intended) = old_files.resolve_wikilink(&link.target, dir_before).map(relocate) else {
+            continue;
+        };
+        if new_files.resolve_wikilink(&link.target, dir_after).as_deref() != Some(&intended) {
+            let keep_extension = link.target.to_lowercase().ends_with(".md");
+            edits.push((link.start, link.end, new_files.link_text(&intended, dir_after, keep_extension)));
+        }
+    }
+
+    edits.extend(links::relink_markdown_links(content, |decoded| {
+        let (intended, rooted) = old_files.resolve_markdown(decoded, dir_before)?;
+        let intended = relocate(intended);
+        if new_files.resolve_markdown(decoded, dir_after).is_some_and(|(target, _)| target == intended) {
+            return None;
+        }
+        // Vault-rooted links stay vault-rooted; note-relative ones stay relative
+        Some(if rooted {
+            intended
+        } else {
+            links::relative_path(Path::new(dir_after), Path::new(&intended))
+        })
+    }));
+
+    edits.sort_by_key(|&(start, _, _)| start);
+    let mut result = String::with_capacity(content.len());
+    let mut last = 0;
+    let mut count = 0;
+    for (start, end, replacement) in edits {
+        if start < last {
+            continue;
+        }
+        result.push_str(&content[last..start]);
+        result.push_str(&replacement);
+        last = end;
+        count += 1;
+    }
+    result.push_str(&content[last..]);
+    (result, count)
+}
+
+/// Move a note within a vault and, unless `update_links` is false, rewrite every link in
+/// the vault that would otherwise resolve differently afterwards: links to the note
+/// itself, links from the note, and name-only wikilinks the move made ambiguous
+#[tauri::command]
+pub async fn move_note(
+    vault_path: String,
+    old_path: String,
+    new_path: String,
+    update_links: Option<bool>,
+    app: AppHandle,
+) -> Result<MoveReport, IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    let (old, new) = (PathBuf::from(&old_path), PathBuf::from(&new_path));
+    ensure_in_scope(&app, &vault)?;
+    if !long_path(&old).is_file() {
+        return Err(IgneError::not_found(format!("Note does not exist: {}", old_path), &old_path));
+    }
+
+    if !update_links.unwrap_or(true) {
+        move_checked(&app, &old_path, &new_path, false, false).await?;
+        return Ok(MoveReport::default());
+    }
+
+    let (moved_from, moved_to) = (vault_relative(&vault, &old)?, vault_relative(&vault, &new)?);
+
+    let files = blocking({
+        let vault = vault.clone();
+        move || VaultFiles::scan(&vault)
+    })
+    .await?;
+    // One change notice for the move and every rewritten note, sent when this returns
+    let _pause = watch_pause::pause(&app, Some(vec![vault.clone()]));
+    move_checked(&app, &old_path, &new_path, false, false).await?;
+    update_vault_links(&app, &vault, &files, (&moved_from, &moved_to)).await
+}
+
+/// After `moved.0` was moved to `moved.1`, rewrite the links of every note in `old_files`
+/// (the vault as it was before the move) that no longer resolve to the same file
+async fn update_vault_links(
+    app: &AppHandle,
+    vault: &Path,
+    old_files: &VaultFiles,
+    moved: (&str, &str),
+) -> Result<MoveReport, IgneError> {
+    let new_files = VaultFiles::new(
+        old_files
+            .files
+            .iter()
+            .map(|f| relocate(f, moved.0, moved.1))
+            .collect(),
+    );
+
+    let mut report = MoveReport::default();
+    for before in &old_files.files {
+        if !is_markdown_file(before) {
+            continue;
+        }
+        let after = relocate(before, moved.0, moved.1);
+        let path = vault.join(&after);
+
+        // Each note is read and rewritten under its write lock so a concurrent save isn't lost
+        let updated = with_write_lock(app, &path, || {
+            let Ok(content) = fs::read_to_string(long_path(&path)) else {
+                return Ok(0);
+            };
+            let (rewritten, count) = rewrite_links(&content, before, &after, moved, (old_files, &new_files));
+            if count > 0 {
+                atomic_write(&path, rewritten.as_bytes())?;
+            }
+            Ok(count)
+        })
+        .await?;
+
+        if updated > 0 {
+            report.links_updated += updated;
+            report.files_touched.push(path.to_string_lossy().to_string());
+        }
+    }
+
+    Ok(report)
+}
+
+/// A folder can only be renamed onto a path that doesn't exist yet
+fn check_folder_rename(old_path: &str, new_path: &str) -> Result<(), IgneError> {
+    if !long_path(Path::new(old_path)).is_dir() {
+        return Err(IgneError::not_a_directory(format!("Not a directory: {}", old_path), old_path));
+    }
+    if long_path(Path::new(new_path)).exists() {
+        return Err(IgneError::already_exists(format!("Path already exists: {}", new_path), new_path));
+    }
+    Ok(())
+}
+
+/// Rename or move a folder within a vault and rewrite the links across the vault that
+/// pointed into it: path-based markdown links and embeds, and wikilinks written with a
+/// folder path. Name-only wikilinks still resolve and are left alone. Returns how many
+/// notes were rewritten.
+#[tauri::command]
+pub async fn rename_folder(
+    old_path: String,
+    new_path: String,
+    vault_root: String,
+    app: AppHandle,
+) -> Result<u64, IgneError> {
+    let vault = PathBuf::from(&vault_root);
+    let (old, new) = (PathBuf::from(&old_path), PathBuf::from(&new_path));
+    ensure_in_scope(&app, &vault)?;
+    check_folder_rename(&old_path, &new_path)?;
+
+    let (moved_from, moved_to) = (vault_relative(&vault, &old)?, vault_relative(&vault, &new)?);
+    if moved_from.is_empty() || moved_to.is_empty() {
+        return Err(IgneError::protected(format!("Can't rename the vault root: {}", vault_root), &vault_root));
+    }
+
+    let files = blocking({
+        let vault = vault.clone();
+        move || VaultFiles::scan(&vault)
+    })
+    .await?;
+    // One change notice for the move and every rewritten note, sent when this returns
+    let _pause = watch_pause::pause(&app, Some(vec![vault.clone()]));
+    move_checked(&app, &old_path, &new_path, false, false).await?;
+    let report = update_vault_links(&app, &vault, &files, (&moved_from, &moved_to)).await?;
+    Ok(report.files_touched.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rewrite `content`, the note at `note`, for the move of `moved.0` to `moved.1` in a
+    /// vault holding `files`
+    fn rewrite(files: &[&str], moved: (&str, &str), note: &str, content: &str) -> (String, u64) {
+        let old_files = VaultFiles::new(files.iter().map(|f| f.to_string()).collect());
+        let new_files = VaultFiles::new(files.iter().map(|f| relocate(f, moved.0, moved.1)).collect());
+        let after = relocate(note, moved.0, moved.1);
+        rewrite_links(content, note, &after, moved, (&old_files, &new_files))
+    }
+
+    #[test]
+    fn resolves_wikilinks_like_obsidian() {
+        let files = VaultFiles::new(
+            ["Note.md", "a/Note.md", "a/b/Deep.md", "img.png"]
+                .iter()
+                .map(|f| f.to_string())
+                .collect(),
+        );
+        let cases = [
+            ("Note", "", Some("Note.md")),
+            ("Note", "a", Some("a/Note.md")),
+            ("note.md", "", Some("Note.md")),
+            ("b/Deep", "", Some("a/b/Deep.md")),
+            ("img.png", "a", Some("img.png")),
+            ("Missing", "", None),
+        ];
+        for (target, source_dir, expected) in cases {
+            assert_eq!(files.resolve_wikilink(target, source_dir).as_deref(), expected, "{}", target);
+        }
+    }
+
+    #[test]
+    fn rewrites_links_the_move_broke() {
+        let cases = [
+            // A name-only link made ambiguous by a new file of the same name
+            (
+                &["a/Target.md", "c/x.md", "index.md"][..],
+                ("c/x.md", "Target.md"),
+                "index.md",
+                "[[Target]]",
+                "[[a/Target]]",
+            ),
+            // Name-only links to the moved note still resolve
+            (&["notes/A.md", "B.md"], ("notes/A.md", "archive/A.md"), "B.md", "[[A]]", "[[A]]"),
+            // Fragments and percent-encoding are kept
+            (
+                &["My Note.md", "docs/ref.md"],
+                ("My Note.md", "archive/My Note.md"),
+                "docs/ref.md",
+                "[x](../My%20Note.md#Heading)",
+                "[x](../archive/My%20Note.md#Heading)",
+            ),
+            (
+                &["My Note.md", "docs/ref.md"],
+                ("My Note.md", "archive/My Note.md"),
+                "docs/ref.md",
+                "[x](<../My Note.md>)",
+                "[x](<../archive/My Note.md>)",
+            ),
+            // Vault-rooted links stay vault-rooted
+            (
+                &["My Note.md", "docs/ref.md"],
+                ("My Note.md", "archive/My Note.md"),
+                "docs/ref.md",
+                "[x](My%20Note.md)",
+                "[x](archive/My%20Note.md)",
+            ),
+            // `.md` written in a wikilink stays written
+            (&["a/Note.md", "index.md"], ("a/Note.md", "c/Note.md"), "index.md", "[[a/Note.md]]", "[[Note.md]]"),
+            // The moved note's own links: to itself, and relative ones that need a new path
+            (
+                &["a/Self.md", "img.png"],
+                ("a/Self.md", "b/c/Self.md"),
+                "a/Self.md",
+                "[[Self]] [x](Self.md) ![y](../img.png)",
+                "[[Self]] [x](Self.md) ![y](../../img.png)",
+            ),
+            // A wikilink inside a markdown destination: only the outer edit applies
+            (
+                &["dir/Note.md", "dir/[[dir/Note]].md", "index.md"],
+                ("dir", "other"),
+                "index.md",
+                "[x](dir/[[dir/Note]].md)",
+                "[x](other/[[dir/Note]].md)",
+            ),
+            // External links and same-note headings are left alone
+            (
+                &["a.md", "b.md"],
+                ("a.md", "z/a.md"),
+                "b.md",
+                "[w](https://example.com/a.md) [h](#a) [[#a]]",
+                "[w](https://example.com/a.md) [h](#a) [[#a]]",
+            ),
+        ];
+        for (files, moved, note, content, expected) in cases {
+            let (rewritten, count) = rewrite(files, moved, note, content);
+            assert_eq!(rewritten, expected, "{:?} in {}", moved, note);
+            assert_eq!(count > 0, content != expected, "{:?} in {}", moved, note);
+        }
+    }
+
+    #[test]
+    fn relocates_only_whole_folder_prefixes() {
+        let cases = [
+            ("a/b/c.md", "a/b", "x", "x/c.md"),
+            ("a/b", "a/b", "x", "x"),
+            ("a/bc.md", "a/b", "x", "a/bc.md"),
+            ("a/b.md", "a/b", "x", "a/b.md"),
+            ("z/a/b/c.md", "a/b", "x", "z/a/b/c.md"),
+        ];
+        for (path, from, to, expected) in cases {
+            assert_eq!(relocate(path, from, to), expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn rewrites_folder_paths_in_wikilinks() {
+        let files = &["a/b/Note.md", "a/c/Note.md", "Note.md", "index.md"];
+        let cases = [
+            ("[[a/b/Note]]", "[[z/Note]]"),
+            ("[[a/b/Note#Sec|alias]]", "[[z/Note#Sec|alias]]"),
+            ("![[a/b/Note.md]]", "![[z/Note.md]]"),
+            ("[[a/c/Note]] [[Note]]", "[[a/c/Note]] [[Note]]"),
+            ("[x](a/b/Note.md)", "[x](z/Note.md)"),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(rewrite(files, ("a/b", "z"), "index.md", content).0, expected, "{}", content);
+        }
+    }
+
+    #[test]
+    fn refuses_to_rename_onto_an_existing_path() {
+        let dir = std::env::temp_dir().join(format!("igne-note-move-{}", std::process::id()));
+        fs::create_dir_all(dir.join("old")).unwrap();
+        fs::create_dir_all(dir.join("taken")).unwrap();
+        fs::write(dir.join("note.md"), "").unwrap();
+        let path = |name: &str| dir.join(name).to_string_lossy().into_owned();
+
+        assert!(check_folder_rename(&path("old"), &path("new")).is_ok());
+        let err = check_folder_rename(&path("old"), &path("taken")).unwrap_err();
+        assert_eq!(err.code(), "ALREADY_EXISTS");
+        let err = check_folder_rename(&path("note.md"), &path("new")).unwrap_err();
+        assert_eq!(err.code(), "NOT_A_DIRECTORY");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+use crate::error::IgneError;
+use crate::note_kind::{self, NoteKind};
+use crate::scope::ensure_in_scope;
+use crate::vault::walk_vault_files;
+use crate::{is_markdown_file, markdown, toc, WatcherState};
+
+#[derive(Serialize, Clone)]
+pub struct NoteTitle {
+    pub path: String,
+    /// First H1, else the frontmatter `title`, else the file name without extension
+    pub title: String,
+    pub aliases: Vec<String>,
+    /// Markdown, Excalidraw or Canvas
+    pub kind: NoteKind,
+}
+
+/// Titles per watched root. An entry is only trusted while that root's watcher is alive,
+/// since the watcher is what drops it when files change.
+pub struct NoteTitleCache {
+    roots: Mutex<HashMap<String, Vec<NoteTitle>>>,
+}
+
+impl NoteTitleCache {
+    pub fn new() -> Self {
+        Self {
+            roots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn invalidate(&self, root: &str) {
+        if let Ok(mut roots) = self.roots.lock() {
+            roots.remove(root);
+        }
+    }
+}
+
+impl Default for NoteTitleCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// First H1, else the frontmatter `title`, else the file name without extension. Drawings
+/// skip the H1, which is their `# Text Elements` section.
+fn display_title(path: &Path, content: &str) -> String {
+    let is_drawing = note_kind::from_content(&path.to_string_lossy(), content) == NoteKind::Excalidraw;
+    (!is_drawing)
+        .then(|| toc::first_h1(content))
+        .flatten()
+        .or_else(|| markdown::frontmatter_value(content, "title").map(str::to_string))
+        .unwrap_or_else(|| file_stem(path))
+}
+
+/// Lines that can start or complete an H1: `# Title` or a setext `===` underline
+fn may_complete_h1(line: &str) -> bool {
+    let line = line.trim_start_matches(' ').trim_end();
+    let atx = line == "#" || line.starts_with("# ") || line.starts_with("#\t");
+    let setext = line.starts_with('=') && line.chars().all(|c| c == '=');
+    atx || setext
+}
+
+/// `display_title`, reading the file only up to its first H1
+fn read_title(path: &Path) -> io::Result<String> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut content = String::new();
+    let mut line = String::new();
+
+    while reader.read_line(&mut line)? > 0 {
+        let candidate = may_complete_h1(&line);
+        content.push_str(&line);
+        line.clear();
+        if candidate && note_kind::from_content(&path.to_string_lossy(), &content) != NoteKind::Excalidraw {
+            if let Some(title) = toc::first_h1(&content) {
+                return Ok(title);
+            }
+        }
+    }
+
+    Ok(display_title(path, &content))
+}
+
+fn note_title(path: &Path, content: &str) -> NoteTitle {
+    NoteTitle {
+        path: path.to_string_lossy().to_string(),
+        title: display_title(path, content),
+        aliases: markdown::frontmatter_aliases(content),
+        kind: note_kind::from_content(&path.to_string_lossy(), content),
+    }
+}
+
+fn collect_titles(root: &Path) -> Result<Vec<NoteTitle>, IgneError> {
+    let mut titles: Vec<NoteTitle> = walk_vault_files(root)?
+        .into_iter()
+        .filter_map(|file| match note_kind::from_name(&file.to_string_lossy()) {
+            // Canvases are linked by file name; their JSON has no title to read
+            NoteKind::Canvas => Some(NoteTitle {
+                path: file.to_string_lossy().to_string(),
+                title: file_stem(&file),
+                aliases: vec![],
+                kind: NoteKind::Canvas,
+            }),
+            _ if is_markdown_file(&file.to_string_lossy()) => {
+                let content = fs::read_to_string(&file).ok()?;
+                Some(note_title(&file, &content))
+            }
+            _ => None,
+        })
+        .collect();
+    titles.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(titles)
+}
+
+/// Title and aliases of every note and canvas under `root`, for `[[` autocomplete. Hidden folders
+/// are skipped. Results for a watched root are cached until the watcher sees a change.
+#[tauri::command]
+pub fn list_note_titles(
+    root: String,
+    app: AppHandle,
+    cache: State<'_, NoteTitleCache>,
+    watcher_state: State<'_, WatcherState>,
+) -> Result<Vec<NoteTitle>, IgneError> {
+    ensure_in_scope(&app, Path::new(&root))?;
+    cached_titles(&root, &cache, &watcher_state)
+}
+
+/// The notes under `root` with their titles, from the cache while `root` is watched
+pub fn cached_titles(
+    root: &str,
+    cache: &NoteTitleCache,
+    watcher_state: &WatcherState,
+) -> Result<Vec<NoteTitle>, IgneError> {
+    let watched = watcher_state.is_watching(root);
+    if watched {
+        if let Some(titles) = cache.roots.lock()?.get(root) {
+            return Ok(titles.clone());
+        }
+    }
+
+    let titles = collect_titles(Path::new(root))?;
+    if watched {
+        cache.roots.lock()?.insert(root.to_string(), titles.clone());
+    }
+    Ok(titles)
+}
+
+/// Display title of a note, the same one `list_note_titles` reports
+#[tauri::command]
+pub fn get_note_title(path: String, app: AppHandle) -> Result<String, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+    read_title(&path_obj).map_err(|e| IgneError::io(e, &path))
+}
@@ -0,0 +1,45 @@
+use std::path::{Component, Path};
+use tauri::path::BaseDirectory;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::error::IgneError;
+
+/// Bundled icon for a notification. Only plain relative paths inside the app's resources
+/// are accepted, so a caller can't point the OS at arbitrary files.
+fn resolve_icon(app: &AppHandle, icon: &str) -> Result<String, IgneError> {
+    let relative = Path::new(icon);
+    if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(IgneError::invalid_path(
+            format!("Notification icon must be a path inside the app bundle: {}", icon),
+            icon,
+        ));
+    }
+    let path = app
+        .path()
+        .resolve(relative, BaseDirectory::Resource)
+        .map_err(|e| IgneError::other(format!("Failed to resolve notification icon: {}", e)))?;
+    if !path.is_file() {
+        return Err(IgneError::not_found(format!("Notification icon not found: {}", icon), &path));
+    }
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Post a notification to the OS notification center (reminders, sync results). Unlike
+/// in-app toasts it shows while the window is in the background. `icon` is relative to
+/// the app's bundled resources; the app icon is used without it.
+#[tauri::command]
+pub fn show_notification(
+    title: String,
+    body: String,
+    icon: Option<String>,
+    app: AppHandle,
+) -> Result<(), IgneError> {
+    let mut notification = app.notification().builder().title(title).body(body);
+    if let Some(icon) = icon {
+        notification = notification.icon(resolve_icon(&app, &icon)?);
+    }
+    notification
+        .show()
+        .map_err(|e| IgneError::other(format!("Failed to show notification: {}", e)))
+}
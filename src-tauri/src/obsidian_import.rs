@@ -0,0 +1,174 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::error::IgneError;
+use crate::scope::ensure_in_scope;
+
+/// Accepted shape of a setting value; anything else is dropped
+enum Kind {
+    Bool,
+    Number,
+    Text,
+    TextList,
+    OneOf(&'static [&'static str]),
+}
+
+/// `app.json` keys Igne understands (VaultSettings in the frontend uses Obsidian's names)
+const APP_KEYS: &[(&str, Kind)] = &[
+    ("defaultViewMode", Kind::OneOf(&["source", "preview"])),
+    ("livePreview", Kind::Bool),
+    ("strictLineBreaks", Kind::Bool),
+    ("showLineNumber", Kind::Bool),
+    ("showFrontmatter", Kind::Bool),
+    ("foldHeading", Kind::Bool),
+    ("foldIndent", Kind::Bool),
+    ("readableLineLength", Kind::Bool),
+    ("vimMode", Kind::Bool),
+    ("tabSize", Kind::Number),
+    ("useTab", Kind::Bool),
+    ("spellcheck", Kind::Bool),
+    ("spellcheckLanguages", Kind::TextList),
+    ("newFileLocation", Kind::OneOf(&["root", "current", "folder"])),
+    ("newFileFolderPath", Kind::Text),
+    ("attachmentFolderPath", Kind::Text),
+    ("newLinkFormat", Kind::OneOf(&["shortest", "relative", "absolute"])),
+    ("useMarkdownLinks", Kind::Bool),
+    ("alwaysUpdateLinks", Kind::Bool),
+    ("trashOption", Kind::OneOf(&["system", "local", "none"])),
+];
+
+/// `appearance.json` keys Igne understands. Obsidian's `theme` is mapped separately.
+const APPEARANCE_KEYS: &[(&str, Kind)] = &[
+    ("baseFontSize", Kind::Number),
+    ("cssTheme", Kind::Text),
+    ("accentColor", Kind::Text),
+    ("interfaceFontFamily", Kind::Text),
+    ("textFontFamily", Kind::Text),
+    ("monospaceFontFamily", Kind::Text),
+    ("enabledCssSnippets", Kind::TextList),
+    ("showViewHeader", Kind::Bool),
+    ("nativeMenus", Kind::Bool),
+    ("translucency", Kind::Bool),
+];
+
+#[derive(Serialize, Clone, Default)]
+pub struct ImportedObsidianSettings {
+    /// Keys for the vault settings (`app.json`)
+    pub settings: Map<String, Value>,
+    /// Keys for the appearance settings (`appearance.json`)
+    pub appearance: Map<String, Value>,
+    /// Command id → hotkey list, as in Obsidian's `hotkeys.json`
+    pub hotkeys: Map<String, Value>,
+    /// Installed community themes under `.obsidian/themes`
+    pub themes: Vec<String>,
+}
+
+fn matches_kind(value: &Value, kind: &Kind) -> bool {
+    match kind {
+        Kind::Bool => value.is_boolean(),
+        Kind::Number => value.is_number(),
+        Kind::Text => value.is_string(),
+        Kind::TextList => value
+            .as_array()
+            .is_some_and(|items| items.iter().all(Value::is_string)),
+        Kind::OneOf(allowed) => value.as_str().is_some_and(|v| allowed.contains(&v)),
+    }
+}
+
+/// Read a config file as a JSON object; missing or malformed files count as empty
+fn read_object(path: &Path) -> Map<String, Value> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|value| match value {
+            Value::Object(map) => Some(map),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn pick_known(source: &Map<String, Value>, keys: &[(&str, Kind)]) -> Map<String, Value> {
+    keys.iter()
+        .filter_map(|(key, kind)| {
+            source
+                .get(*key)
+                .filter(|value| matches_kind(value, kind))
+                .map(|value| (key.to_string(), value.clone()))
+        })
+        .collect()
+}
+
+/// Hotkeys are `{ "command-id": [{ "modifiers": [...], "key": "..." }] }`; entries with
+/// any other shape are skipped
+fn valid_hotkeys(source: Map<String, Value>) -> Map<String, Value> {
+    source
+        .into_iter()
+        .filter(|(_, bindings)| {
+            bindings.as_array().is_some_and(|list| {
+                list.iter().all(|binding| {
+                    binding.get("key").is_some_and(Value::is_string)
+                        && binding
+                            .get("modifiers")
+                            .and_then(Value::as_array)
+                            .is_some_and(|mods| mods.iter().all(Value::is_string))
+                })
+            })
+        })
+        .collect()
+}
+
+fn installed_themes(config_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(config_dir.join("themes")) else {
+        return vec![];
+    };
+    let mut themes: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().join("theme.css").is_file())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    themes.sort();
+    themes
+}
+
+/// Read an Obsidian vault's `.obsidian` config and map it onto Igne's settings shape.
+/// Keys Igne doesn't know, and values of the wrong type, are left out.
+#[tauri::command]
+pub fn import_obsidian_settings(
+    vault_path: String,
+    app: AppHandle,
+) -> Result<ImportedObsidianSettings, IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &vault)?;
+
+    let config_dir = vault.join(".obsidian");
+    if !config_dir.is_dir() {
+        return Err(IgneError::not_found(
+            format!("No .obsidian folder in {}", vault_path),
+            &config_dir,
+        ));
+    }
+
+    let app_json = read_object(&config_dir.join("app.json"));
+    let appearance_json = read_object(&config_dir.join("appearance.json"));
+
+    let mut appearance = pick_known(&appearance_json, APPEARANCE_KEYS);
+    // Obsidian calls its dark and light base themes "obsidian" and "moonstone"
+    let base_theme = match appearance_json.get("theme").and_then(Value::as_str) {
+        Some("obsidian") => Some("dark"),
+        Some("moonstone") => Some("light"),
+        _ => None,
+    };
+    if let Some(theme) = base_theme {
+        appearance.insert("baseTheme".into(), Value::from(theme));
+    }
+
+    Ok(ImportedObsidianSettings {
+        settings: pick_known(&app_json, APP_KEYS),
+        appearance,
+        hotkeys: valid_hotkeys(read_object(&config_dir.join("hotkeys.json"))),
+        themes: installed_themes(&config_dir),
+    })
+}
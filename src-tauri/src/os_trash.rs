@@ -0,0 +1,110 @@
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::error::IgneError;
+use crate::scope::{ensure_in_scope, resolve};
+
+#[cfg(any(windows, all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))))]
+mod platform {
+    use trash::{os_limited, TrashItem, TrashItemSize};
+
+    use crate::error::IgneError;
+
+    pub fn list() -> Result<Vec<TrashItem>, IgneError> {
+        Ok(os_limited::list()?)
+    }
+
+    /// Whether the item is a folder, and its size when it's a file
+    pub fn details(item: &TrashItem) -> (bool, u64) {
+        match os_limited::metadata(item).map(|metadata| metadata.size) {
+            Ok(TrashItemSize::Entries(_)) => (true, 0),
+            Ok(TrashItemSize::Bytes(size)) => (false, size),
+            Err(_) => (false, 0),
+        }
+    }
+
+    pub fn restore(item: TrashItem) -> Result<(), IgneError> {
+        Ok(os_limited::restore_all([item])?)
+    }
+}
+
+/// The `trash` crate can only move things into the macOS trash, not look inside it
+#[cfg(not(any(windows, all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android")))))]
+mod platform {
+    use trash::TrashItem;
+
+    use crate::error::IgneError;
+
+    fn unsupported() -> IgneError {
+        IgneError::unsupported("Browsing the system trash isn't supported on this platform")
+    }
+
+    pub fn list() -> Result<Vec<TrashItem>, IgneError> {
+        Err(unsupported())
+    }
+
+    pub fn details(_item: &TrashItem) -> (bool, u64) {
+        (false, 0)
+    }
+
+    pub fn restore(_item: TrashItem) -> Result<(), IgneError> {
+        Err(unsupported())
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct OsTrashEntry {
+    /// The system's id for the item, to pass to `restore_trashed`
+    pub id: String,
+    pub name: String,
+    pub original_path: String,
+    pub is_dir: bool,
+    /// 0 for folders
+    pub size: u64,
+    /// Seconds since the epoch
+    pub deleted_at: u64,
+}
+
+/// Items in the system trash that were deleted from inside the vault, newest first. Fails
+/// with `UNSUPPORTED` on macOS, where the trash can't be listed.
+#[tauri::command]
+pub fn list_recently_trashed(vault_root: String, app: AppHandle) -> Result<Vec<OsTrashEntry>, IgneError> {
+    let vault = PathBuf::from(&vault_root);
+    ensure_in_scope(&app, &vault)?;
+    // Trashed paths are canonical on Linux but not on Windows, so both forms are tried
+    let resolved = resolve(&vault);
+
+    let mut entries: Vec<OsTrashEntry> = platform::list()?
+        .into_iter()
+        .filter(|item| item.original_parent.starts_with(&vault) || item.original_parent.starts_with(&resolved))
+        .map(|item| {
+            let (is_dir, size) = platform::details(&item);
+            OsTrashEntry {
+                id: item.id.to_string_lossy().to_string(),
+                name: item.name.to_string_lossy().to_string(),
+                original_path: item.original_path().to_string_lossy().to_string(),
+                is_dir,
+                size,
+                deleted_at: item.time_deleted.max(0) as u64,
+            }
+        })
+        .collect();
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+    Ok(entries)
+}
+
+/// Put an item from `list_recently_trashed` back where it was deleted from, returning
+/// that path. Fails with `ALREADY_EXISTS` if something has taken its place since.
+#[tauri::command]
+pub fn restore_trashed(id: String, app: AppHandle) -> Result<String, IgneError> {
+    let item = platform::list()?
+        .into_iter()
+        .find(|item| item.id.to_string_lossy() == id)
+        .ok_or_else(|| IgneError::not_found(format!("Not in the trash: {}", id), &id))?;
+    let original = item.original_path();
+    ensure_in_scope(&app, &original)?;
+
+    platform::restore(item)?;
+    Ok(original.to_string_lossy().to_string())
+}
@@ -0,0 +1,500 @@
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tauri::AppHandle;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::error::IgneError;
+use crate::links::lexical_normalize;
+use crate::scope::{ensure_in_scope, resolve};
+
+/// Device names Windows reserves regardless of extension (`con.md` is still `CON`)
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Characters Windows doesn't allow in file names. Rejected on every platform so a vault
+/// created on Linux or macOS still syncs to Windows.
+const ILLEGAL_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Windows `MAX_PATH`, counting the terminating NUL
+#[cfg(windows)]
+const MAX_PATH: usize = 260;
+
+fn is_reserved_name(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem))
+}
+
+/// A usable version of `name`: illegal characters become `-`, trailing dots and spaces are
+/// dropped and reserved device names get a `_` suffix
+pub fn sanitize_file_name(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| if ILLEGAL_CHARS.contains(&c) || c.is_control() { '-' } else { c })
+        .collect();
+    let mut fixed = replaced.trim_end_matches(['.', ' ']).to_string();
+
+    if is_reserved_name(&fixed) {
+        let insert_at = fixed.find('.').unwrap_or(fixed.len());
+        fixed.insert(insert_at, '_');
+    }
+    if fixed.is_empty() {
+        fixed = "Untitled".to_string();
+    }
+    fixed
+}
+
+/// Reject file names Windows can't handle, suggesting a fixed name. Trailing dots and
+/// spaces aren't checked here; `validate_new_path` strips them.
+pub fn validate_file_name(name: &str) -> Result<(), IgneError> {
+    let problem = if name.is_empty() {
+        Some("is empty")
+    } else if name.chars().any(|c| ILLEGAL_CHARS.contains(&c) || c.is_control()) {
+        Some("contains characters that aren't allowed (< > : \" / \\ | ? *)")
+    } else if is_reserved_name(name) {
+        Some("is reserved by Windows")
+    } else {
+        None
+    };
+
+    match problem {
+        Some(problem) => Err(IgneError::invalid_path(
+            format!(
+                "The name \"{}\" {}. Try \"{}\" instead.",
+                name,
+                problem,
+                sanitize_file_name(name)
+            ),
+            name,
+        )),
+        None => Ok(()),
+    }
+}
+
+/// `path` without the trailing dots and spaces of its last component, which Windows drops
+/// on its own and Explorer can't open. A name that is nothing but dots and spaces is kept.
+pub fn trim_file_name(path: &Path) -> PathBuf {
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy()) else {
+        return path.to_path_buf();
+    };
+    let trimmed = name.trim_end_matches(['.', ' ']);
+    if trimmed.len() == name.len() || trimmed.is_empty() {
+        return path.to_path_buf();
+    }
+    path.with_file_name(trimmed)
+}
+
+/// The path to create for a file or folder that is about to be created or renamed to:
+/// trailing dots and spaces are stripped from its name, which must then be valid
+pub fn validate_new_path(path: &Path) -> Result<PathBuf, IgneError> {
+    let path = trim_file_name(path);
+    match path.file_name() {
+        Some(name) => validate_file_name(&name.to_string_lossy()).map(|_| path),
+        None => Err(IgneError::invalid_path(
+            format!("Not a file path: {}", path.display()),
+            &path,
+        )),
+    }
+}
+
+/// NFC form of a name, for comparisons only; paths passed to the filesystem keep the
+/// on-disk bytes (macOS stores NFD)
+pub fn nfc(name: &str) -> String {
+    name.nfc().collect()
+}
+
+/// The on-disk path for `path` when its file name only differs by Unicode normalization,
+/// e.g. an NFC link to a note synced from macOS as NFD, or by the trailing dots and spaces
+/// `validate_new_path` strips
+pub fn find_on_disk(path: &Path) -> Option<PathBuf> {
+    if long_path(path).exists() {
+        return Some(path.to_path_buf());
+    }
+
+    let wanted = nfc(&path.file_name()?.to_string_lossy());
+    let trimmed = wanted.trim_end_matches(['.', ' ']);
+    let parent = path.parent()?;
+    let names: Vec<_> = fs::read_dir(long_path(parent)).ok()?.flatten().map(|entry| entry.file_name()).collect();
+    let find = |wanted: &str| names.iter().find(|name| nfc(&name.to_string_lossy()) == wanted);
+    find(&wanted)
+        .or_else(|| find(trimmed).filter(|_| !trimmed.is_empty()))
+        .map(|name| parent.join(name))
+}
+
+/// A valid UTF-8 file name close to `name`. Bytes that aren't UTF-8 are read as Latin-1,
+/// which is what older tools usually wrote, and the result is sanitized.
+#[cfg(unix)]
+pub fn utf8_approximation(name: &OsStr) -> String {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut decoded = String::with_capacity(name.len());
+    for chunk in name.as_bytes().utf8_chunks() {
+        decoded.push_str(chunk.valid());
+        decoded.extend(chunk.invalid().iter().map(|&byte| char::from(byte)));
+    }
+    sanitize_file_name(&decoded)
+}
+
+/// A valid file name close to `name`; unpaired surrogates become `_`
+#[cfg(not(unix))]
+pub fn utf8_approximation(name: &OsStr) -> String {
+    sanitize_file_name(&name.to_string_lossy().replace('\u{FFFD}', "_"))
+}
+
+/// The on-disk path behind a path the frontend got from `to_string_lossy`, for a file
+/// whose name isn't valid Unicode. `None` when no such file is there; an error when
+/// several names read the same once decoded.
+pub fn find_lossy_entry(path: &Path) -> Result<Option<PathBuf>, IgneError> {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Ok(None);
+    };
+    let wanted = name.to_string_lossy();
+    let matches: Vec<PathBuf> = fs::read_dir(long_path(parent))
+        .map_err(|e| IgneError::io(e, parent))?
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            name.to_str().is_none() && name.to_string_lossy() == wanted
+        })
+        .map(|entry| entry.path())
+        .collect();
+
+    match matches.len() {
+        0 => Ok(None),
+        1 => Ok(matches.into_iter().next()),
+        _ => Err(IgneError::conflict(
+            format!("Several files are named like {}; rename them outside Igne", wanted),
+            path,
+        )),
+    }
+}
+
+/// Add the `\\?\` extended-length prefix to absolute paths past `MAX_PATH` so deeply nested
+/// notes can still be opened. A no-op on other platforms.
+#[cfg(windows)]
+pub fn long_path(path: &Path) -> PathBuf {
+    let raw = path.as_os_str().to_string_lossy();
+    if raw.len() < MAX_PATH || raw.starts_with(r"\\?\") || !path.is_absolute() {
+        return path.to_path_buf();
+    }
+
+    // Extended-length paths are passed to the filesystem verbatim, so they must use
+    // backslashes and contain no `.`/`..` components
+    let normalized = crate::links::lexical_normalize(path);
+    let normalized = normalized.to_string_lossy().replace('/', "\\");
+    match normalized.strip_prefix(r"\\") {
+        Some(unc) => PathBuf::from(format!(r"\\?\UNC\{}", unc)),
+        None => PathBuf::from(format!(r"\\?\{}", normalized)),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn long_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+/// A path inside a vault in both forms, for payloads the frontend matches against either
+/// absolute paths or link-style vault-relative ones
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VaultPath {
+    pub path: String,
+    pub relative_path: String,
+}
+
+impl VaultPath {
+    pub fn new(vault: &Path, path: &Path) -> Result<Self, IgneError> {
+        Ok(Self {
+            relative_path: vault_relative(vault, path)?,
+            path: path.to_string_lossy().to_string(),
+        })
+    }
+}
+
+/// `path` relative to `vault` with `/` separators, "" for the vault itself. Both are
+/// canonicalized first so symlinked folders and `..` segments compare equal.
+pub fn vault_relative(vault: &Path, path: &Path) -> Result<String, IgneError> {
+    let root = resolve(&lexical_normalize(vault));
+    let full = resolve(&lexical_normalize(path));
+    slash_relative(&root, &full)
+        .ok_or_else(|| IgneError::invalid_path(format!("Path is not in the vault: {}", path.display()), path))
+}
+
+/// `path` relative to `root` with `/` separators, compared as given without touching the
+/// filesystem. `None` when `path` isn't under `root`.
+pub fn slash_relative(root: &Path, path: &Path) -> Option<String> {
+    let parts: Vec<String> = path
+        .strip_prefix(root)
+        .ok()?
+        .components()
+        .map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    Some(parts.join("/"))
+}
+
+/// `relative` with `.` and `..` applied and `/` separators, `/` and `\` both separating in
+/// the input. `None` when `..` climbs above the start or a part is a drive or root.
+pub fn normalize_relative(relative: &str) -> Option<String> {
+    let mut parts: Vec<&str> = vec![];
+    for part in relative.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            // A drive or root would replace whatever this is joined onto
+            part if !matches!(Path::new(part).components().next(), Some(Component::Normal(_))) => return None,
+            part => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+/// `relative` joined onto `root` with `.` and `..` applied. `None` when `..` climbs above
+/// `root` or a part is a drive or root.
+fn join_within(root: &Path, relative: &str) -> Option<PathBuf> {
+    let relative = normalize_relative(relative)?;
+    let mut path = lexical_normalize(root);
+    path.extend(relative.split('/').filter(|part| !part.is_empty()));
+    Some(path)
+}
+
+/// Whether `joined` still resolves inside `root` once symlinks are followed
+fn stays_within(root: &Path, joined: &Path) -> bool {
+    resolve(joined).starts_with(resolve(root))
+}
+
+/// Absolute path of a vault-relative path, under the vault path as given (canonical forms
+/// such as Windows' `\\?\` prefix stay out of paths the frontend sees). `/` and `\` both
+/// separate; `..` may step back within the vault but not out of it, nor may a symlinked
+/// folder, and "" is the vault.
+pub fn vault_absolute(vault: &Path, relative: &str) -> Result<PathBuf, IgneError> {
+    join_within(vault, relative)
+        .filter(|path| stays_within(vault, path))
+        .ok_or_else(|| IgneError::invalid_path(format!("Path leaves the vault: {}", relative), relative))
+}
+
+/// `child` joined onto `root`, for building paths from link targets and user input. Fails
+/// when `..`, an absolute `child` or a symlinked folder on the way would lead outside `root`.
+pub fn safe_join_path(root: &Path, child: &str) -> Result<PathBuf, IgneError> {
+    let escapes = || IgneError::invalid_path(format!("{} leads outside {}", child, root.display()), child);
+    // Rooted (`/x`, `\\server\share`) and drive-relative (`C:x`) children would otherwise
+    // be joined as if relative
+    if child.starts_with(['/', '\\']) || child.as_bytes().get(1) == Some(&b':') {
+        return Err(escapes());
+    }
+    join_within(root, child)
+        .filter(|path| stays_within(root, path))
+        .ok_or_else(escapes)
+}
+
+/// Vault-relative form of an absolute path, `/`-separated. Fails with `INVALID_PATH` for
+/// paths outside the vault.
+#[tauri::command]
+pub fn to_vault_relative(vault_path: String, path: String, app: AppHandle) -> Result<String, IgneError> {
+    ensure_in_scope(&app, Path::new(&vault_path))?;
+    vault_relative(Path::new(&vault_path), Path::new(&path))
+}
+
+/// `child` joined onto `root` and normalized, failing with `INVALID_PATH` when it would
+/// escape `root`
+#[tauri::command]
+pub fn safe_join(root: String, child: String, app: AppHandle) -> Result<String, IgneError> {
+    ensure_in_scope(&app, Path::new(&root))?;
+    Ok(safe_join_path(Path::new(&root), &child)?.to_string_lossy().to_string())
+}
+
+/// Absolute path of a vault-relative one with the OS's separators. Fails with `INVALID_PATH`
+/// when `..` would climb out of the vault.
+#[tauri::command]
+pub fn to_absolute(vault_path: String, relative: String, app: AppHandle) -> Result<String, IgneError> {
+    ensure_in_scope(&app, Path::new(&vault_path))?;
+    Ok(vault_absolute(Path::new(&vault_path), &relative)?.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("igne-paths-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn rejects_each_reserved_name() {
+        for name in RESERVED_NAMES {
+            for candidate in [name.to_string(), name.to_lowercase(), format!("{}.md", name)] {
+                let err = validate_file_name(&candidate).unwrap_err();
+                assert_eq!(err.code(), "INVALID_PATH", "{}", candidate);
+                assert!(err.message().contains("reserved"), "{}", candidate);
+                assert!(validate_file_name(&sanitize_file_name(&candidate)).is_ok(), "{}", candidate);
+            }
+        }
+        assert!(validate_file_name("console.md").is_ok());
+    }
+
+    #[test]
+    fn rejects_illegal_characters() {
+        let err = validate_file_name("a:b?.md").unwrap_err();
+        assert!(err.message().contains("Try \"a-b-.md\" instead"));
+    }
+
+    #[test]
+    fn strips_trailing_dots_and_spaces() {
+        let dir = Path::new("vault");
+        assert_eq!(validate_new_path(&dir.join("Note. . ")).unwrap(), dir.join("Note"));
+        assert_eq!(validate_new_path(&dir.join("Note.md")).unwrap(), dir.join("Note.md"));
+        assert_eq!(validate_new_path(&dir.join("con. ")).unwrap_err().code(), "INVALID_PATH");
+    }
+
+    #[test]
+    fn finds_a_note_saved_without_its_trailing_dot() {
+        let dir = temp_dir("trailing");
+        fs::write(dir.join("Note"), "").unwrap();
+        assert_eq!(find_on_disk(&dir.join("Note.")), Some(dir.join("Note")));
+        assert_eq!(find_on_disk(&dir.join("Other.")), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reads_and_writes_a_300_character_nested_path() {
+        let root = temp_dir("long");
+        let mut dir = root.clone();
+        while dir.as_os_str().len() < 300 {
+            dir.push("a folder nested deep inside the vault");
+        }
+        let note = dir.join("note.md");
+        assert!(note.as_os_str().len() > 300);
+
+        fs::create_dir_all(long_path(&dir)).unwrap();
+        crate::atomic_write(&note, b"deep").unwrap();
+        assert_eq!(find_on_disk(&note), Some(note.clone()));
+        assert_eq!(fs::read(long_path(&note)).unwrap(), b"deep");
+        fs::remove_dir_all(long_path(&root)).unwrap();
+    }
+
+    #[test]
+    fn normalizes_vault_relative_paths() {
+        let cases = [
+            ("", Some("")),
+            ("a/./b/../c.md", Some("a/c.md")),
+            ("a\\b.md", Some("a/b.md")),
+            ("/a//b.md", Some("a/b.md")),
+            ("a/..", Some("")),
+            ("../c.md", None),
+            ("a/../../c.md", None),
+        ];
+        for (relative, expected) in cases {
+            assert_eq!(normalize_relative(relative).as_deref(), expected, "{}", relative);
+        }
+    }
+
+    #[test]
+    fn converts_between_absolute_and_vault_relative_paths() {
+        let vault = temp_dir("relative");
+        fs::create_dir_all(vault.join("sub")).unwrap();
+        fs::write(vault.join("sub/note.md"), "").unwrap();
+
+        let cases = [
+            (vault.clone(), ""),
+            (vault.join("sub/note.md"), "sub/note.md"),
+            (vault.join("sub/../sub/./note.md"), "sub/note.md"),
+            (vault.join("new/folder/note.md"), "new/folder/note.md"),
+        ];
+        for (path, relative) in cases {
+            assert_eq!(vault_relative(&vault, &path).unwrap(), relative, "{}", path.display());
+            assert_eq!(vault_absolute(&vault, relative).unwrap(), lexical_normalize(&path));
+        }
+        assert_eq!(vault_absolute(&vault, "sub\\note.md").unwrap(), vault.join("sub").join("note.md"));
+
+        let outside = vault.join("../elsewhere.md");
+        assert_eq!(vault_relative(&vault, &outside).unwrap_err().code(), "INVALID_PATH");
+        assert_eq!(vault_absolute(&vault, "../elsewhere.md").unwrap_err().code(), "INVALID_PATH");
+        assert_eq!(vault_absolute(&vault, "sub/../../x.md").unwrap_err().code(), "INVALID_PATH");
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn vault_absolute_refuses_symlinks_out_of_the_vault() {
+        let vault = temp_dir("absolute-link");
+        let outside = temp_dir("absolute-outside");
+        std::os::unix::fs::symlink(&outside, vault.join("link")).unwrap();
+
+        assert_eq!(vault_absolute(&vault, "link/note.md").unwrap_err().code(), "INVALID_PATH");
+        fs::remove_dir_all(&vault).unwrap();
+        fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[test]
+    fn safe_join_stays_inside_the_root() {
+        let root = temp_dir("safe-join");
+        let cases = [
+            ("note.md", Some("note.md")),
+            ("a/b/note.md", Some("a/b/note.md")),
+            ("a\\b\\note.md", Some("a/b/note.md")),
+            ("a/../note.md", Some("note.md")),
+            ("./a/./note.md", Some("a/note.md")),
+            ("", Some("")),
+            ("..", None),
+            ("../note.md", None),
+            ("a/../../note.md", None),
+            ("a\\..\\..\\note.md", None),
+            ("/etc/passwd", None),
+            ("\\etc\\passwd", None),
+            ("C:\\Windows\\win.ini", None),
+            ("C:Windows", None),
+            ("c:/note.md", None),
+            ("\\\\server\\share\\note.md", None),
+            ("//server/share/note.md", None),
+        ];
+        for (child, expected) in cases {
+            let joined = safe_join_path(&root, child);
+            match expected {
+                Some(relative) => {
+                    let parts = relative.split('/').filter(|part| !part.is_empty());
+                    let expected = parts.fold(root.clone(), |path, part| path.join(part));
+                    assert_eq!(joined.unwrap(), expected, "{}", child);
+                }
+                None => assert_eq!(joined.unwrap_err().code(), "INVALID_PATH", "{}", child),
+            }
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn safe_join_refuses_symlinks_out_of_the_root() {
+        let root = temp_dir("safe-join-link");
+        let outside = temp_dir("safe-join-outside");
+        fs::create_dir_all(root.join("inner")).unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+        std::os::unix::fs::symlink(root.join("inner"), root.join("inner-link")).unwrap();
+
+        assert_eq!(safe_join_path(&root, "link/note.md").unwrap_err().code(), "INVALID_PATH");
+        assert_eq!(safe_join_path(&root, "link").unwrap_err().code(), "INVALID_PATH");
+        assert_eq!(safe_join_path(&root, "inner-link/note.md").unwrap(), root.join("inner-link/note.md"));
+        fs::remove_dir_all(&root).unwrap();
+        fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn prefixes_paths_past_max_path() {
+        let deep = format!(r"C:\Vault\{}note.md", "folder\\".repeat(40));
+        let long = long_path(Path::new(&deep));
+        assert!(long.to_string_lossy().starts_with(r"\\?\C:\Vault\folder\"));
+
+        let unc = format!(r"\\server\share\{}note.md", "folder\\".repeat(40));
+        assert!(long_path(Path::new(&unc)).to_string_lossy().starts_with(r"\\?\UNC\server\share\"));
+
+        assert_eq!(long_path(Path::new(r"C:\Vault\note.md")), Path::new(r"C:\Vault\note.md"));
+    }
+}
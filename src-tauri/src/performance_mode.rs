@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::app_config::{load_settings, settings_path, update_settings};
+use crate::directory_size::DirectorySizeCache;
+use crate::error::IgneError;
+use crate::note_links::NoteLinksCache;
+use crate::note_titles::NoteTitleCache;
+use crate::scope::{ensure_in_scope, resolve};
+use crate::search_index;
+use crate::startup::warm_note_links;
+use crate::{restart_watches_under, WatchMode, WatchOptions};
+
+/// Key in `<app_data_dir>/settings.json` mapping vault paths to their mode
+const SETTINGS_KEY: &str = "performanceModes";
+
+/// Poll interval of folder watches in reduced mode
+const REDUCED_POLL_INTERVAL_MS: u64 = 30_000;
+
+/// How long the startup index waits in reduced mode, so it runs once opening the vault
+/// has settled rather than alongside it
+const REDUCED_INDEX_DELAY: Duration = Duration::from_secs(120);
+
+/// How hard the backend works a vault in the background, for vaults on slow or metered
+/// network shares
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PerformanceMode {
+    /// Native watching, indexing at startup
+    #[default]
+    Full,
+    /// Polling every 30 seconds on modification times alone, so changes arrive in batches
+    /// without reading every file; indexing at startup waits two minutes
+    Reduced,
+    /// No watching or background indexing; `refresh_vault` rescans on request
+    Manual,
+}
+
+/// Payload of `performance-mode-changed`
+#[derive(Serialize, Clone)]
+struct PerformanceModeChanged {
+    vault_path: String,
+    mode: PerformanceMode,
+}
+
+/// Payload of `vault-refreshed`, and what `refresh_vault` returns
+#[derive(Serialize, Clone)]
+pub struct VaultRefresh {
+    pub vault_path: String,
+    pub search_index_path: String,
+    pub notes: u64,
+}
+
+/// Wakes `wait_for_index` when a vault's mode is saved
+pub struct ModeChanges {
+    saved: Mutex<u64>,
+    changed: Condvar,
+}
+
+impl ModeChanges {
+    pub fn new() -> Self {
+        Self {
+            saved: Mutex::new(0),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn notify(&self) {
+        if let Ok(mut saved) = self.saved.lock() {
+            *saved += 1;
+        }
+        self.changed.notify_all();
+    }
+}
+
+/// The mode set for the innermost vault containing `path`; full when none is set or the
+/// settings can't be read
+pub fn mode_for(app: &AppHandle, path: &Path) -> PerformanceMode {
+    let Ok(settings_path) = settings_path(app) else {
+        return PerformanceMode::Full;
+    };
+    let modes = load_settings(&settings_path)
+        .ok()
+        .and_then(|mut settings| settings.remove(SETTINGS_KEY));
+    let Some(Value::Object(modes)) = modes else {
+        return PerformanceMode::Full;
+    };
+
+    let path = resolve(path);
+    modes
+        .into_iter()
+        .map(|(vault, mode)| (resolve(Path::new(&vault)), mode))
+        .filter(|(vault, _)| path.starts_with(vault))
+        .max_by_key(|(vault, _)| vault.components().count())
+        .and_then(|(_, mode)| serde_json::from_value(mode).ok())
+        .unwrap_or_default()
+}
+
+/// Block the startup index of `vault` until it may run, returning the mode it runs under.
+/// Full and manual return at once; reduced waits out `REDUCED_INDEX_DELAY`, unless the
+/// vault is switched to another mode meanwhile, which takes effect right away.
+pub fn wait_for_index(app: &AppHandle, vault: &Path) -> PerformanceMode {
+    let changes = app.state::<ModeChanges>();
+    let deadline = Instant::now() + REDUCED_INDEX_DELAY;
+    loop {
+        // Read before the mode so a save in between still wakes the wait below
+        let Ok(seen) = changes.saved.lock().map(|saved| *saved) else {
+            return mode_for(app, vault);
+        };
+        let mode = mode_for(app, vault);
+        let now = Instant::now();
+        if mode != PerformanceMode::Reduced || now >= deadline {
+            return mode;
+        }
+        let Ok(saved) = changes.saved.lock() else {
+            return mode;
+        };
+        let _ = changes.changed.wait_timeout_while(saved, deadline - now, |saved| *saved == seen);
+    }
+}
+
+/// How a folder watch the frontend asked for runs under its vault's `mode`
+pub fn watch_options(mode: PerformanceMode, requested: WatchOptions) -> WatchOptions {
+    match mode {
+        PerformanceMode::Full => requested,
+        PerformanceMode::Reduced => WatchOptions {
+            mode: WatchMode::Poll,
+            poll_interval_ms: Some(requested.poll_interval_ms.unwrap_or(0).max(REDUCED_POLL_INTERVAL_MS)),
+        },
+        PerformanceMode::Manual => WatchOptions {
+            mode: WatchMode::Off,
+            poll_interval_ms: None,
+        },
+    }
+}
+
+/// Forget everything cached about the vault's contents
+fn invalidate_caches(app: &AppHandle, vault: &Path) {
+    app.state::<NoteTitleCache>().invalidate(&vault.to_string_lossy());
+    app.state::<NoteLinksCache>().invalidate_under(vault);
+    app.state::<DirectorySizeCache>().invalidate_under(vault);
+}
+
+#[tauri::command]
+pub fn get_performance_mode(vault_path: String, app: AppHandle) -> Result<PerformanceMode, IgneError> {
+    ensure_in_scope(&app, Path::new(&vault_path))?;
+    Ok(mode_for(&app, Path::new(&vault_path)))
+}
+
+/// Save the vault's mode in settings and apply it right away: its folder watches are set
+/// up again as native, polling or off. Emits `performance-mode-changed`.
+#[tauri::command]
+pub async fn set_performance_mode(
+    vault_path: String,
+    mode: PerformanceMode,
+    app: AppHandle,
+) -> Result<(), IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &vault)?;
+    if !vault.is_dir() {
+        return Err(IgneError::not_a_directory(format!("Not a directory: {}", vault_path), &vault_path));
+    }
+
+    update_settings(&app, |settings| {
+        let mut modes = match settings.remove(SETTINGS_KEY) {
+            Some(Value::Object(modes)) => modes,
+            _ => Map::new(),
+        };
+        modes.insert(vault_path.clone(), serde_json::to_value(mode)?);
+        settings.insert(SETTINGS_KEY.into(), Value::Object(modes));
+        Ok(())
+    })
+    .await?;
+
+    app.state::<ModeChanges>().notify();
+    // Changes made while watching was off or slow may not have been seen
+    invalidate_caches(&app, &vault);
+    restart_watches_under(&app, &vault);
+    let _ = app.emit("performance-mode-changed", PerformanceModeChanged { vault_path, mode });
+    Ok(())
+}
+
+/// Rescan the vault once: drop cached titles, links and sizes, rebuild the search index and
+/// re-read every note's links. For manual mode, where nothing happens in the background.
+/// Emits `vault-refreshed` so windows re-list the tree.
+#[tauri::command]
+pub async fn refresh_vault(vault_path: String, app: AppHandle) -> Result<VaultRefresh, IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &vault)?;
+    if !vault.is_dir() {
+        return Err(IgneError::not_a_directory(format!("Not a directory: {}", vault_path), &vault_path));
+    }
+
+    let worker = app.clone();
+    let refresh = tauri::async_runtime::spawn_blocking(move || {
+        invalidate_caches(&worker, &vault);
+        let search_index_path = search_index::write_index(&worker, &vault)?;
+        Ok::<_, IgneError>(VaultRefresh {
+            vault_path,
+            search_index_path: search_index_path.to_string_lossy().to_string(),
+            notes: warm_note_links(&worker, &vault),
+        })
+    })
+    .await
+    .map_err(|e| IgneError::other(format!("Vault refresh failed: {}", e)))??;
+
+    let _ = app.emit("vault-refreshed", refresh.clone());
+    Ok(refresh)
+}
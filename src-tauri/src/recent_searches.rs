@@ -0,0 +1,57 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::atomic_write;
+use crate::error::IgneError;
+use crate::scope::ensure_in_scope;
+use crate::write_lock::with_write_lock;
+
+const RECENT_SEARCHES_FILE: &str = "recent_searches.json";
+const MAX_RECENT_SEARCHES: usize = 50;
+
+fn searches_path(vault: &Path) -> PathBuf {
+    vault.join(".obsidian").join(RECENT_SEARCHES_FILE)
+}
+
+/// Stored queries, most recent first. A missing or unreadable file is an empty history.
+fn load(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Remember a search query for the vault. Repeating a query moves it back to the top;
+/// only the last 50 are kept.
+#[tauri::command]
+pub async fn save_recent_search(vault_path: String, query: String, app: AppHandle) -> Result<(), IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &vault)?;
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Ok(());
+    }
+
+    let path = searches_path(&vault);
+    with_write_lock(&app, &path, || {
+        let mut searches = load(&path);
+        searches.retain(|existing| *existing != query);
+        searches.insert(0, query);
+        searches.truncate(MAX_RECENT_SEARCHES);
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| IgneError::io(e, dir))?;
+        }
+        atomic_write(&path, serde_json::to_string_pretty(&searches)?.as_bytes())
+    })
+    .await
+}
+
+/// The vault's recent search queries, most recent first
+#[tauri::command]
+pub fn get_recent_searches(vault_path: String, app: AppHandle) -> Result<Vec<String>, IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &vault)?;
+    Ok(load(&searches_path(&vault)))
+}
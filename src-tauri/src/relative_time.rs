@@ -0,0 +1,76 @@
+use chrono::{DateTime, Local, TimeZone};
+use serde::Serialize;
+
+#[derive(Serialize, Clone)]
+pub struct RelativeTime {
+    /// English text such as "just now", "3 minutes ago" or "yesterday"
+    pub label: String,
+    /// The same amount as `Intl.RelativeTimeFormat` takes it, negative for the past, so
+    /// other languages get the same rounding: e.g. -3 with "minute", -1 with "day"
+    pub value: i64,
+    /// "second" (only for just now), "minute", "hour", "day", "week", "month" or "year"
+    pub unit: &'static str,
+}
+
+fn label(value: i64, unit: &str) -> String {
+    let count = value.abs();
+    let units = if count == 1 { unit.to_string() } else { format!("{}s", unit) };
+    if value < 0 {
+        format!("{} {} ago", count, units)
+    } else {
+        format!("in {} {}", count, units)
+    }
+}
+
+/// Under a minute is "just now", under a day counts minutes or hours, and beyond that
+/// local calendar days decide, so a note from 22:00 reads "yesterday" at 01:00
+fn describe(then: DateTime<Local>, now: DateTime<Local>) -> RelativeTime {
+    let seconds = (then - now).num_seconds();
+    let days = (then.date_naive() - now.date_naive()).num_days();
+
+    let (value, unit) = match seconds.abs() {
+        0..60 => {
+            return RelativeTime {
+                label: "just now".to_string(),
+                value: 0,
+                unit: "second",
+            }
+        }
+        60..3600 => (seconds / 60, "minute"),
+        3600..86_400 if days == 0 => (seconds / 3600, "hour"),
+        _ => match days.abs() {
+            0..7 => (days, "day"),
+            7..30 => (days / 7, "week"),
+            30..365 => (days / 30, "month"),
+            _ => (days / 365, "year"),
+        },
+    };
+    let label = match (value, unit) {
+        (-1, "day") => "yesterday".to_string(),
+        (1, "day") => "tomorrow".to_string(),
+        _ => label(value, unit),
+    };
+    RelativeTime { label, value, unit }
+}
+
+fn relative_to_now(timestamp: u64, now: DateTime<Local>) -> Option<RelativeTime> {
+    if timestamp == 0 {
+        return None;
+    }
+    let then = Local.timestamp_opt(i64::try_from(timestamp).ok()?, 0).single()?;
+    Some(describe(then, now))
+}
+
+/// How long ago an epoch-seconds time was, e.g. a `FileEntry`'s `modified`, in the local
+/// time zone. `None` for 0, which file entries use when the time is unknown.
+#[tauri::command]
+pub fn relative_time(timestamp: u64) -> Option<RelativeTime> {
+    relative_to_now(timestamp, Local::now())
+}
+
+/// `relative_time` for many times against the same "now", for a whole folder of the tree
+#[tauri::command]
+pub fn relative_times(timestamps: Vec<u64>) -> Vec<Option<RelativeTime>> {
+    let now = Local::now();
+    timestamps.into_iter().map(|timestamp| relative_to_now(timestamp, now)).collect()
+}
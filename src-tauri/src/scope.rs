@@ -0,0 +1,154 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, FilePath};
+
+use crate::error::IgneError;
+
+/// Paths the user explicitly granted outside of registered vaults (open-file/folder dialogs,
+/// files opened from the OS). Filesystem commands may only touch paths under a registered
+/// vault or one of these grants; the app's own config goes through `app_config`.
+pub struct ScopeState {
+    granted: Mutex<Vec<PathBuf>>,
+}
+
+impl ScopeState {
+    pub fn new() -> Self {
+        Self {
+            granted: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn grant(&self, path: &Path) {
+        let resolved = resolve(path);
+        if let Ok(mut granted) = self.granted.lock() {
+            if !granted.contains(&resolved) {
+                granted.push(resolved);
+            }
+        }
+    }
+}
+
+impl Default for ScopeState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Developer escape hatch from `<app_data_dir>/settings.json`, for people embedding Igne
+/// in setups where the vault registry doesn't describe what the app may touch
+#[derive(Deserialize)]
+struct ScopeSettings {
+    #[serde(default, rename = "disablePathScope")]
+    disable_path_scope: bool,
+}
+
+fn scope_disabled(app: &AppHandle) -> bool {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join("settings.json")).ok())
+        .and_then(|content| serde_json::from_str::<ScopeSettings>(&content).ok())
+        .map(|s| s.disable_path_scope)
+        .unwrap_or(false)
+}
+
+/// Canonicalize the longest existing ancestor and re-append the rest, so paths that
+/// don't exist yet (new notes, nested folders) still resolve through symlinks
+pub fn resolve(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+
+    let mut existing = path.to_path_buf();
+    let mut rest = Vec::new();
+    while !existing.exists() {
+        match (existing.file_name(), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                rest.push(name.to_os_string());
+                existing = parent.to_path_buf();
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+
+    let mut resolved = existing.canonicalize().unwrap_or(existing);
+    for name in rest.into_iter().rev() {
+        resolved.push(name);
+    }
+    resolved
+}
+
+/// Verify that `path` falls under a registered vault or a granted path
+pub fn ensure_in_scope(app: &AppHandle, path: &Path) -> Result<(), IgneError> {
+    // `..` that survives resolution (non-existent parents) could walk out of a root
+    if path.components().any(|c| c == Component::ParentDir) && !path.exists() {
+        return Err(IgneError::scope_denied(path));
+    }
+
+    let resolved = resolve(path);
+
+    let mut roots: Vec<PathBuf> = crate::registered_vault_roots(app);
+    if let Ok(granted) = app.state::<ScopeState>().granted.lock() {
+        roots.extend(granted.iter().cloned());
+    }
+
+    if roots.iter().any(|root| resolved.starts_with(resolve(root))) {
+        return Ok(());
+    }
+
+    if scope_disabled(app) {
+        return Ok(());
+    }
+
+    Err(IgneError::scope_denied(path))
+}
+
+#[derive(Deserialize)]
+pub struct PickFilter {
+    pub name: String,
+    pub extensions: Vec<String>,
+}
+
+fn into_granted(app: &AppHandle, picked: Option<FilePath>) -> Result<Option<String>, IgneError> {
+    let Some(picked) = picked else {
+        return Ok(None);
+    };
+    let path = picked
+        .into_path()
+        .map_err(|e| IgneError::other(format!("Picked path is not a file path: {}", e)))?;
+    app.state::<ScopeState>().grant(&path);
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+/// Show the native folder picker and grant access to the chosen folder. `None` when the
+/// dialog was cancelled.
+#[tauri::command]
+pub async fn pick_folder(title: String, app: AppHandle) -> Result<Option<String>, IgneError> {
+    let dialog = app.dialog().file().set_title(title);
+    let picked = tauri::async_runtime::spawn_blocking(move || dialog.blocking_pick_folder())
+        .await
+        .map_err(|e| IgneError::other(format!("Folder picker failed: {}", e)))?;
+    into_granted(&app, picked)
+}
+
+/// Show the native file picker and grant access to the chosen file. `None` when the
+/// dialog was cancelled.
+#[tauri::command]
+pub async fn pick_file(
+    title: String,
+    filters: Vec<PickFilter>,
+    app: AppHandle,
+) -> Result<Option<String>, IgneError> {
+    let mut dialog = app.dialog().file().set_title(title);
+    for filter in &filters {
+        let extensions: Vec<&str> = filter.extensions.iter().map(String::as_str).collect();
+        dialog = dialog.add_filter(&filter.name, &extensions);
+    }
+    let picked = tauri::async_runtime::spawn_blocking(move || dialog.blocking_pick_file())
+        .await
+        .map_err(|e| IgneError::other(format!("File picker failed: {}", e)))?;
+    into_granted(&app, picked)
+}
@@ -0,0 +1,357 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager, State};
+use unicode_normalization::char::is_combining_mark;
+
+use crate::error::IgneError;
+use crate::paths::{nfc, VaultPath};
+use crate::scope::{ensure_in_scope, resolve};
+use crate::snapshots::stable_hash;
+use crate::vault::walk_vault_files;
+use crate::{atomic_write, is_markdown_file, note_kind};
+
+/// Indexes live under `<app_data>/search_index/<vault>.bin`, outside the vault so sync
+/// tools never upload them
+const INDEX_DIR: &str = "search_index";
+/// Bumped whenever the serialized layout changes, so stale files are rejected
+const INDEX_VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct SearchIndex {
+    version: u32,
+    files: Vec<VaultPath>,
+    /// Token → (index into `files`, byte offsets of each occurrence)
+    postings: BTreeMap<String, Vec<(u32, Vec<u32>)>>,
+}
+
+/// Modification time and size of an index file when it was loaded
+type FileStamp = (SystemTime, u64);
+
+/// Deserialized indexes by file, reused until the file's modification time or size changes
+pub struct SearchIndexCache {
+    indexes: Mutex<HashMap<PathBuf, (FileStamp, Arc<SearchIndex>)>>,
+}
+
+impl SearchIndexCache {
+    pub fn new() -> Self {
+        Self {
+            indexes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn load(&self, path: &Path) -> Result<Arc<SearchIndex>, IgneError> {
+        let metadata = fs::metadata(path).map_err(|e| IgneError::io(e, path))?;
+        let stamp = (metadata.modified().map_err(|e| IgneError::io(e, path))?, metadata.len());
+        let cached = self.indexes.lock().ok().and_then(|indexes| indexes.get(path).cloned());
+        if let Some((cached_stamp, index)) = cached {
+            if cached_stamp == stamp {
+                return Ok(index);
+            }
+        }
+
+        let bytes = fs::read(path).map_err(|e| IgneError::io(e, path))?;
+        let index: SearchIndex = bincode::deserialize(&bytes)
+            .ok()
+            .filter(|index: &SearchIndex| index.version == INDEX_VERSION)
+            .ok_or_else(|| IgneError::invalid_input(format!("Not a current search index: {}", path.display())))?;
+        let index = Arc::new(index);
+        if let Ok(mut indexes) = self.indexes.lock() {
+            indexes.insert(path.to_path_buf(), (stamp, index.clone()));
+        }
+        Ok(index)
+    }
+}
+
+impl Default for SearchIndexCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct SearchResult {
+    pub path: String,
+    /// `path` relative to the vault, `/`-separated
+    pub relative_path: String,
+    pub score: f64,
+    /// Byte offsets of every matched term in the file, ascending
+    pub positions: Vec<u32>,
+}
+
+fn is_word_char(c: char) -> bool {
+    // Combining marks keep NFD text (as macOS writes it) in one word
+    c.is_alphanumeric() || is_combining_mark(c)
+}
+
+/// Lowercased NFC words with their byte offsets
+fn tokenize(content: &str) -> Vec<(usize, String)> {
+    let mut tokens = vec![];
+    let mut start = None;
+
+    for (i, c) in content.char_indices().chain(std::iter::once((content.len(), ' '))) {
+        match (start, is_word_char(c)) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                tokens.push((s, nfc(&content[s..i].to_lowercase())));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+fn build(files: Vec<(VaultPath, String)>) -> SearchIndex {
+    let mut postings: BTreeMap<String, Vec<(u32, Vec<u32>)>> = BTreeMap::new();
+    let mut paths = Vec::with_capacity(files.len());
+
+    for (file_index, (path, content)) in files.into_iter().enumerate() {
+        let mut positions: HashMap<String, Vec<u32>> = HashMap::new();
+        for (offset, token) in tokenize(&content) {
+            positions.entry(token).or_default().push(offset as u32);
+        }
+        for (token, offsets) in positions {
+            postings.entry(token).or_default().push((file_index as u32, offsets));
+        }
+        paths.push(path);
+    }
+
+    SearchIndex {
+        version: INDEX_VERSION,
+        files: paths,
+        postings,
+    }
+}
+
+/// Tokenize every note in the vault and write the index to the app data dir. Returns the
+/// index file path for `query_search_index`.
+#[tauri::command]
+pub fn build_search_index(vault_path: String, app: AppHandle) -> Result<String, IgneError> {
+    let root = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &root)?;
+    Ok(write_index(&app, &root)?.to_string_lossy().to_string())
+}
+
+fn index_dir(app: &AppHandle) -> Result<PathBuf, IgneError> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(INDEX_DIR))
+        .map_err(|e| IgneError::other(format!("Failed to get app data dir: {}", e)))
+}
+
+/// Build and save the index of the vault at `root`, returning where it was written
+pub fn write_index(app: &AppHandle, root: &Path) -> Result<PathBuf, IgneError> {
+    let files = walk_vault_files(root)?
+        .into_iter()
+        .filter(|file| is_markdown_file(&file.to_string_lossy()))
+        .filter_map(|file| {
+            let content = fs::read_to_string(&file).ok()?;
+            // A drawing's JSON isn't words anyone searches for
+            let content = note_kind::readable_text(&file.to_string_lossy(), &content).to_string();
+            Some((VaultPath::new(root, &file).ok()?, content))
+        })
+        .collect();
+    let index = build(files);
+
+    let dir = index_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| IgneError::io(e, &dir))?;
+    let index_path = dir.join(format!("{}.bin", stable_hash(&resolve(root).to_string_lossy())));
+
+    let bytes = bincode::serialize(&index)
+        .map_err(|e| IgneError::other(format!("Failed to serialize search index: {}", e)))?;
+    atomic_write(&index_path, &bytes)?;
+
+    Ok(index_path)
+}
+
+/// Notes containing every query word, best match first. The last word also matches as a
+/// prefix so results update while typing. Scores are term frequency weighted by rarity.
+#[tauri::command]
+pub fn query_search_index(
+    index_path: String,
+    query: String,
+    app: AppHandle,
+    cache: State<'_, SearchIndexCache>,
+) -> Result<Vec<SearchResult>, IgneError> {
+    let path = PathBuf::from(&index_path);
+    // Index files live outside the path scope, so only paths `write_index` hands out work
+    if path.parent() != Some(index_dir(&app)?.as_path()) {
+        return Err(IgneError::scope_denied(&path));
+    }
+    let index = cache.load(&path)?;
+    Ok(search(&index, &query))
+}
+
+fn search(index: &SearchIndex, query: &str) -> Vec<SearchResult> {
+    let terms: Vec<String> = tokenize(query).into_iter().map(|(_, token)| token).collect();
+    let Some(last) = terms.len().checked_sub(1) else {
+        return vec![];
+    };
+
+    let file_count = index.files.len().max(1) as f64;
+    let mut matches: Option<HashMap<u32, (f64, Vec<u32>)>> = None;
+
+    for (i, term) in terms.iter().enumerate() {
+        let postings: Vec<&Vec<(u32, Vec<u32>)>> = if i == last {
+            index
+                .postings
+                .range(term.clone()..)
+                .take_while(|(token, _)| token.starts_with(term.as_str()))
+                .map(|(_, postings)| postings)
+                .collect()
+        } else {
+            index.postings.get(term).into_iter().collect()
+        };
+
+        let mut term_matches: HashMap<u32, (f64, Vec<u32>)> = HashMap::new();
+        for token_postings in postings {
+            let idf = (file_count / token_postings.len() as f64).ln() + 1.0;
+            for (file, offsets) in token_postings {
+                let entry = term_matches.entry(*file).or_default();
+                entry.0 += offsets.len() as f64 * idf;
+                entry.1.extend(offsets);
+            }
+        }
+
+        matches = Some(match matches {
+            None => term_matches,
+            Some(previous) => previous
+                .into_iter()
+                .filter_map(|(file, (score, mut offsets))| {
+                    let (term_score, term_offsets) = term_matches.remove(&file)?;
+                    offsets.extend(term_offsets);
+                    Some((file, (score + term_score, offsets)))
+                })
+                .collect(),
+        });
+    }
+
+    let mut results: Vec<SearchResult> = matches
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(file, (score, mut positions))| {
+            positions.sort_unstable();
+            positions.dedup();
+            let file = index.files.get(file as usize)?;
+            Some(SearchResult {
+                path: file.path.clone(),
+                relative_path: file.relative_path.clone(),
+                score,
+                positions,
+            })
+        })
+        .collect();
+    results.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(files: &[(&str, &str)]) -> SearchIndex {
+        build(
+            files
+                .iter()
+                .map(|(name, content)| {
+                    let path = VaultPath {
+                        path: format!("/vault/{}", name),
+                        relative_path: name.to_string(),
+                    };
+                    (path, content.to_string())
+                })
+                .collect(),
+        )
+    }
+
+    fn found(index: &SearchIndex, query: &str) -> Vec<String> {
+        search(index, query).into_iter().map(|result| result.relative_path).collect()
+    }
+
+    #[test]
+    fn tokenizes_into_lowercase_nfc_words() {
+        let tokens = tokenize("Hello, wörld! cafe\u{301}-Bar 42");
+        let expected = [(0, "hello"), (7, "wörld"), (15, "café"), (22, "bar"), (26, "42")];
+        assert_eq!(tokens.len(), expected.len());
+        for ((offset, token), (expected_offset, expected_token)) in tokens.iter().zip(expected) {
+            assert_eq!((*offset, token.as_str()), (expected_offset, expected_token));
+        }
+    }
+
+    #[test]
+    fn matches_nfd_and_nfc_spellings() {
+        let index = index(&[("nfd.md", "cafe\u{301} au lait"), ("nfc.md", "un café noir")]);
+        assert_eq!(found(&index, "café"), ["nfc.md", "nfd.md"]);
+        assert_eq!(found(&index, "cafe\u{301}"), ["nfc.md", "nfd.md"]);
+        assert_eq!(found(&index, "CAFÉ"), ["nfc.md", "nfd.md"]);
+    }
+
+    #[test]
+    fn matches_every_term_and_the_last_as_a_prefix() {
+        let index = index(&[
+            ("a.md", "project alpha beta"),
+            ("b.md", "alpha only"),
+            ("c.md", "beta programs"),
+            ("d.md", "pro alpha"),
+        ]);
+        let cases = [
+            ("alpha", &["a.md", "b.md", "d.md"][..]),
+            ("alpha beta", &["a.md"]),
+            ("pro", &["a.md", "c.md", "d.md"]),
+            ("pro alpha", &["d.md"]),
+            ("alpha pro", &["a.md", "d.md"]),
+            ("gamma", &[]),
+            ("", &[]),
+            ("  ...  ", &[]),
+        ];
+        for (query, expected) in cases {
+            assert_eq!(found(&index, query), expected, "{}", query);
+        }
+    }
+
+    #[test]
+    fn ranks_by_frequency_and_rarity() {
+        let index = index(&[
+            ("once.md", "rust and common words"),
+            ("twice.md", "rust rust and common words"),
+            ("rare.md", "rust common zebra"),
+            ("none.md", "common words"),
+        ]);
+        assert_eq!(found(&index, "rust"), ["twice.md", "once.md", "rare.md"]);
+        assert_eq!(found(&index, "common zebra"), ["rare.md"]);
+
+        let results = search(&index, "rust common");
+        assert_eq!(results[0].relative_path, "twice.md");
+        assert_eq!(results[0].positions, [0, 5, 14]);
+        // A rare term outweighs a common one
+        let common = search(&index, "common")[0].score;
+        let rare = search(&index, "zebra")[0].score;
+        assert!(rare > common);
+    }
+
+    #[test]
+    fn reuses_the_loaded_index_until_the_file_changes() {
+        let dir = std::env::temp_dir().join(format!("igne-search-index-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("index.bin");
+        let write = |files: &[(&str, &str)]| fs::write(&path, bincode::serialize(&index(files)).unwrap()).unwrap();
+
+        let cache = SearchIndexCache::new();
+        write(&[("a.md", "alpha")]);
+        let first = cache.load(&path).unwrap();
+        assert!(Arc::ptr_eq(&first, &cache.load(&path).unwrap()));
+
+        write(&[("a.md", "alpha"), ("b.md", "alpha beta")]);
+        let second = cache.load(&path).unwrap();
+        assert_eq!(found(&second, "alpha"), ["a.md", "b.md"]);
+
+        fs::write(&path, b"not an index").unwrap();
+        assert_eq!(cache.load(&path).err().map(|e| e.code()), Some("INVALID_INPUT"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,363 @@
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::error::IgneError;
+use crate::paths::long_path;
+use crate::scope::{ensure_in_scope, resolve};
+use crate::write_lock::with_write_lock;
+use crate::{atomic_write, registered_vault_roots};
+
+/// Snapshots live under `<app_data>/snapshots/<vault>/<note>/<unix millis>.md`, outside
+/// the vault so sync tools never see them. Later snapshots in the same millisecond are
+/// `<unix millis>-<n>.md`.
+const SNAPSHOT_DIR: &str = "snapshots";
+/// Original path of the note a snapshot folder belongs to
+const NOTE_PATH_FILE: &str = "path";
+
+const DEFAULT_RETENTION_DAYS: u64 = 7;
+const DEFAULT_MAX_PER_FILE: usize = 50;
+
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// Retention settings from `<app_data_dir>/settings.json`
+#[derive(Deserialize)]
+struct SnapshotSettings {
+    #[serde(default = "default_retention_days", rename = "snapshotRetentionDays")]
+    retention_days: u64,
+    #[serde(default = "default_max_per_file", rename = "snapshotMaxPerFile")]
+    max_per_file: usize,
+}
+
+fn default_retention_days() -> u64 {
+    DEFAULT_RETENTION_DAYS
+}
+
+fn default_max_per_file() -> usize {
+    DEFAULT_MAX_PER_FILE
+}
+
+fn snapshot_settings(app: &AppHandle) -> SnapshotSettings {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join("settings.json")).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or(SnapshotSettings {
+            retention_days: DEFAULT_RETENTION_DAYS,
+            max_per_file: DEFAULT_MAX_PER_FILE,
+        })
+}
+
+#[derive(Serialize, Clone)]
+pub struct SnapshotEntry {
+    /// Unix milliseconds
+    pub timestamp: u64,
+    /// Tells apart snapshots taken in the same millisecond; 0 for the first
+    pub seq: u32,
+    pub size: u64,
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct PruneResult {
+    pub removed: u64,
+    pub freed_bytes: u64,
+}
+
+/// FNV-1a, stable across builds (unlike `DefaultHasher`) so folder names don't change
+pub fn stable_hash(s: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in s.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn vault_store(app: &AppHandle, vault_root: &Path) -> Result<PathBuf, IgneError> {
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| IgneError::other(format!("Failed to get app data dir: {}", e)))?;
+    Ok(app_data
+        .join(SNAPSHOT_DIR)
+        .join(stable_hash(&resolve(vault_root).to_string_lossy())))
+}
+
+/// Snapshot folder for a note, grouped under the registered vault that contains it (or
+/// the note's own folder when it isn't in a vault)
+fn note_store(app: &AppHandle, path: &Path) -> Result<PathBuf, IgneError> {
+    let resolved = resolve(path);
+    let vault_root = registered_vault_roots(app)
+        .into_iter()
+        .map(|root| resolve(&root))
+        .filter(|root| resolved.starts_with(root))
+        .max_by_key(|root| root.components().count())
+        .or_else(|| resolved.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+
+    Ok(vault_store(app, &vault_root)?.join(stable_hash(&resolved.to_string_lossy())))
+}
+
+fn snapshot_name(timestamp: u64, seq: u32) -> String {
+    match seq {
+        0 => format!("{}.md", timestamp),
+        seq => format!("{}-{}.md", timestamp, seq),
+    }
+}
+
+/// The timestamp and sequence number of a snapshot file name
+fn parse_snapshot_name(name: &str) -> Option<(u64, u32)> {
+    let stem = name.strip_suffix(".md")?;
+    match stem.split_once('-') {
+        Some((timestamp, seq)) => Some((timestamp.parse().ok()?, seq.parse().ok()?)),
+        None => Some((stem.parse().ok()?, 0)),
+    }
+}
+
+/// Snapshot files in a note folder as (timestamp, seq, path, size), newest first
+fn read_snapshots(store: &Path) -> Vec<(u64, u32, PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(store) else {
+        return vec![];
+    };
+    let mut snapshots: Vec<(u64, u32, PathBuf, u64)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let (timestamp, seq) = parse_snapshot_name(path.file_name()?.to_str()?)?;
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            Some((timestamp, seq, path, size))
+        })
+        .collect();
+    snapshots.sort_by_key(|(timestamp, seq, _, _)| std::cmp::Reverse((*timestamp, *seq)));
+    snapshots
+}
+
+/// Write `content` as a new snapshot file in `store`. Never replaces one: a snapshot from
+/// the same millisecond gets the next free sequence number.
+fn write_snapshot(store: &Path, content: &[u8]) -> std::io::Result<PathBuf> {
+    let timestamp = now_millis();
+    let mut seq = 0;
+    loop {
+        let snapshot = store.join(snapshot_name(timestamp, seq));
+        match fs::File::options().write(true).create_new(true).open(&snapshot) {
+            Ok(mut file) => {
+                file.write_all(content)?;
+                return Ok(snapshot);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && seq < 1000 => seq += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Unix milliseconds before which snapshots are past `retention_days`. Saturates, since
+/// the setting comes straight from settings.json and may be huge.
+fn retention_cutoff(now: u64, retention_days: u64) -> u64 {
+    now.saturating_sub(retention_days.saturating_mul(MILLIS_PER_DAY))
+}
+
+/// Remove snapshots past the per-file count or older than the retention period
+fn prune_note_store(store: &Path, settings: &SnapshotSettings) -> PruneResult {
+    let cutoff = retention_cutoff(now_millis(), settings.retention_days);
+    let mut result = PruneResult::default();
+
+    for (index, (timestamp, _, path, size)) in read_snapshots(store).into_iter().enumerate() {
+        // The newest snapshot is always kept so a note never loses all history
+        let expired = index > 0 && (index >= settings.max_per_file || timestamp < cutoff);
+        if expired && fs::remove_file(&path).is_ok() {
+            result.removed += 1;
+            result.freed_bytes += size;
+        }
+    }
+
+    if read_snapshots(store).is_empty() {
+        let _ = fs::remove_dir_all(store);
+    }
+    result
+}
+
+/// Record `content` as the latest snapshot of `path`, skipping it when identical to the
+/// previous one
+pub fn record_snapshot(app: &AppHandle, path: &Path, content: &[u8]) -> Result<(), IgneError> {
+    let store = note_store(app, path)?;
+    fs::create_dir_all(&store).map_err(|e| IgneError::io(e, &store))?;
+
+    if let Some((_, _, latest, _)) = read_snapshots(&store).first() {
+        if fs::read(latest).is_ok_and(|previous| previous == content) {
+            return Ok(());
+        }
+    }
+
+    write_snapshot(&store, content).map_err(|e| IgneError::io(e, &store))?;
+    let _ = fs::write(store.join(NOTE_PATH_FILE), path.to_string_lossy().as_bytes());
+
+    prune_note_store(&store, &snapshot_settings(app));
+    Ok(())
+}
+
+/// Snapshot after a successful save; failures are logged rather than failing the save
+pub fn record_snapshot_logged(app: &AppHandle, path: &Path, content: &[u8]) {
+    if let Err(e) = record_snapshot(app, path, content) {
+        warn!("Failed to snapshot {}: {}", path.display(), e);
+    }
+}
+
+fn find_snapshot(app: &AppHandle, path: &Path, timestamp: u64, seq: u32) -> Result<PathBuf, IgneError> {
+    let snapshot = note_store(app, path)?.join(snapshot_name(timestamp, seq));
+    if !snapshot.is_file() {
+        return Err(IgneError::not_found(
+            format!("No snapshot of {} at {}", path.display(), timestamp),
+            path,
+        ));
+    }
+    Ok(snapshot)
+}
+
+/// Saved snapshots of a note, newest first
+#[tauri::command]
+pub fn list_snapshots(path: String, app: AppHandle) -> Result<Vec<SnapshotEntry>, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+
+    Ok(read_snapshots(&note_store(&app, &path_obj)?)
+        .into_iter()
+        .map(|(timestamp, seq, _, size)| SnapshotEntry { timestamp, seq, size })
+        .collect())
+}
+
+/// Content of one snapshot, picked by the `timestamp` and `seq` (default 0) that
+/// `list_snapshots` reported
+#[tauri::command]
+pub fn get_snapshot(path: String, timestamp: u64, seq: Option<u32>, app: AppHandle) -> Result<String, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+
+    let snapshot = find_snapshot(&app, &path_obj, timestamp, seq.unwrap_or(0))?;
+    fs::read_to_string(&snapshot).map_err(|e| IgneError::io(e, &snapshot))
+}
+
+/// Write a snapshot back over the note. The restore is itself snapshotted, so it can be
+/// undone from the same list.
+#[tauri::command]
+pub async fn restore_snapshot(
+    path: String,
+    timestamp: u64,
+    seq: Option<u32>,
+    app: AppHandle,
+) -> Result<(), IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+
+    let snapshot = find_snapshot(&app, &path_obj, timestamp, seq.unwrap_or(0))?;
+    let content = fs::read(&snapshot).map_err(|e| IgneError::io(e, &snapshot))?;
+
+    with_write_lock(&app, &path_obj, || {
+        if let Ok(current) = fs::read(long_path(&path_obj)) {
+            record_snapshot_logged(&app, &path_obj, &current);
+        }
+        atomic_write(&path_obj, &content)?;
+        record_snapshot_logged(&app, &path_obj, &content);
+        Ok(())
+    })
+    .await
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            _ => entry.metadata().map(|m| m.len()).unwrap_or(0),
+        })
+        .sum()
+}
+
+/// Disk space used by a vault's snapshots, in bytes
+#[tauri::command]
+pub fn snapshot_store_size(vault_path: String, app: AppHandle) -> Result<u64, IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &vault)?;
+    Ok(dir_size(&vault_store(&app, &vault)?))
+}
+
+/// Apply the retention settings to every note in a vault. `max_age_days` overrides the
+/// configured retention, e.g. for a "clear older than" button.
+#[tauri::command]
+pub fn prune_snapshots(
+    vault_path: String,
+    max_age_days: Option<u64>,
+    app: AppHandle,
+) -> Result<PruneResult, IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &vault)?;
+
+    let mut settings = snapshot_settings(&app);
+    if let Some(days) = max_age_days {
+        settings.retention_days = days;
+    }
+
+    let mut total = PruneResult::default();
+    let Ok(notes) = fs::read_dir(vault_store(&app, &vault)?) else {
+        return Ok(total);
+    };
+    for note in notes.flatten() {
+        let result = prune_note_store(&note.path(), &settings);
+        total.removed += result.removed;
+        total.freed_bytes += result.freed_bytes;
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retention_cutoff_saturates() {
+        assert_eq!(retention_cutoff(10 * MILLIS_PER_DAY, 7), 3 * MILLIS_PER_DAY);
+        assert_eq!(retention_cutoff(10 * MILLIS_PER_DAY, 30), 0);
+        assert_eq!(retention_cutoff(10 * MILLIS_PER_DAY, u64::MAX), 0);
+    }
+
+    #[test]
+    fn parses_snapshot_names() {
+        assert_eq!(parse_snapshot_name("1700000000000.md"), Some((1700000000000, 0)));
+        assert_eq!(parse_snapshot_name("1700000000000-3.md"), Some((1700000000000, 3)));
+        assert_eq!(parse_snapshot_name("1700000000000-x.md"), None);
+        assert_eq!(parse_snapshot_name("path"), None);
+        assert_eq!(snapshot_name(1700000000000, 0), "1700000000000.md");
+        assert_eq!(snapshot_name(1700000000000, 3), "1700000000000-3.md");
+    }
+
+    #[test]
+    fn snapshots_in_the_same_millisecond_are_all_kept() {
+        let store = std::env::temp_dir().join(format!("igne-snapshots-{}", std::process::id()));
+        fs::create_dir_all(&store).unwrap();
+
+        let contents: Vec<String> = (0..20).map(|i| format!("version {}", i)).collect();
+        for content in &contents {
+            write_snapshot(&store, content.as_bytes()).unwrap();
+        }
+        let snapshots = read_snapshots(&store);
+        let newest = fs::read_to_string(&snapshots[0].2).unwrap();
+        fs::remove_dir_all(&store).unwrap();
+
+        assert_eq!(snapshots.len(), contents.len());
+        assert_eq!(newest, "version 19");
+    }
+}
@@ -0,0 +1,279 @@
+use serde::Serialize;
+use std::sync::OnceLock;
+use symspell::{SymSpell, SymSpellBuilder, UnicodeStringStrategy, Verbosity};
+
+use crate::blocking;
+use crate::error::IgneError;
+
+/// SymSpell's English frequency dictionary (`word count` per line, MIT licensed). It lists
+/// inflected forms and contractions as words of their own.
+const EN_WORDS: &str = include_str!("../dictionaries/en.txt");
+
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Suggestions are at most this many edits away from the misspelling
+const MAX_EDIT_DISTANCE: i64 = 2;
+
+/// Letters of each word SymSpell indexes; 5 instead of its default 7 halves the memory
+/// the English dictionary takes, to about 80 MB
+const PREFIX_LENGTH: i64 = 5;
+
+/// A misspelled word. Offsets are UTF-16 code units so they map directly onto
+/// CodeMirror document positions.
+#[derive(Serialize, Clone)]
+pub struct SpellError {
+    pub word: String,
+    pub start: usize,
+    pub end: usize,
+    pub suggestions: Vec<String>,
+}
+
+struct Dictionary {
+    symspell: SymSpell<UnicodeStringStrategy>,
+}
+
+impl Dictionary {
+    fn from_frequencies(list: &str) -> Self {
+        let mut symspell = SymSpellBuilder::default()
+            .max_dictionary_edit_distance(MAX_EDIT_DISTANCE)
+            .prefix_length(PREFIX_LENGTH)
+            .build()
+            .expect("every SymSpell setting has a default");
+        for line in list.lines() {
+            symspell.load_dictionary_line(line, 0, 1, " ");
+        }
+        Self { symspell }
+    }
+
+    fn contains(&self, word: &str) -> bool {
+        self.symspell
+            .lookup(word, Verbosity::Top, 0)
+            .first()
+            .is_some_and(|found| found.term == word)
+    }
+
+    /// A dictionary word, or one with a possessive `'s` added
+    fn knows(&self, word: &str) -> bool {
+        self.contains(word)
+            || word
+                .strip_suffix("'s")
+                .or_else(|| word.strip_suffix('\''))
+                .is_some_and(|stem| self.contains(stem))
+    }
+
+    /// The closest dictionary words, most frequent first
+    fn suggest(&self, word: &str) -> Vec<String> {
+        let mut found = self.symspell.lookup(word, Verbosity::Closest, MAX_EDIT_DISTANCE);
+        // The crate returns equally close words least frequent first
+        found.sort_by_key(|suggestion| (suggestion.distance, std::cmp::Reverse(suggestion.count)));
+        found
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|suggestion| suggestion.term)
+            .collect()
+    }
+}
+
+fn dictionary_for(language: &str) -> Option<&'static Dictionary> {
+    static EN: OnceLock<Dictionary> = OnceLock::new();
+
+    match language.to_lowercase().replace('_', "-").as_str() {
+        "en" | "en-us" | "en-gb" | "en-au" | "en-ca" => {
+            Some(EN.get_or_init(|| Dictionary::from_frequencies(EN_WORDS)))
+        }
+        _ => None,
+    }
+}
+
+/// Byte ranges that should never be spell checked: fenced code, inline code and wikilinks
+fn excluded_ranges(text: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+
+    // Fenced code blocks
+    let mut offset = 0;
+    let mut fence_start: Option<usize> = None;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            match fence_start.take() {
+                Some(start) => ranges.push((start, offset + line.len())),
+                None => fence_start = Some(offset),
+            }
+        }
+        offset += line.len();
+    }
+    if let Some(start) = fence_start {
+        ranges.push((start, text.len()));
+    }
+
+    // Inline code and wikilinks
+    for (open, close) in [("`", "`"), ("[[", "]]")] {
+        let mut from = 0;
+        while let Some(rel) = text[from..].find(open) {
+            let start = from + rel;
+            let Some(end_rel) = text[start + open.len()..].find(close) else {
+                break;
+            };
+            let end = start + open.len() + end_rel + close.len();
+            ranges.push((start, end));
+            from = end;
+        }
+    }
+
+    ranges
+}
+
+/// Tokens that look like URLs, emails, tags or identifiers rather than prose
+fn is_skippable_chunk(chunk: &str) -> bool {
+    chunk.contains("://") || chunk.starts_with("www.") || chunk.contains('@') || chunk.starts_with('#')
+}
+
+/// UTF-16 offsets of byte offsets visited in increasing order, counting only the text
+/// since the previous one
+struct Utf16Cursor<'a> {
+    text: &'a str,
+    byte: usize,
+    utf16: usize,
+}
+
+impl Utf16Cursor<'_> {
+    fn advance_to(&mut self, byte: usize) -> usize {
+        self.utf16 += self.text[self.byte..byte].encode_utf16().count();
+        self.byte = byte;
+        self.utf16
+    }
+}
+
+fn check_text(text: &str, dictionary: &Dictionary) -> Vec<SpellError> {
+    let excluded = excluded_ranges(text);
+    let is_excluded = |pos: usize| excluded.iter().any(|&(s, e)| pos >= s && pos < e);
+
+    let mut cursor = Utf16Cursor { text, byte: 0, utf16: 0 };
+    let mut errors = Vec::new();
+    let mut chunk_start = 0;
+
+    for chunk in text.split_inclusive(char::is_whitespace) {
+        let base = chunk_start;
+        chunk_start += chunk.len();
+
+        if is_skippable_chunk(chunk.trim()) {
+            continue;
+        }
+
+        // Split the chunk into words: letters with internal apostrophes
+        let mut word_start: Option<usize> = None;
+        let chars: Vec<(usize, char)> = chunk.char_indices().collect();
+        for (idx, &(i, c)) in chars.iter().enumerate() {
+            let next_is_letter = chars.get(idx + 1).map(|(_, n)| n.is_alphabetic()).unwrap_or(false);
+            let is_word_char = c.is_alphabetic()
+                || ((c == '\'' || c == '’') && word_start.is_some() && next_is_letter)
+                || c.is_ascii_digit()
+                || c == '_';
+
+            if is_word_char {
+                word_start.get_or_insert(i);
+                if idx + 1 < chars.len() {
+                    continue;
+                }
+            }
+
+            if let Some(start) = word_start.take() {
+                let end = if is_word_char { i + c.len_utf8() } else { i };
+                let word = &chunk[start..end];
+                if let Some(error) = check_word(&mut cursor, dictionary, word, base + start, &is_excluded) {
+                    errors.push(error);
+                }
+            }
+        }
+    }
+
+    errors
+}
+
+fn check_word(
+    cursor: &mut Utf16Cursor,
+    dictionary: &Dictionary,
+    word: &str,
+    byte_start: usize,
+    is_excluded: &dyn Fn(usize) -> bool,
+) -> Option<SpellError> {
+    // Identifiers, acronyms and single letters aren't worth flagging
+    if word.chars().count() < 2
+        || word.chars().any(|c| c.is_ascii_digit() || c == '_')
+        || word.chars().all(|c| !c.is_lowercase())
+        || is_excluded(byte_start)
+    {
+        return None;
+    }
+
+    let normalized = word.replace('’', "'").to_lowercase();
+    if dictionary.knows(&normalized) {
+        return None;
+    }
+
+    let start = cursor.advance_to(byte_start);
+    Some(SpellError {
+        word: word.to_string(),
+        start,
+        end: start + word.encode_utf16().count(),
+        suggestions: dictionary.suggest(&normalized),
+    })
+}
+
+/// Spell check a note's text against a bundled dictionary (defaults to English). Runs on
+/// the blocking pool: the first check of a language builds its index, which takes seconds.
+#[tauri::command]
+pub async fn spell_check_text(text: String, language: Option<String>) -> Result<Vec<SpellError>, IgneError> {
+    blocking(move || {
+        let language = language.unwrap_or_else(|| "en".to_string());
+        let dictionary = dictionary_for(&language)
+            .ok_or_else(|| IgneError::invalid_input(format!("Unsupported spell check language: {}", language)))?;
+        Ok(check_text(&text, dictionary))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(text: &str) -> Vec<SpellError> {
+        check_text(text, dictionary_for("en").unwrap())
+    }
+
+    #[test]
+    fn flags_misspellings_with_suggestions() {
+        let errors = check("I recieve teh letters");
+        let words: Vec<&str> = errors.iter().map(|e| e.word.as_str()).collect();
+        assert_eq!(words, ["recieve", "teh"]);
+        assert_eq!(errors[0].suggestions.first().map(String::as_str), Some("receive"));
+        assert_eq!(errors[1].suggestions.first().map(String::as_str), Some("the"));
+    }
+
+    #[test]
+    fn knows_inflections_contractions_and_possessives() {
+        assert!(check("She tries walking, doesn't she? The dog's bowls.").is_empty());
+    }
+
+    #[test]
+    fn reports_utf16_offsets() {
+        let text = "😀 é wrld and 😀 agian";
+        let errors = check(text);
+        assert_eq!(errors.len(), 2);
+        for error in errors {
+            let utf16: Vec<u16> = text.encode_utf16().collect();
+            assert_eq!(String::from_utf16(&utf16[error.start..error.end]).unwrap(), error.word);
+        }
+    }
+
+    #[test]
+    fn skips_code_links_and_urls() {
+        assert!(check("`fnord` [[Zorblat]] https://exmple.com\n```\nqwzx\n```\n").is_empty());
+    }
+
+    #[test]
+    fn rejects_unsupported_languages() {
+        let result = tauri::async_runtime::block_on(spell_check_text("text".into(), Some("xx".into())));
+        assert_eq!(result.err().map(|e| e.code()), Some("INVALID_INPUT"));
+    }
+}
@@ -0,0 +1,186 @@
+use log::{info, warn};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::error::IgneError;
+use crate::note_links::NoteLinksCache;
+use crate::performance_mode::{self, PerformanceMode};
+use crate::vault::walk_vault_files;
+use crate::{
+    is_markdown_file, last_opened_vault, long_path, read_dir_shallow, search_index, FileEntry, SortOrder,
+    MAIN_WINDOW,
+};
+
+#[derive(Serialize, Clone)]
+pub struct PhaseTiming {
+    /// "vault" or "index"
+    pub phase: &'static str,
+    /// How long the phase itself took
+    pub duration_ms: u64,
+    /// When it finished, counted from app launch
+    pub since_launch_ms: u64,
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct StartupTimings {
+    /// The vault being bootstrapped, once it's known
+    pub vault_path: Option<String>,
+    /// Finished phases in order
+    pub phases: Vec<PhaseTiming>,
+    /// Written by the index phase, for `query_search_index`
+    pub search_index_path: Option<String>,
+}
+
+/// Payload of `vault-ready`: the vault exists and its top level has been read
+#[derive(Serialize, Clone)]
+struct VaultReady {
+    path: String,
+    entries: Vec<FileEntry>,
+    timing: PhaseTiming,
+}
+
+/// Payload of `index-ready`: the search index is written and note links are cached
+#[derive(Serialize, Clone)]
+struct IndexReady {
+    path: String,
+    search_index_path: Option<String>,
+    notes: u64,
+    /// The vault is in manual performance mode, so nothing was indexed
+    skipped: bool,
+    timing: PhaseTiming,
+}
+
+/// When the app launched and how far the startup bootstrap has got
+pub struct StartupState {
+    launched: Instant,
+    timings: Mutex<StartupTimings>,
+}
+
+impl StartupState {
+    pub fn new() -> Self {
+        Self {
+            launched: Instant::now(),
+            timings: Mutex::new(StartupTimings::default()),
+        }
+    }
+
+    /// Record a finished phase that began at `started`
+    fn finish(&self, phase: &'static str, started: Instant) -> PhaseTiming {
+        let timing = PhaseTiming {
+            phase,
+            duration_ms: started.elapsed().as_millis() as u64,
+            since_launch_ms: self.launched.elapsed().as_millis() as u64,
+        };
+        info!(
+            "Startup phase {} took {} ms ({} ms since launch)",
+            phase, timing.duration_ms, timing.since_launch_ms
+        );
+        if let Ok(mut timings) = self.timings.lock() {
+            timings.phases.push(timing.clone());
+        }
+        timing
+    }
+
+    fn update(&self, change: impl FnOnce(&mut StartupTimings)) {
+        if let Ok(mut timings) = self.timings.lock() {
+            change(&mut timings);
+        }
+    }
+}
+
+impl Default for StartupState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The vault the frontend is about to reopen, if it had one open last time. On first
+/// run there is none; the frontend creates the default vault itself when it wants one.
+fn startup_vault(app: &AppHandle) -> Option<PathBuf> {
+    last_opened_vault(app).filter(|vault| vault.is_dir())
+}
+
+/// Warm what the frontend asks for first: the search index and every note's links
+fn warm_indexes(app: &AppHandle, vault: &Path) -> (Option<String>, u64) {
+    let search_index_path = match search_index::write_index(app, vault) {
+        Ok(path) => Some(path.to_string_lossy().to_string()),
+        Err(e) => {
+            warn!("Failed to build search index for {}: {}", vault.display(), e);
+            None
+        }
+    };
+
+    (search_index_path, warm_note_links(app, vault))
+}
+
+/// Parse and cache the links of every note in the vault, returning how many were read
+pub fn warm_note_links(app: &AppHandle, vault: &Path) -> u64 {
+    let links = app.state::<NoteLinksCache>();
+    let mut notes = 0;
+    for file in walk_vault_files(vault).unwrap_or_default() {
+        if is_markdown_file(&file.to_string_lossy()) && links.get(&file).is_ok() {
+            notes += 1;
+        }
+    }
+    notes
+}
+
+fn bootstrap(app: &AppHandle) -> Result<(), IgneError> {
+    let state = app.state::<StartupState>();
+
+    let started = Instant::now();
+    let Some(vault) = startup_vault(app) else {
+        info!("No vault to reopen; skipping the startup bootstrap");
+        return Ok(());
+    };
+    let path = vault.to_string_lossy().to_string();
+    let entries = read_dir_shallow(&long_path(&vault), SortOrder::FoldersFirst)?;
+    state.update(|timings| timings.vault_path = Some(path.clone()));
+    let timing = state.finish("vault", started);
+    let ready = VaultReady {
+        path: path.clone(),
+        entries: entries.clone(),
+        timing,
+    };
+    let _ = app.emit_to(MAIN_WINDOW, "vault-ready", ready);
+
+    // The tree itself is walked by the window as it opens the vault
+    let mode = performance_mode::wait_for_index(app, &vault);
+    let started = Instant::now();
+    let skipped = mode == PerformanceMode::Manual;
+    let (search_index_path, notes) = if skipped { (None, 0) } else { warm_indexes(app, &vault) };
+    state.update(|timings| timings.search_index_path = search_index_path.clone());
+    let timing = state.finish("index", started);
+    let ready = IndexReady {
+        path,
+        search_index_path,
+        notes,
+        skipped,
+        timing,
+    };
+    let _ = app.emit_to(MAIN_WINDOW, "index-ready", ready);
+
+    Ok(())
+}
+
+/// Start the vault bootstrap on a background thread from `setup()`, so the window shows
+/// while the last vault is listed and indexed. Progress arrives as `vault-ready` and
+/// `index-ready`, usually before the window listens, so it catches up with
+/// `get_startup_timings`.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = bootstrap(&app) {
+            warn!("Startup bootstrap stopped: {}", e);
+        }
+    });
+}
+
+/// How long each finished startup phase took, so slow starts can be measured and reported
+#[tauri::command]
+pub fn get_startup_timings(state: State<'_, StartupState>) -> Result<StartupTimings, IgneError> {
+    Ok(state.timings.lock()?.clone())
+}
@@ -0,0 +1,196 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::error::IgneError;
+use crate::paths::long_path;
+use crate::scope::ensure_in_scope;
+use crate::vault::walk_vault_files;
+use crate::write_lock::with_write_lock;
+use crate::{atomic_write, is_markdown_file, markdown};
+
+#[derive(Serialize, Clone)]
+pub struct TagInfo {
+    pub tag: String,
+    pub count: u64,
+}
+
+/// Tag counts keyed by lowercase tag, keeping the first spelling seen for display
+#[derive(Default)]
+struct TagCounts(HashMap<String, TagInfo>);
+
+impl TagCounts {
+    fn add(&mut self, tags: Vec<String>) {
+        for tag in tags {
+            self.0
+                .entry(tag.to_lowercase())
+                .or_insert_with(|| TagInfo { tag, count: 0 })
+                .count += 1;
+        }
+    }
+}
+
+fn is_fence(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("```") || trimmed.starts_with("~~~")
+}
+
+/// Count the tags of one note, reading it a paragraph at a time. Inline code can't span a
+/// blank line, so paragraphs can be scanned independently; fenced blocks are skipped.
+fn scan_note(path: &Path, counts: &mut TagCounts) -> io::Result<()> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut line = String::new();
+
+    if reader.read_line(&mut line)? > 0 && line.trim_end() == "---" {
+        let mut frontmatter = line.clone();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                // Unclosed frontmatter is ordinary text
+                counts.add(markdown::inline_tags(&format!("\n{}", frontmatter)));
+                return Ok(());
+            }
+            frontmatter.push_str(&line);
+            if line.trim_end() == "---" {
+                break;
+            }
+        }
+        counts.add(markdown::frontmatter_tags(&frontmatter));
+        line.clear();
+    }
+
+    // Paragraphs start with a newline so a `---` line is never taken for frontmatter
+    let mut paragraph = String::from("\n");
+    let mut in_fence = false;
+    loop {
+        if line.is_empty() && reader.read_line(&mut line)? == 0 {
+            break;
+        }
+
+        if is_fence(&line) {
+            in_fence = !in_fence;
+        }
+        if in_fence || is_fence(&line) || line.trim().is_empty() {
+            if paragraph.len() > 1 {
+                counts.add(markdown::inline_tags(&paragraph));
+                paragraph.truncate(1);
+            }
+        } else {
+            paragraph.push_str(&line);
+        }
+        line.clear();
+    }
+    counts.add(markdown::inline_tags(&paragraph));
+
+    Ok(())
+}
+
+/// Every tag used in the vault (frontmatter and inline, code excluded) with how often it
+/// appears, most used first. Tags differing only in case are counted together.
+#[tauri::command]
+pub fn get_all_tags(vault_path: String, app: AppHandle) -> Result<Vec<TagInfo>, IgneError> {
+    let root = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &root)?;
+
+    let mut counts = TagCounts::default();
+    for file in walk_vault_files(&root)? {
+        if is_markdown_file(&file.to_string_lossy()) {
+            // Unreadable or non-UTF-8 notes are skipped rather than failing the inventory
+            let _ = scan_note(&file, &mut counts);
+        }
+    }
+
+    let mut tags: Vec<TagInfo> = counts.0.into_values().collect();
+    tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.to_lowercase().cmp(&b.tag.to_lowercase())));
+    Ok(tags)
+}
+
+/// Frontmatter keys Obsidian reads tags from
+const TAG_KEYS: [&str; 2] = ["tags", "tag"];
+
+/// Tags made of tag characters are written bare; anything else is quoted for YAML
+fn yaml_tag(tag: &str) -> String {
+    if tag.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '/')) {
+        tag.to_string()
+    } else {
+        format!("\"{}\"", tag.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// `content` with its `tags:`/`tag:` entries replaced by one `tags:` block list, added
+/// where the first of them was (or at the end of the frontmatter, creating it if needed).
+/// Every other frontmatter line and the body are copied unchanged.
+fn write_frontmatter_tags(content: &str, tags: &[String]) -> String {
+    let newline = if content.starts_with("---\r\n") { "\r\n" } else { "\n" };
+    let mut block = format!("tags:{}", newline);
+    for tag in tags {
+        block.push_str(&format!("  - {}{}", yaml_tag(tag), newline));
+    }
+
+    let Some((inner_start, inner_end)) = markdown::frontmatter_inner_range(content) else {
+        return format!("---{nl}{}---{nl}{}", block, content, nl = newline);
+    };
+    let inner = &content[inner_start..inner_end];
+
+    let mut rewritten = String::with_capacity(inner.len() + block.len());
+    let mut in_tag_key = false;
+    let mut inserted = false;
+    for line in inner.split_inclusive('\n') {
+        let starts_key = !line.starts_with(char::is_whitespace) && !line.starts_with('-');
+        if starts_key {
+            in_tag_key = TAG_KEYS
+                .iter()
+                .any(|key| line.strip_prefix(key).is_some_and(|rest| rest.starts_with(':')));
+        }
+        if in_tag_key && !line.trim().is_empty() {
+            if !inserted {
+                rewritten.push_str(&block);
+                inserted = true;
+            }
+            continue;
+        }
+        rewritten.push_str(line);
+    }
+    if !inserted {
+        if !rewritten.is_empty() && !rewritten.ends_with('\n') {
+            rewritten.push_str(newline);
+        }
+        rewritten.push_str(&block);
+    }
+
+    format!("{}{}{}", &content[..inner_start], rewritten, &content[inner_end..])
+}
+
+/// Collect a note's frontmatter and inline tags, drop duplicates (ignoring case, keeping
+/// the first spelling unless `lowercase`), and write them back as the frontmatter `tags`
+/// list. Inline tags stay in the body. Returns the tag list that was written.
+#[tauri::command]
+pub async fn normalize_tags(path: String, lowercase: bool, app: AppHandle) -> Result<Vec<String>, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+
+    with_write_lock(&app, &path_obj, || {
+        let content = fs::read_to_string(long_path(&path_obj)).map_err(|e| IgneError::io(e, &path))?;
+
+        let mut seen = HashSet::new();
+        let tags: Vec<String> = markdown::extract_tags(&content)
+            .into_iter()
+            .map(|tag| if lowercase { tag.to_lowercase() } else { tag })
+            .filter(|tag| seen.insert(tag.to_lowercase()))
+            .collect();
+        // Nothing to declare: don't add an empty frontmatter block to a tagless note
+        if tags.is_empty() {
+            return Ok(tags);
+        }
+
+        let normalized = write_frontmatter_tags(&content, &tags);
+        if normalized != content {
+            atomic_write(&path_obj, normalized.as_bytes())?;
+        }
+        Ok(tags)
+    })
+    .await
+}
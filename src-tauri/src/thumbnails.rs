@@ -0,0 +1,149 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager};
+
+use crate::atomic_write;
+use crate::error::IgneError;
+use crate::paths::long_path;
+use crate::scope::{ensure_in_scope, resolve};
+use crate::snapshots::stable_hash;
+
+/// Cached thumbnails live in `<app_data>/thumbnails/`, named
+/// `<path hash>-<source mtime>-<max dimension>.<jpg|webp>`
+const THUMBNAIL_DIR: &str = "thumbnails";
+const DEFAULT_MAX_DIMENSION: u32 = 256;
+/// Thumbnails larger than this are pointless for a file tree or picker
+const MAX_DIMENSION_LIMIT: u32 = 2048;
+const THUMBNAIL_JPEG_QUALITY: u8 = 80;
+
+#[derive(Serialize, Clone, Default)]
+pub struct ThumbnailCacheStats {
+    pub file_count: u64,
+    pub total_bytes: u64,
+}
+
+fn thumbnail_dir(app: &AppHandle) -> Result<PathBuf, IgneError> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(THUMBNAIL_DIR))
+        .map_err(|e| IgneError::other(format!("Failed to get app data dir: {}", e)))
+}
+
+/// Opaque images become JPEG; ones with transparency become (lossless) WebP, which the
+/// image crate can encode without dropping the alpha channel
+fn encode_thumbnail(img: &image::DynamicImage) -> Result<(Vec<u8>, &'static str), IgneError> {
+    use image::codecs::jpeg::JpegEncoder;
+
+    let mut out = Vec::new();
+    let (result, extension) = if img.color().has_alpha() {
+        (img.write_to(&mut std::io::Cursor::new(&mut out), image::ImageFormat::WebP), "webp")
+    } else {
+        let encoder = JpegEncoder::new_with_quality(&mut out, THUMBNAIL_JPEG_QUALITY);
+        (img.to_rgb8().write_with_encoder(encoder), "jpg")
+    };
+    result.map_err(|e| IgneError::other(format!("Failed to encode thumbnail: {}", e)))?;
+    Ok((out, extension))
+}
+
+fn generate(source: &Path, path: &str, max_dimension: u32) -> Result<(Vec<u8>, &'static str), IgneError> {
+    let reader = image::ImageReader::open(long_path(source))
+        .map_err(|e| IgneError::io(e, path))?
+        .with_guessed_format()
+        .map_err(|e| IgneError::io(e, path))?;
+    if reader.format().is_none() {
+        return Err(IgneError::unsupported_image(format!("Unsupported image format: {}", path), path));
+    }
+    let img = reader
+        .decode()
+        .map_err(|e| IgneError::unsupported_image(format!("Could not decode image {}: {}", path, e), path))?;
+
+    // `thumbnail` keeps the aspect ratio; images already small enough are only re-encoded
+    let img = if img.width() > max_dimension || img.height() > max_dimension {
+        img.thumbnail(max_dimension, max_dimension)
+    } else {
+        img
+    };
+    encode_thumbnail(&img)
+}
+
+/// A scaled-down copy of an image attachment, at most `max_dimension` pixels (default 256)
+/// on its longest side. Results are cached on disk until the source file changes.
+/// Images that can't be decoded fail with `UNSUPPORTED_IMAGE` so the UI can show a
+/// placeholder.
+#[tauri::command]
+pub async fn get_thumbnail(path: String, max_dimension: Option<u32>, app: AppHandle) -> Result<Vec<u8>, IgneError> {
+    let source = PathBuf::from(&path);
+    ensure_in_scope(&app, &source)?;
+    let max_dimension = max_dimension.unwrap_or(DEFAULT_MAX_DIMENSION).clamp(1, MAX_DIMENSION_LIMIT);
+
+    let modified = fs::metadata(long_path(&source))
+        .and_then(|m| m.modified())
+        .map_err(|e| IgneError::io(e, &path))?;
+    let mtime = modified.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let key = stable_hash(&resolve(&source).to_string_lossy());
+    let stem = format!("{}-{}-{}", key, mtime, max_dimension);
+
+    let dir = thumbnail_dir(&app)?;
+    for extension in ["jpg", "webp"] {
+        if let Ok(bytes) = fs::read(dir.join(format!("{}.{}", stem, extension))) {
+            return Ok(bytes);
+        }
+    }
+
+    let (bytes, extension) =
+        tauri::async_runtime::spawn_blocking(move || generate(&source, &path, max_dimension))
+            .await
+            .map_err(|e| IgneError::other(format!("Thumbnail task failed: {}", e)))??;
+
+    // Thumbnails of an older version of the image are never read again
+    if let Ok(entries) = fs::read_dir(&dir) {
+        let (prefix, current) = (format!("{}-", key), format!("{}-{}-", key, mtime));
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with(&prefix) && !name.starts_with(&current) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    fs::create_dir_all(&dir).map_err(|e| IgneError::io(e, &dir))?;
+    atomic_write(&dir.join(format!("{}.{}", stem, extension)), &bytes)?;
+    Ok(bytes)
+}
+
+fn cache_stats(dir: &Path) -> ThumbnailCacheStats {
+    let mut stats = ThumbnailCacheStats::default();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return stats;
+    };
+    for entry in entries.flatten() {
+        match entry.metadata() {
+            Ok(metadata) if metadata.is_file() => {
+                stats.file_count += 1;
+                stats.total_bytes += metadata.len();
+            }
+            _ => {}
+        }
+    }
+    stats
+}
+
+/// How many thumbnails are cached and how much disk they use
+#[tauri::command]
+pub fn get_thumbnail_cache_size(app: AppHandle) -> Result<ThumbnailCacheStats, IgneError> {
+    Ok(cache_stats(&thumbnail_dir(&app)?))
+}
+
+/// Delete every cached thumbnail, returning what was removed
+#[tauri::command]
+pub fn clear_thumbnail_cache(app: AppHandle) -> Result<ThumbnailCacheStats, IgneError> {
+    let dir = thumbnail_dir(&app)?;
+    let stats = cache_stats(&dir);
+    match fs::remove_dir_all(&dir) {
+        Ok(()) => Ok(stats),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(stats),
+        Err(e) => Err(IgneError::io(e, &dir)),
+    }
+}
@@ -0,0 +1,168 @@
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::error::IgneError;
+use crate::markdown_ast::parser_options;
+use crate::paths::{long_path, nfc};
+use crate::scope::ensure_in_scope;
+
+#[derive(Serialize, Clone)]
+pub struct TocEntry {
+    pub level: u8,
+    pub text: String,
+    /// GitHub-style slug for scrolling to the heading
+    pub anchor: String,
+    pub children: Vec<TocEntry>,
+}
+
+/// GitHub's heading slug: lowercase, punctuation dropped, spaces become hyphens
+fn slugify(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Repeated headings get `-1`, `-2`, ... like GitHub does
+fn unique_anchor(slug: String, seen: &mut HashMap<String, usize>) -> String {
+    match seen.get_mut(&slug) {
+        Some(count) => {
+            *count += 1;
+            let anchor = format!("{}-{}", slug, count);
+            seen.insert(anchor.clone(), 0);
+            anchor
+        }
+        None => {
+            seen.insert(slug.clone(), 0);
+            slug
+        }
+    }
+}
+
+/// Nest under the last entry while it is a shallower heading, so skipped levels still nest
+fn insert(entries: &mut Vec<TocEntry>, entry: TocEntry) {
+    match entries.last_mut() {
+        Some(last) if last.level < entry.level => insert(&mut last.children, entry),
+        _ => entries.push(entry),
+    }
+}
+
+/// A heading with its plain text (formatting stripped) and the byte offset of its line
+struct Heading {
+    level: u8,
+    text: String,
+    start: usize,
+}
+
+fn headings(content: &str) -> Vec<Heading> {
+    let mut headings = vec![];
+    let mut current: Option<Heading> = None;
+
+    for (event, range) in Parser::new_ext(content, parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                current = Some(Heading { level: level as u8, text: String::new(), start: range.start })
+            }
+            Event::Text(text) | Event::Code(text) | Event::InlineMath(text) => {
+                if let Some(heading) = current.as_mut() {
+                    heading.text.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => headings.extend(current.take()),
+            _ => {}
+        }
+    }
+
+    headings
+}
+
+/// Text of the first top-level heading, used as the note's display title
+pub fn first_h1(content: &str) -> Option<String> {
+    headings(content)
+        .into_iter()
+        .find(|heading| heading.level == 1)
+        .map(|heading| heading.text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+pub fn build_toc(content: &str) -> Vec<TocEntry> {
+    let mut toc = vec![];
+    let mut seen = HashMap::new();
+
+    for heading in headings(content) {
+        let anchor = unique_anchor(slugify(&heading.text), &mut seen);
+        insert(
+            &mut toc,
+            TocEntry { level: heading.level, text: heading.text, anchor, children: vec![] },
+        );
+    }
+
+    toc
+}
+
+/// Nested table of contents for a note, following heading levels
+#[tauri::command]
+pub fn get_toc(path: String, app: AppHandle) -> Result<Vec<TocEntry>, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+    let content = fs::read_to_string(long_path(&path_obj)).map_err(|e| IgneError::io(e, &path))?;
+    Ok(build_toc(&content))
+}
+
+#[derive(Serialize, Clone)]
+pub struct Section {
+    pub heading: String,
+    pub level: u8,
+    /// 0-based line of the heading
+    pub start_line: usize,
+    /// The heading line through to the next heading of the same or higher level
+    pub content: String,
+}
+
+/// Heading text comparison for `[[Note#Heading]]` links: case-insensitive, NFC, and
+/// ignoring surrounding whitespace
+fn heading_key(text: &str) -> String {
+    nfc(text.trim()).to_lowercase()
+}
+
+/// The section of `content` under `heading`, matched like `[[Note#Heading]]` links are
+pub fn find_section(content: &str, heading: &str) -> Option<Section> {
+    let all = headings(content);
+    let wanted = heading_key(heading);
+    let index = all.iter().position(|h| heading_key(&h.text) == wanted)?;
+
+    let found = &all[index];
+    let end = all[index + 1..]
+        .iter()
+        .find(|h| h.level <= found.level)
+        .map(|h| h.start)
+        .unwrap_or(content.len());
+
+    Some(Section {
+        heading: found.text.clone(),
+        level: found.level,
+        start_line: content[..found.start].matches('\n').count(),
+        content: content[found.start..end].trim_end().to_string(),
+    })
+}
+
+/// The section under `heading`, for jumping to or transcluding `[[Note#Heading]]`
+#[tauri::command]
+pub fn read_section(path: String, heading: String, app: AppHandle) -> Result<Section, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+    let content = fs::read_to_string(long_path(&path_obj)).map_err(|e| IgneError::io(e, &path))?;
+
+    find_section(&content, &heading).ok_or_else(|| {
+        IgneError::not_found(format!("Heading \"{}\" not found in {}", heading, path), &path)
+    })
+}
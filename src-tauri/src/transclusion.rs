@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::error::IgneError;
+use crate::note_move::VaultFiles;
+use crate::paths::vault_relative;
+use crate::scope::ensure_in_scope;
+use crate::toc::find_section;
+use crate::{is_markdown_file, markdown};
+
+/// An embed: `![[target]]` or `![[target#heading]]`, with its byte range
+struct Embed {
+    start: usize,
+    end: usize,
+    target: String,
+    heading: Option<String>,
+}
+
+/// `![[...]]` embeds outside code, except same-note and `#^block` embeds
+fn note_embeds(content: &str) -> Vec<Embed> {
+    let mut embeds = vec![];
+    for link in markdown::extract_wikilinks(content) {
+        if link.target.is_empty() {
+            continue;
+        }
+        let Some(open) = content[..link.start].rfind("![[") else {
+            continue;
+        };
+        if !content[open + 3..link.start].trim().is_empty() {
+            continue;
+        }
+        let Some(close) = content[link.end..].find("]]").map(|i| link.end + i) else {
+            continue;
+        };
+
+        let link_text = content[link.end..close].split_once('|').map_or(&content[link.end..close], |(l, _)| l);
+        let heading = link_text.strip_prefix('#').map(str::trim).filter(|h| !h.is_empty());
+        if heading.is_some_and(|h| h.starts_with('^')) {
+            continue;
+        }
+        embeds.push(Embed {
+            start: open,
+            end: close + 2,
+            target: link.target,
+            heading: heading.map(str::to_string),
+        });
+    }
+    embeds
+}
+
+struct Expander<'a> {
+    root: &'a Path,
+    files: VaultFiles,
+    /// Note contents by vault-relative path, `None` when unreadable
+    contents: HashMap<String, Option<String>>,
+}
+
+impl Expander<'_> {
+    fn read(&mut self, relative: &str) -> Option<String> {
+        let root = self.root;
+        self.contents
+            .entry(relative.to_string())
+            .or_insert_with(|| fs::read_to_string(root.join(relative)).ok())
+            .clone()
+    }
+
+    /// `content` of the note at `relative` with its embeds replaced, `depth` levels deep.
+    /// Notes already being expanded on the way here (`stack`) are left as embeds.
+    fn expand(&mut self, content: &str, relative: &str, depth: usize, stack: &mut Vec<String>) -> String {
+        if depth == 0 {
+            return content.to_string();
+        }
+        let source_dir = relative.rsplit_once('/').map_or("", |(dir, _)| dir);
+
+        let mut result = String::with_capacity(content.len());
+        let mut last = 0;
+        for embed in note_embeds(content) {
+            let Some(target) = self.files.resolve_wikilink(&embed.target, source_dir) else {
+                continue;
+            };
+            // Images and other attachments stay embeds
+            if !is_markdown_file(&target) || stack.contains(&target) {
+                continue;
+            }
+            let Some(note) = self.read(&target) else {
+                continue;
+            };
+            let embedded = match &embed.heading {
+                Some(heading) => match find_section(&note, heading) {
+                    Some(section) => section.content,
+                    None => continue,
+                },
+                None => markdown::body(&note).trim().to_string(),
+            };
+
+            stack.push(target.clone());
+            let expanded = self.expand(&embedded, &target, depth - 1, stack);
+            stack.pop();
+
+            result.push_str(&content[last..embed.start]);
+            result.push_str(&expanded);
+            last = embed.end;
+        }
+        result.push_str(&content[last..]);
+        result
+    }
+}
+
+/// Replace `![[Note]]` and `![[Note#Heading]]` embeds with the note's body or section,
+/// recursively up to `max_depth` levels, for exporting a self-contained note. Embeds
+/// that don't resolve, that would loop back to a note being expanded, and embeds of
+/// images and other files are left as written.
+#[tauri::command]
+pub fn expand_transclusions(
+    content: String,
+    root: String,
+    from_path: String,
+    max_depth: usize,
+    app: AppHandle,
+) -> Result<String, IgneError> {
+    let root_path = PathBuf::from(&root);
+    ensure_in_scope(&app, &root_path)?;
+    let from = vault_relative(&root_path, Path::new(&from_path))?;
+
+    let mut expander = Expander {
+        root: &root_path,
+        files: VaultFiles::scan(&root_path)?,
+        contents: HashMap::new(),
+    };
+    Ok(expander.expand(&content, &from, max_depth, &mut vec![from.clone()]))
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::IgneError;
+use crate::is_markdown_file;
+use crate::markdown;
+use crate::note_links::NoteLinksCache;
+use crate::note_move::VaultFiles;
+use crate::scope::ensure_in_scope;
+
+/// Decoration settings from `<app_data_dir>/settings.json`
+#[derive(Deserialize, Default)]
+struct DecorationSettings {
+    /// Frontmatter keys shown in the file tree, e.g. `["status", "priority"]`
+    #[serde(default, rename = "treeDecorationKeys")]
+    keys: Vec<String>,
+}
+
+fn decoration_keys(app: &AppHandle) -> Vec<String> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join("settings.json")).ok())
+        .and_then(|content| serde_json::from_str::<DecorationSettings>(&content).ok())
+        .unwrap_or_default()
+        .keys
+}
+
+#[derive(Serialize, Clone)]
+pub struct TreeDecoration {
+    pub path: String,
+    /// Other notes linking here
+    pub backlink_count: u64,
+    /// Distinct link targets of the note, resolved or not; embeds aren't counted
+    pub outgoing_link_count: u64,
+    pub open_task_count: u64,
+    /// The `treeDecorationKeys` the note has: a string for scalars, an array for lists
+    pub frontmatter_keys: BTreeMap<String, Value>,
+}
+
+fn parent_dir(relative: &str) -> &str {
+    relative.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/// `key` from the frontmatter block, as a list when written as one
+fn project(frontmatter: &str, key: &str) -> Option<Value> {
+    match markdown::frontmatter_value(frontmatter, key) {
+        Some(value) if !value.starts_with('[') => Some(Value::from(value)),
+        _ => {
+            let list = markdown::frontmatter_list(frontmatter, key);
+            (!list.is_empty()).then(|| Value::from(list))
+        }
+    }
+}
+
+/// Counts and frontmatter to show next to each markdown file in the tree, for `paths` or
+/// the whole vault. Built from the cached note summaries, so only notes that changed since
+/// they were last read get parsed again. The frontmatter keys come from the
+/// `treeDecorationKeys` setting; without it none are included.
+#[tauri::command]
+pub fn get_tree_decorations(
+    vault_path: String,
+    paths: Option<Vec<String>>,
+    app: AppHandle,
+    cache: State<'_, NoteLinksCache>,
+) -> Result<Vec<TreeDecoration>, IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &vault)?;
+    let keys = decoration_keys(&app);
+    let wanted: Option<HashSet<PathBuf>> = paths.map(|paths| paths.iter().map(PathBuf::from).collect());
+
+    let files = VaultFiles::scan(&vault)?;
+    let mut notes = vec![];
+    for relative in files.files().iter().filter(|f| is_markdown_file(f)) {
+        let path = relative.split('/').fold(vault.clone(), |path, part| path.join(part));
+        // Notes that can't be read right now simply go without decorations
+        if let Ok(summary) = cache.summary(&path) {
+            notes.push((relative, path, summary));
+        }
+    }
+
+    // Every note links in, so the whole vault is resolved even for a few `paths`
+    let mut backlinks: HashMap<String, u64> = HashMap::new();
+    for (relative, _, summary) in &notes {
+        let targets: HashSet<String> = summary
+            .links
+            .links
+            .iter()
+            .filter_map(|target| files.resolve_link(target, parent_dir(relative)))
+            .filter(|target| target != *relative)
+            .collect();
+        for target in targets {
+            *backlinks.entry(target).or_default() += 1;
+        }
+    }
+
+    Ok(notes
+        .into_iter()
+        .filter(|(_, path, _)| wanted.as_ref().is_none_or(|wanted| wanted.contains(path)))
+        .map(|(relative, path, summary)| {
+            let frontmatter = summary.frontmatter.as_deref().unwrap_or_default();
+            TreeDecoration {
+                path: path.to_string_lossy().to_string(),
+                backlink_count: backlinks.get(relative).copied().unwrap_or(0),
+                outgoing_link_count: summary.links.links.len() as u64,
+                open_task_count: summary.open_tasks,
+                frontmatter_keys: keys
+                    .iter()
+                    .filter_map(|key| Some((key.clone(), project(frontmatter, key)?)))
+                    .collect(),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,198 @@
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::error::IgneError;
+use crate::paths::safe_join_path;
+use crate::fs_move::move_path;
+use crate::scope::ensure_in_scope;
+use crate::vault_trash::move_to_free_path;
+use crate::write_lock::with_write_lock;
+use crate::check_protected_path;
+
+/// Deleted items wait under `<app_data>/undo_delete/<token>/` until restored or expired
+const UNDO_DIR: &str = "undo_delete";
+const MANIFEST_FILE: &str = "original.json";
+/// The deleted item goes in this subfolder of its backup, so a note that happens to be
+/// named like the manifest can't take its place
+const ITEM_DIR: &str = "item";
+/// How long "Undo delete" stays possible
+const BACKUP_TTL_SECS: u64 = 10 * 60;
+
+static NEXT_TOKEN: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Serialize, Deserialize)]
+struct BackupManifest {
+    original: String,
+    deleted_at: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DeletedBackup {
+    /// Pass to `restore_deleted`
+    pub token: String,
+    /// Seconds since the epoch after which the backup may be purged
+    pub expires_at: u64,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn undo_root(app: &AppHandle) -> Result<PathBuf, IgneError> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(UNDO_DIR))
+        .map_err(|e| IgneError::other(format!("Failed to get app data dir: {}", e)))
+}
+
+fn read_manifest(backup: &Path) -> Option<BackupManifest> {
+    fs::read_to_string(backup.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+/// Remove backups older than the TTL. Backups without a readable manifest can't be
+/// restored anyway and go too.
+pub fn purge_expired(app: &AppHandle) {
+    if let Ok(root) = undo_root(app) {
+        purge_backups(&root, now_secs());
+    }
+}
+
+fn purge_backups(root: &Path, now: u64) {
+    let Ok(entries) = fs::read_dir(root) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let backup = entry.path();
+        let expired = read_manifest(&backup)
+            .is_none_or(|manifest| now.saturating_sub(manifest.deleted_at) > BACKUP_TTL_SECS);
+        if expired {
+            if let Err(e) = fs::remove_dir_all(&backup) {
+                warn!("Failed to purge delete backup {}: {}", backup.display(), e);
+            }
+        }
+    }
+}
+
+/// Delete a file or folder by moving it to a short-lived backup, so the UI can offer
+/// "Undo delete" for ten minutes without involving the OS trash
+#[tauri::command]
+pub async fn delete_file_with_backup(
+    path: String,
+    force: Option<bool>,
+    app: AppHandle,
+) -> Result<DeletedBackup, IgneError> {
+    let source = PathBuf::from(&path);
+    ensure_in_scope(&app, &source)?;
+    check_protected_path(&app, &source, force.unwrap_or(false))?;
+    purge_expired(&app);
+
+    let file_name = source
+        .file_name()
+        .ok_or_else(|| IgneError::invalid_path(format!("Not a file path: {}", path), &path))?;
+    let deleted_at = now_secs();
+    let token = format!("{}-{}-{}", deleted_at, std::process::id(), NEXT_TOKEN.fetch_add(1, Ordering::Relaxed));
+    let backup = undo_root(&app)?.join(&token);
+
+    with_write_lock(&app, &source, || {
+        if !source.exists() {
+            return Err(IgneError::not_found(format!("Path does not exist: {}", path), &path));
+        }
+        let item_dir = backup.join(ITEM_DIR);
+        fs::create_dir_all(&item_dir).map_err(|e| IgneError::io(e, &item_dir))?;
+        let manifest = serde_json::to_string(&BackupManifest {
+            original: path.clone(),
+            deleted_at,
+        })?;
+        let manifest_path = backup.join(MANIFEST_FILE);
+        fs::write(&manifest_path, manifest).map_err(|e| IgneError::io(e, &manifest_path))?;
+
+        if let Err(e) = move_path(&source, &item_dir.join(file_name), false) {
+            let _ = fs::remove_dir_all(&backup);
+            return Err(e);
+        }
+        Ok(())
+    })
+    .await?;
+
+    Ok(DeletedBackup {
+        token,
+        expires_at: deleted_at + BACKUP_TTL_SECS,
+    })
+}
+
+/// Move a backed-up item back to where it was deleted from. Returns the restored path,
+/// which gets a numeric suffix if something now occupies the original location.
+#[tauri::command]
+pub async fn restore_deleted(token: String, app: AppHandle) -> Result<String, IgneError> {
+    if token.is_empty() || !token.chars().all(|c| c.is_ascii_digit() || c == '-') {
+        return Err(IgneError::invalid_input(format!("Invalid undo token: {}", token)));
+    }
+    let backup = safe_join_path(&undo_root(&app)?, &token)?;
+    // A token past its `expires_at` fails like one whose backup is already gone
+    purge_expired(&app);
+    let manifest = read_manifest(&backup).ok_or_else(|| {
+        IgneError::not_found(format!("Deleted item is no longer available: {}", token), &backup)
+    })?;
+
+    let original = PathBuf::from(&manifest.original);
+    ensure_in_scope(&app, &original)?;
+    let file_name = original.file_name().ok_or_else(|| {
+        IgneError::invalid_path(format!("Not a file path: {}", manifest.original), &original)
+    })?;
+
+    let destination = with_write_lock(&app, &original, || {
+        if let Some(parent) = original.parent() {
+            fs::create_dir_all(parent).map_err(|e| IgneError::io(e, parent))?;
+        }
+        move_to_free_path(&backup.join(ITEM_DIR).join(file_name), &original)
+    })
+    .await?;
+
+    if let Err(e) = fs::remove_dir_all(&backup) {
+        warn!("Failed to remove delete backup {}: {}", backup.display(), e);
+    }
+    Ok(destination.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn purges_expired_and_unreadable_backups() {
+        let root = std::env::temp_dir().join(format!("igne-undo-delete-{}", std::process::id()));
+        let now = 1_700_000_000;
+        let backup = |token: &str, deleted_at: Option<u64>| {
+            let dir = root.join(token);
+            fs::create_dir_all(dir.join(ITEM_DIR)).unwrap();
+            if let Some(deleted_at) = deleted_at {
+                let manifest = BackupManifest { original: "/vault/note.md".into(), deleted_at };
+                fs::write(dir.join(MANIFEST_FILE), serde_json::to_string(&manifest).unwrap()).unwrap();
+            }
+        };
+        backup("fresh", Some(now - 60));
+        backup("last-second", Some(now - BACKUP_TTL_SECS));
+        backup("expired", Some(now - BACKUP_TTL_SECS - 1));
+        backup("no-manifest", None);
+
+        purge_backups(&root, now);
+        let mut left: Vec<String> = fs::read_dir(&root)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        left.sort();
+        assert_eq!(left, ["fresh", "last-second"]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
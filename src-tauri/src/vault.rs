@@ -0,0 +1,272 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::error::IgneError;
+use crate::scope::ensure_in_scope;
+use crate::paths::nfc;
+use crate::{is_markdown_file, links, markdown, note_kind};
+
+/// Hidden entries (`.obsidian`, `.git`, `.trash`, ...) are config or history, not vault content
+pub fn is_excluded_name(name: &str) -> bool {
+    name.starts_with('.')
+}
+
+/// Every file under `root`, skipping hidden entries. Symlinked folders are not followed.
+pub fn walk_vault_files(root: &Path) -> Result<Vec<PathBuf>, IgneError> {
+    let mut files = vec![];
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            // The root must be readable; unreadable subfolders are skipped
+            Err(e) if dir == root => return Err(IgneError::io(e, root)),
+            Err(_) => continue,
+        };
+
+        for entry in entries.flatten() {
+            if is_excluded_name(&entry.file_name().to_string_lossy()) {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                stack.push(entry.path());
+            } else if file_type.is_file() {
+                files.push(entry.path());
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct VaultStats {
+    pub note_count: u64,
+    pub word_count: u64,
+    pub tag_count: u64,
+    pub link_count: u64,
+    pub attachment_count: u64,
+    pub total_size: u64,
+}
+
+/// Aggregate totals for a vault overview panel in a single walk
+#[tauri::command]
+pub fn vault_stats(root: String, app: AppHandle) -> Result<VaultStats, IgneError> {
+    let root_path = PathBuf::from(&root);
+    ensure_in_scope(&app, &root_path)?;
+
+    let mut stats = VaultStats::default();
+    let mut tags = HashSet::new();
+
+    for file in walk_vault_files(&root_path)? {
+        stats.total_size += fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+
+        if !is_markdown_file(&file.to_string_lossy()) {
+            stats.attachment_count += 1;
+            continue;
+        }
+
+        stats.note_count += 1;
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        // Drawings count their text elements, not their JSON
+        let content = note_kind::readable_text(&file.to_string_lossy(), &content);
+
+        stats.word_count += markdown::count_words(content) as u64;
+        // Same-note `[[#Heading]]` links don't connect notes, so they aren't counted
+        let wikilinks = markdown::extract_wikilinks(content)
+            .into_iter()
+            .filter(|l| !l.target.is_empty())
+            .count();
+        stats.link_count += (wikilinks + links::find_markdown_links(content).len()) as u64;
+        for tag in markdown::extract_tags(content) {
+            tags.insert(tag.to_lowercase());
+        }
+    }
+
+    stats.tag_count = tags.len() as u64;
+    Ok(stats)
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct VaultValidation {
+    pub exists: bool,
+    pub is_dir: bool,
+    pub is_writable: bool,
+    pub has_obsidian_config: bool,
+    pub markdown_file_count: u64,
+}
+
+/// Permission bits don't reflect ACLs, read-only mounts or sandboxing, so actually try
+/// creating and removing a file
+pub fn probe_writable(dir: &Path) -> bool {
+    let probe = dir.join(format!(".igne-write-test-{}", std::process::id()));
+    match fs::OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(file) => {
+            drop(file);
+            fs::remove_file(&probe).is_ok()
+        }
+        Err(_) => false,
+    }
+}
+
+/// Check a folder before opening it as a vault
+#[tauri::command]
+pub fn validate_vault(path: String, app: AppHandle) -> Result<VaultValidation, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+
+    let mut validation = VaultValidation {
+        exists: path_obj.exists(),
+        is_dir: path_obj.is_dir(),
+        ..Default::default()
+    };
+    if !validation.is_dir {
+        return Ok(validation);
+    }
+
+    validation.is_writable = probe_writable(&path_obj);
+    validation.has_obsidian_config = path_obj.join(".obsidian").is_dir();
+    validation.markdown_file_count = walk_vault_files(&path_obj)?
+        .iter()
+        .filter(|f| is_markdown_file(&f.to_string_lossy()))
+        .count() as u64;
+
+    Ok(validation)
+}
+
+/// Stop counting here so probing a huge folder (a home dir, a drive root) stays fast
+const DETECTION_FILE_LIMIT: u64 = 10_000;
+
+#[derive(Serialize, Clone, Default)]
+pub struct VaultDetectionResult {
+    pub is_vault: bool,
+    pub has_obsidian_config: bool,
+    /// Capped at 10,000
+    pub markdown_file_count: u64,
+    pub vault_name: Option<String>,
+}
+
+/// Markdown files under `root`, skipping hidden entries, counting no further than `limit`
+fn count_markdown_files(root: &Path, limit: u64) -> u64 {
+    let mut count = 0;
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if is_excluded_name(&name) {
+                continue;
+            }
+            match entry.file_type() {
+                Ok(t) if t.is_dir() => stack.push(entry.path()),
+                Ok(t) if t.is_file() && is_markdown_file(&name) => {
+                    count += 1;
+                    if count >= limit {
+                        return count;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    count
+}
+
+/// A `name` in `.obsidian/workspace.json` if one was written there, else the folder name
+fn detect_vault_name(root: &Path) -> Option<String> {
+    fs::read_to_string(root.join(".obsidian").join("workspace.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|workspace| workspace.get("name")?.as_str().map(str::to_string))
+        .filter(|name| !name.trim().is_empty())
+        .or_else(|| root.file_name().map(|n| n.to_string_lossy().to_string()))
+}
+
+/// Whether `root` has a markdown note directly in it, not in a subfolder
+fn has_top_level_markdown(root: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(root) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let name = entry.file_name().to_string_lossy().to_string();
+        !is_excluded_name(&name) && is_markdown_file(&name) && entry.file_type().is_ok_and(|t| t.is_file())
+    })
+}
+
+/// Guess whether a folder is a vault: it has an `.obsidian` folder or markdown notes at its
+/// top level. A folder whose notes are all in subfolders (a home dir, a projects dir) isn't
+/// one. The folder must be in scope, so a folder from the picker is checked after
+/// `pick_folder` has granted it.
+#[tauri::command]
+pub fn is_vault_directory(path: String, app: AppHandle) -> Result<VaultDetectionResult, IgneError> {
+    let path_obj = PathBuf::from(&path);
+    ensure_in_scope(&app, &path_obj)?;
+    if !path_obj.is_dir() {
+        return Ok(VaultDetectionResult::default());
+    }
+
+    let has_obsidian_config = path_obj.join(".obsidian").is_dir();
+    let markdown_file_count = count_markdown_files(&path_obj, DETECTION_FILE_LIMIT);
+
+    Ok(VaultDetectionResult {
+        is_vault: has_obsidian_config || has_top_level_markdown(&path_obj),
+        has_obsidian_config,
+        markdown_file_count,
+        vault_name: detect_vault_name(&path_obj),
+    })
+}
+
+#[derive(Serialize, Clone)]
+pub struct NormalizationConflict {
+    pub first: String,
+    pub second: String,
+    /// Shared NFC form of both names
+    pub normalized: String,
+}
+
+/// Files in the same folder whose names differ only by Unicode normalization (NFC vs NFD).
+/// They look identical but are distinct files, and sync tools handle them inconsistently.
+#[tauri::command]
+pub fn find_normalization_conflicts(
+    vault_path: String,
+    app: AppHandle,
+) -> Result<Vec<NormalizationConflict>, IgneError> {
+    let root = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &root)?;
+
+    let mut seen: HashMap<(PathBuf, String), PathBuf> = HashMap::new();
+    let mut conflicts = vec![];
+
+    for file in walk_vault_files(&root)? {
+        let (Some(parent), Some(name)) = (file.parent(), file.file_name()) else {
+            continue;
+        };
+        let normalized = nfc(&name.to_string_lossy());
+
+        match seen.get(&(parent.to_path_buf(), normalized.clone())) {
+            Some(other) => conflicts.push(NormalizationConflict {
+                first: other.to_string_lossy().to_string(),
+                second: file.to_string_lossy().to_string(),
+                normalized,
+            }),
+            None => {
+                seen.insert((parent.to_path_buf(), normalized), file);
+            }
+        }
+    }
+
+    conflicts.sort_by(|a, b| a.first.cmp(&b.first));
+    Ok(conflicts)
+}
@@ -0,0 +1,269 @@
+use regex::{Captures, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::error::IgneError;
+use crate::scope::ensure_in_scope;
+use crate::vault::walk_vault_files;
+use crate::{snapshots, watch_pause};
+use crate::write_lock::with_write_lock;
+use crate::note_kind::{self, NoteKind};
+use crate::{atomic_write, blocking, is_markdown_file};
+
+/// Bigger patterns are almost certainly pasted by mistake and slow to compile
+const MAX_PATTERN_LEN: usize = 1000;
+
+const DEFAULT_REPLACE_TIMEOUT_MS: u64 = 10_000;
+
+/// Find and replace settings from `<app_data_dir>/settings.json`
+#[derive(Deserialize)]
+struct ReplaceSettings {
+    /// How long the search part of `find_and_replace` may take before it gives up
+    #[serde(default = "default_replace_timeout_ms", rename = "findReplaceTimeoutMs")]
+    timeout_ms: u64,
+}
+
+fn default_replace_timeout_ms() -> u64 {
+    DEFAULT_REPLACE_TIMEOUT_MS
+}
+
+fn replace_timeout(app: &AppHandle) -> Duration {
+    let timeout_ms = app
+        .path()
+        .app_data_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join("settings.json")).ok())
+        .and_then(|content| serde_json::from_str::<ReplaceSettings>(&content).ok())
+        .map_or(DEFAULT_REPLACE_TIMEOUT_MS, |settings| settings.timeout_ms);
+    Duration::from_millis(timeout_ms)
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct MatchCounts {
+    pub total_matches: u64,
+    pub file_count: u64,
+}
+
+/// Plain queries match literally; regex queries are used as written
+fn build_matcher(query: &str, regex: bool, case_insensitive: bool) -> Result<Regex, IgneError> {
+    if query.len() > MAX_PATTERN_LEN {
+        return Err(IgneError::invalid_input(format!(
+            "Search query is longer than {} characters",
+            MAX_PATTERN_LEN
+        )));
+    }
+    let pattern = if regex { query.to_string() } else { regex::escape(query) };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(case_insensitive)
+        .build()
+        .map_err(|e| IgneError::invalid_input(format!("Invalid search pattern: {}", e)))
+}
+
+/// Number of matches and of matching notes in a vault, for a live "142 matches in 30
+/// files" badge without sending the matches themselves. Plain queries ignore case.
+#[tauri::command]
+pub fn count_vault_matches(
+    root: String,
+    query: String,
+    regex: Option<bool>,
+    app: AppHandle,
+) -> Result<MatchCounts, IgneError> {
+    let root_path = PathBuf::from(&root);
+    ensure_in_scope(&app, &root_path)?;
+    if query.is_empty() {
+        return Ok(MatchCounts::default());
+    }
+    let regex = regex.unwrap_or(false);
+    let matcher = build_matcher(&query, regex, !regex)?;
+
+    let mut counts = MatchCounts::default();
+    for file in walk_vault_files(&root_path)? {
+        if !is_markdown_file(&file.to_string_lossy()) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let content = note_kind::readable_text(&file.to_string_lossy(), &content);
+        // Most notes don't match; rejecting them first skips the full scan
+        if !matcher.is_match(content) {
+            continue;
+        }
+        // Empty matches (`a*`) would count every position
+        let matches = matcher.find_iter(content).filter(|m| !m.is_empty()).count() as u64;
+        if matches > 0 {
+            counts.total_matches += matches;
+            counts.file_count += 1;
+        }
+    }
+
+    Ok(counts)
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct FindReplaceReport {
+    pub matches_found: u64,
+    /// Notes with matches; in a dry run, the notes that would change
+    pub files_affected: Vec<String>,
+    /// Zero in a dry run
+    pub replacements_made: u64,
+}
+
+/// `content` with every non-empty match replaced, and how many were. In regex mode
+/// `replace` may refer to groups (`$1`, `${name}`); plain replacements are inserted as is.
+fn replace_matches(matcher: &Regex, content: &str, replace: &str, expand: bool) -> (String, u64) {
+    let mut result = String::with_capacity(content.len());
+    let mut last = 0;
+    let mut count = 0;
+    for caps in matcher.captures_iter(content) {
+        let Some(m) = caps.get(0).filter(|m| !m.is_empty()) else {
+            continue;
+        };
+        result.push_str(&content[last..m.start()]);
+        if expand {
+            Captures::expand(&caps, replace, &mut result);
+        } else {
+            result.push_str(replace);
+        }
+        last = m.end();
+        count += 1;
+    }
+    result.push_str(&content[last..]);
+    (result, count)
+}
+
+/// The markdown notes with matches and how many, giving up once `timeout` runs out
+fn search(vault: &Path, matcher: &Regex, timeout: Duration) -> Result<(FindReplaceReport, Vec<PathBuf>), IgneError> {
+    let started = Instant::now();
+    let mut report = FindReplaceReport::default();
+    let mut affected = vec![];
+    for file in walk_vault_files(vault)? {
+        if started.elapsed() > timeout {
+            return Err(IgneError::other(format!(
+                "Find and replace stopped after {} ms without changing anything",
+                timeout.as_millis()
+            )));
+        }
+        if !is_markdown_file(&file.to_string_lossy()) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        // Drawings keep their text in the JSON as well, which a plain rewrite would leave
+        // out of sync, so they're left alone
+        if note_kind::from_content(&file.to_string_lossy(), &content) == NoteKind::Excalidraw {
+            continue;
+        }
+        let matches = matcher.find_iter(&content).filter(|m| !m.is_empty()).count() as u64;
+        if matches > 0 {
+            report.matches_found += matches;
+            report.files_affected.push(file.to_string_lossy().to_string());
+            affected.push(file);
+        }
+    }
+    Ok((report, affected))
+}
+
+/// Replace `find` with `replace` in every note of the vault except Excalidraw drawings.
+/// `find` is literal unless `regex`, and matches case-insensitively unless `case_sensitive`.
+/// The vault is searched first, within the `findReplaceTimeoutMs` setting (10 seconds by
+/// default), and nothing is written when that runs out. With `dry_run` the report only
+/// says what would change.
+#[tauri::command]
+pub async fn find_and_replace(
+    vault_path: String,
+    find: String,
+    replace: String,
+    regex: Option<bool>,
+    case_sensitive: Option<bool>,
+    dry_run: Option<bool>,
+    app: AppHandle,
+) -> Result<FindReplaceReport, IgneError> {
+    let vault = PathBuf::from(&vault_path);
+    ensure_in_scope(&app, &vault)?;
+    if find.is_empty() {
+        return Ok(FindReplaceReport::default());
+    }
+    let regex = regex.unwrap_or(false);
+    let matcher = build_matcher(&find, regex, !case_sensitive.unwrap_or(false))?;
+
+    let timeout = replace_timeout(&app);
+    let (mut report, affected) = blocking({
+        let (vault, matcher) = (vault.clone(), matcher.clone());
+        move || search(&vault, &matcher, timeout)
+    })
+    .await?;
+    if dry_run.unwrap_or(false) {
+        return Ok(report);
+    }
+
+    // One change notice per window for the whole batch
+    let _pause = watch_pause::pause(&app, Some(vec![vault.clone()]));
+    report.files_affected.clear();
+    for path in affected {
+        // Read again under the lock: the note may have been saved since the search
+        let replaced = with_write_lock(&app, &path, || {
+            let Ok(content) = fs::read_to_string(&path) else {
+                return Ok(0);
+            };
+            let (updated, count) = replace_matches(&matcher, &content, &replace, regex);
+            if count > 0 {
+                // The note as it was, so a bad replace can be undone from its history
+                snapshots::record_snapshot_logged(&app, &path, content.as_bytes());
+                atomic_write(&path, updated.as_bytes())?;
+            }
+            Ok(count)
+        })
+        .await?;
+
+        if replaced > 0 {
+            report.replacements_made += replaced;
+            report.files_affected.push(path.to_string_lossy().to_string());
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_matches_like_find_and_replace() {
+        // (find, regex, case_sensitive, content, replace, expected, replacements)
+        let cases = [
+            // Empty matches are never replaced
+            ("a*", true, true, "baaac", "X", "bXc", 1),
+            ("x*", true, true, "abc", "Y", "abc", 0),
+            // Plain mode is literal on both sides
+            ("cost", false, true, "the cost", "$1 each", "the $1 each", 1),
+            ("a.b", false, true, "a.b axb", "Z", "Z axb", 1),
+            ("(x)", false, true, "(x) x", "${1}", "${1} x", 1),
+            // Regex mode expands groups
+            (r"(\w+)@(\w+)", true, true, "me@host", "$2 at ${1}", "host at me", 1),
+            (r"(?P<day>\d+)/(?P<month>\d+)", true, true, "3/4 and 5/6", "${month}-${day}", "4-3 and 6-5", 2),
+            (r"(\w+)", true, true, "a b", "$$1", "$1 $1", 2),
+            // Case sensitivity
+            ("Note", false, false, "note NOTE Note", "Page", "Page Page Page", 3),
+            ("Note", false, true, "note NOTE Note", "Page", "note NOTE Page", 1),
+            ("n.te", true, false, "NOTE", "x", "x", 1),
+            ("é", false, false, "É é", "e", "e e", 2),
+        ];
+        for (find, regex, case_sensitive, content, replace, expected, replacements) in cases {
+            let matcher = build_matcher(find, regex, !case_sensitive).unwrap();
+            let (updated, count) = replace_matches(&matcher, content, replace, regex);
+            assert_eq!((updated.as_str(), count), (expected, replacements), "{} in {}", find, content);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_patterns() {
+        assert_eq!(build_matcher("(", true, false).unwrap_err().code(), "INVALID_INPUT");
+        assert!(build_matcher("(", false, false).is_ok());
+        assert_eq!(build_matcher(&"a".repeat(MAX_PATTERN_LEN + 1), false, false).unwrap_err().code(), "INVALID_INPUT");
+    }
+}
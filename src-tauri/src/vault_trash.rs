@@ -0,0 +1,275 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+use crate::error::IgneError;
+use crate::fs_move::move_path;
+use crate::paths::{vault_absolute, vault_relative};
+use crate::scope::ensure_in_scope;
+use crate::write_lock::with_write_locks;
+
+/// Obsidian-compatible in-vault trash folder
+pub const TRASH_DIR: &str = ".trash";
+
+/// Records where each trashed item came from, since collision renames lose the original name
+const MANIFEST_FILE: &str = ".igne-trash.json";
+
+#[derive(Serialize, Deserialize, Default)]
+struct TrashManifest {
+    #[serde(default)]
+    entries: BTreeMap<String, ManifestEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct ManifestEntry {
+    /// Vault-relative original path
+    original: String,
+    deleted_at: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct VaultTrashEntry {
+    pub name: String,
+    pub path: String,
+    pub original_path: String,
+    pub is_dir: bool,
+    pub size: u64,
+    pub deleted_at: u64,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Whether any component of `path` is the in-vault trash folder
+pub fn is_in_vault_trash(path: &Path) -> bool {
+    path.components().any(|c| c.as_os_str() == TRASH_DIR)
+}
+
+fn load_manifest(trash_root: &Path) -> TrashManifest {
+    fs::read_to_string(trash_root.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_manifest(trash_root: &Path, manifest: &TrashManifest) -> Result<(), IgneError> {
+    let json = serde_json::to_string_pretty(manifest)?;
+    let manifest_path = trash_root.join(MANIFEST_FILE);
+    fs::write(&manifest_path, json).map_err(|e| IgneError::io(e, &manifest_path))
+}
+
+/// First non-existing variant of `path`: "Note.md", "Note 1.md", "Note 2.md", ...
+pub fn unique_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let parent = path.parent().unwrap_or(Path::new(""));
+
+    let mut n = 1;
+    loop {
+        let candidate = parent.join(format!("{} {}{}", stem, n, ext));
+        if !candidate.exists() {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+/// Move `source` to `wanted`, or to the first free variant of it as `unique_path` names
+/// them, returning where it went. The move never replaces anything: a name taken between
+/// the check and the move just moves on to the next one.
+pub fn move_to_free_path(source: &Path, wanted: &Path) -> Result<PathBuf, IgneError> {
+    for _ in 0..100 {
+        let target = unique_path(wanted);
+        match move_path(source, &target, false) {
+            Ok(_) => return Ok(target),
+            Err(e) if e.code() == "ALREADY_EXISTS" => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(IgneError::already_exists(
+        format!("No free name for {}", wanted.display()),
+        wanted,
+    ))
+}
+
+/// The vault a trashed path belongs to: everything before its `.trash` folder
+fn trashed_vault_root(trashed: &Path) -> Option<PathBuf> {
+    let mut vault_root = PathBuf::new();
+    for component in trashed.components() {
+        if component.as_os_str() == TRASH_DIR {
+            return Some(vault_root);
+        }
+        vault_root.push(component.as_os_str());
+    }
+    None
+}
+
+/// Move a file or folder into `<vault>/.trash`, keeping its relative location
+#[tauri::command]
+pub async fn trash_to_vault(path: String, vault_root: String, app: AppHandle) -> Result<String, IgneError> {
+    let source = PathBuf::from(&path);
+    let vault = PathBuf::from(&vault_root);
+    ensure_in_scope(&app, &source)?;
+    crate::check_protected_path(&app, &source, false)?;
+
+    if !source.exists() {
+        return Err(IgneError::not_found(format!("Path does not exist: {}", path), &path));
+    }
+    if is_in_vault_trash(&source) {
+        return Err(IgneError::conflict(format!("Path is already in the trash: {}", path), &path));
+    }
+
+    // Manifest keys and entries are vault-relative paths with forward slashes
+    let rel = vault_relative(&vault, &source)?;
+
+    let trash_root = vault.join(TRASH_DIR);
+    let wanted = vault_absolute(&trash_root, &rel)?;
+    let manifest_path = trash_root.join(MANIFEST_FILE);
+    // Autosave can't bring the note back mid-move, and two trashings can't interleave
+    // their manifest updates
+    let target = with_write_locks(&app, &[&source, &wanted, &manifest_path], || {
+        if let Some(parent) = wanted.parent() {
+            fs::create_dir_all(parent).map_err(|e| IgneError::io(e, parent))?;
+        }
+        let target = move_to_free_path(&source, &wanted)?;
+
+        let mut manifest = load_manifest(&trash_root);
+        manifest.entries.insert(
+            vault_relative(&trash_root, &target)?,
+            ManifestEntry {
+                original: rel,
+                deleted_at: now_secs(),
+            },
+        );
+        save_manifest(&trash_root, &manifest)?;
+        Ok(target)
+    })
+    .await?;
+
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Move a trashed item back to where it was deleted from. Returns the restored path,
+/// which gets a numeric suffix if something now occupies the original location.
+#[tauri::command]
+pub async fn restore_from_vault_trash(trashed_path: String, app: AppHandle) -> Result<String, IgneError> {
+    let trashed = PathBuf::from(&trashed_path);
+    ensure_in_scope(&app, &trashed)?;
+
+    if !trashed.exists() {
+        return Err(IgneError::not_found(
+            format!("Path does not exist: {}", trashed_path),
+            &trashed_path,
+        ));
+    }
+
+    let vault = trashed_vault_root(&trashed)
+        .ok_or_else(|| {
+            IgneError::invalid_path(
+                format!("Path is not in a vault trash folder: {}", trashed_path),
+                &trashed_path,
+            )
+        })?;
+    let trash_root = vault.join(TRASH_DIR);
+    let manifest_path = trash_root.join(MANIFEST_FILE);
+    let key = vault_relative(&trash_root, &trashed)?;
+    let original_rel = load_manifest(&trash_root)
+        .entries
+        .get(&key)
+        .map_or_else(|| key.clone(), |e| e.original.clone());
+    let wanted = vault_absolute(&vault, &original_rel)?;
+    ensure_in_scope(&app, &wanted)?;
+
+    let destination = with_write_locks(&app, &[&trashed, &wanted, &manifest_path], || {
+        if let Some(parent) = wanted.parent() {
+            fs::create_dir_all(parent).map_err(|e| IgneError::io(e, parent))?;
+        }
+        let destination = move_to_free_path(&trashed, &wanted)?;
+
+        let mut manifest = load_manifest(&trash_root);
+        if manifest.entries.remove(&key).is_some() {
+            save_manifest(&trash_root, &manifest)?;
+        }
+        Ok(destination)
+    })
+    .await?;
+
+    Ok(destination.to_string_lossy().to_string())
+}
+
+/// List items in `<vault>/.trash`, newest first
+#[tauri::command]
+pub fn list_vault_trash(vault_root: String, app: AppHandle) -> Result<Vec<VaultTrashEntry>, IgneError> {
+    let vault = PathBuf::from(&vault_root);
+    let trash_root = vault.join(TRASH_DIR);
+    ensure_in_scope(&app, &trash_root)?;
+
+    if !trash_root.exists() {
+        return Ok(vec![]);
+    }
+
+    let manifest = load_manifest(&trash_root);
+    let mut entries = vec![];
+
+    let mut push_entry = |trashed: PathBuf, original_rel: &str, deleted_at: Option<u64>| {
+        let Ok(meta) = fs::metadata(&trashed) else {
+            return;
+        };
+        let modified = meta
+            .modified()
+            .map(|t| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs())
+            .unwrap_or(0);
+
+        entries.push(VaultTrashEntry {
+            name: trashed
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: trashed.to_string_lossy().to_string(),
+            original_path: vault.join(original_rel).to_string_lossy().to_string(),
+            is_dir: meta.is_dir(),
+            size: meta.len(),
+            deleted_at: deleted_at.unwrap_or(modified),
+        });
+    };
+
+    for (trashed_rel, entry) in &manifest.entries {
+        push_entry(trash_root.join(trashed_rel), &entry.original, Some(entry.deleted_at));
+    }
+
+    // Items trashed by Obsidian (flat, no manifest entry)
+    let dir = fs::read_dir(&trash_root).map_err(|e| IgneError::io(e, &trash_root))?;
+    for entry in dir.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name == MANIFEST_FILE {
+            continue;
+        }
+        let tracked = manifest
+            .entries
+            .keys()
+            .any(|k| k == &name || k.starts_with(&format!("{}/", name)));
+        if !tracked {
+            push_entry(entry.path(), &name, None);
+        }
+    }
+
+    entries.sort_by_key(|e| std::cmp::Reverse(e.deleted_at));
+    Ok(entries)
+}
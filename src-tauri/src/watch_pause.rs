@@ -0,0 +1,182 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::error::IgneError;
+use crate::scope::{ensure_in_scope, resolve};
+use crate::{emit_file_change, emit_fs_change};
+
+/// A frontend pause that's never resumed, e.g. because the page reloaded mid-import,
+/// ends on its own after this long
+const FRONTEND_PAUSE_LIMIT: Duration = Duration::from_secs(60);
+
+/// The event a held change is delivered as on resume
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum Held {
+    /// `fs-change` for the watched folder
+    Tree(String),
+    /// `fs-file-change` for the watched file
+    File(String),
+}
+
+#[derive(Default)]
+struct Pauses {
+    next_id: u64,
+    /// Active pauses and the folders they cover; `None` covers everything
+    active: HashMap<u64, Option<Vec<PathBuf>>>,
+    /// The pause set by `pause_watching`
+    frontend: Option<u64>,
+    /// Changes seen while paused, by window and event
+    held: HashMap<(String, Held), BTreeSet<PathBuf>>,
+}
+
+impl Pauses {
+    fn covers(&self, path: &Path) -> bool {
+        let path = resolve(path);
+        self.active.values().any(|folders| match folders {
+            None => true,
+            Some(folders) => folders.iter().any(|folder| path.starts_with(folder)),
+        })
+    }
+}
+
+/// Watcher pauses for bulk writes. While a path is paused, external changes to it are
+/// held rather than emitted and arrive in one event per window on resume; the app's own
+/// writes are dropped by the self-write filter before they get here.
+pub struct WatchPause(Mutex<Pauses>);
+
+impl WatchPause {
+    pub fn new() -> Self {
+        Self(Mutex::new(Pauses::default()))
+    }
+
+    fn begin(&self, folders: Option<Vec<PathBuf>>) -> u64 {
+        let Ok(mut pauses) = self.0.lock() else {
+            return 0;
+        };
+        pauses.next_id += 1;
+        let id = pauses.next_id;
+        let folders = folders.map(|folders| folders.iter().map(|folder| resolve(folder)).collect());
+        pauses.active.insert(id, folders);
+        id
+    }
+
+    /// End pause `id` and deliver the held changes no other pause still covers
+    fn end(&self, app: &AppHandle, id: u64) {
+        let mut ready = vec![];
+        if let Ok(mut pauses) = self.0.lock() {
+            pauses.active.remove(&id);
+            if pauses.frontend == Some(id) {
+                pauses.frontend = None;
+            }
+            for (key, paths) in std::mem::take(&mut pauses.held) {
+                let (still_paused, free): (BTreeSet<PathBuf>, BTreeSet<PathBuf>) =
+                    paths.into_iter().partition(|path| pauses.covers(path));
+                if !still_paused.is_empty() {
+                    pauses.held.insert(key.clone(), still_paused);
+                }
+                if !free.is_empty() {
+                    ready.push((key, free.into_iter().collect::<Vec<_>>()));
+                }
+            }
+        }
+
+        for ((label, held), paths) in ready {
+            match held {
+                Held::Tree(root) => emit_fs_change(app, &label, &root, &paths),
+                Held::File(path) => emit_file_change(app, &label, &path),
+            }
+        }
+    }
+
+    /// Keep a change to `paths` for later if any of them is paused. Returns whether it
+    /// was held, in which case the watcher shouldn't emit it now.
+    pub fn hold(&self, label: &str, held: Held, paths: &[PathBuf]) -> bool {
+        let Ok(mut pauses) = self.0.lock() else {
+            return false;
+        };
+        if !paths.iter().any(|path| pauses.covers(path)) {
+            return false;
+        }
+        pauses
+            .held
+            .entry((label.to_string(), held))
+            .or_default()
+            .extend(paths.iter().cloned());
+        true
+    }
+}
+
+impl Default for WatchPause {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Ends its pause when dropped, so a bulk operation that fails halfway still resumes
+pub struct PauseGuard {
+    app: AppHandle,
+    id: u64,
+}
+
+impl Drop for PauseGuard {
+    fn drop(&mut self) {
+        self.app.state::<WatchPause>().end(&self.app, self.id);
+    }
+}
+
+/// Pause watcher events under `folders` (every watched path when `None`) until the
+/// returned guard is dropped
+pub fn pause(app: &AppHandle, folders: Option<Vec<PathBuf>>) -> PauseGuard {
+    PauseGuard {
+        app: app.clone(),
+        id: app.state::<WatchPause>().begin(folders),
+    }
+}
+
+/// Hold watcher events under `paths`, or everywhere when omitted, until `resume_watching`,
+/// for bulk writes the frontend makes itself. Replaces an earlier pause, and ends by itself
+/// after a minute so a reloaded page can't leave the watcher paused.
+#[tauri::command]
+pub fn pause_watching(paths: Option<Vec<String>>, app: AppHandle) -> Result<(), IgneError> {
+    let folders = match paths {
+        Some(paths) => {
+            let folders: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+            for folder in &folders {
+                ensure_in_scope(&app, folder)?;
+            }
+            Some(folders)
+        }
+        None => None,
+    };
+
+    let state = app.state::<WatchPause>();
+    let id = state.begin(folders);
+    let previous = state.0.lock()?.frontend.replace(id);
+    if let Some(previous) = previous {
+        state.end(&app, previous);
+    }
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FRONTEND_PAUSE_LIMIT).await;
+        let state = app.state::<WatchPause>();
+        let expired = state.0.lock().is_ok_and(|pauses| pauses.frontend == Some(id));
+        if expired {
+            state.end(&app, id);
+        }
+    });
+    Ok(())
+}
+
+/// End the pause from `pause_watching` and emit what changed meanwhile
+#[tauri::command]
+pub fn resume_watching(app: AppHandle) -> Result<(), IgneError> {
+    let state = app.state::<WatchPause>();
+    let frontend = state.0.lock()?.frontend;
+    if let Some(id) = frontend {
+        state.end(&app, id);
+    }
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use serde::Serialize;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+use crate::error::IgneError;
+use crate::note_kind::NoteKind;
+use crate::note_titles::{cached_titles, NoteTitleCache};
+use crate::scope::ensure_in_scope;
+use crate::WatcherState;
+
+const DEFAULT_LIMIT: usize = 20;
+
+#[derive(Serialize, Clone)]
+pub struct WikilinkCandidate {
+    /// What `[[...]]` is completed with: the file name, without the extension for notes
+    pub display: String,
+    pub path: String,
+    pub relative_path: String,
+    pub kind: NoteKind,
+    /// The frontmatter alias that matched, when it matched better than the name
+    pub alias: Option<String>,
+    /// 0 to 1, 1 being an exact match
+    pub score: f32,
+}
+
+/// Whether `chars[i]` begins a word: the first character, one after a separator, or an
+/// uppercase letter following a lowercase one
+fn is_word_start(chars: &[char], i: usize) -> bool {
+    i == 0 || !chars[i - 1].is_alphanumeric() || (chars[i - 1].is_lowercase() && chars[i].is_uppercase())
+}
+
+fn max_score(a: Option<f32>, b: Option<f32>) -> Option<f32> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        _ => a.or(b),
+    }
+}
+
+/// How well `query` matches `candidate` as a subsequence, ignoring case and spaces in the
+/// query. Characters at word starts and runs of consecutive characters count extra, and
+/// a shorter candidate beats a longer one with the same matches. `None` when it doesn't
+/// match at all.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<f32> {
+    if candidate.to_lowercase() == query.to_lowercase() {
+        return Some(1.0);
+    }
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some(0.0);
+    }
+
+    let chars: Vec<char> = candidate.chars().collect();
+    let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    // best[i]: top score of the query so far, its last character matched at `i`
+    let mut best: Vec<Option<f32>> = vec![None; chars.len()];
+    for (qi, &q) in query.iter().enumerate() {
+        let mut next = vec![None; chars.len()];
+        // Top score ending at least two characters back, so not adjacent
+        let mut apart: Option<f32> = None;
+        for i in 0..chars.len() {
+            if i >= 2 {
+                apart = max_score(apart, best[i - 2]);
+            }
+            if lower[i] != q {
+                continue;
+            }
+            let before = if qi == 0 {
+                Some(0.0)
+            } else {
+                let adjacent = i.checked_sub(1).and_then(|j| best[j]).map(|score| score + 2.0);
+                max_score(adjacent, apart)
+            };
+            let here = if is_word_start(&chars, i) { 4.0 } else { 1.0 };
+            next[i] = before.map(|score| score + here);
+        }
+        best = next;
+    }
+    let points = best.into_iter().flatten().reduce(f32::max)?;
+
+    let quality = points / (query.len() as f32 * 6.0);
+    let coverage = query.len() as f32 / chars.len() as f32;
+    // Kept below 1 so only an exact match reaches it
+    Some((0.8 * quality + 0.2 * coverage).min(0.99))
+}
+
+/// `path` relative to `root`, `/`-separated. Note paths come from walking `root`, so a
+/// prefix strip is enough.
+fn relative_to(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Notes and canvases of the vault ranked by how well their name (or an alias) fuzzily matches what
+/// was typed after `[[`, best first. Text after `#` or `|` is ignored; a `/` in `partial`
+/// matches against the note's folder path as well.
+#[tauri::command]
+pub fn autocomplete_wikilink(
+    vault_path: String,
+    partial: String,
+    limit: Option<usize>,
+    app: AppHandle,
+    cache: State<'_, NoteTitleCache>,
+    watcher_state: State<'_, WatcherState>,
+) -> Result<Vec<WikilinkCandidate>, IgneError> {
+    let root = Path::new(&vault_path);
+    ensure_in_scope(&app, root)?;
+
+    let partial = partial.trim_start_matches("[[");
+    let query = partial.split(['#', '|']).next().unwrap_or_default().trim();
+    let by_path = query.contains('/');
+
+    let mut candidates: Vec<WikilinkCandidate> = cached_titles(&vault_path, &cache, &watcher_state)?
+        .into_iter()
+        .filter_map(|note| {
+            let path = Path::new(&note.path);
+            // Canvases are linked with their extension, `[[Board.canvas]]`
+            let is_canvas = note.kind == NoteKind::Canvas;
+            let display = if is_canvas { path.file_name()? } else { path.file_stem()? };
+            let display = display.to_string_lossy().to_string();
+            let relative = relative_to(root, path);
+            let link = match relative.rsplit_once('.') {
+                Some((link, _)) if !is_canvas => link,
+                _ => relative.as_str(),
+            };
+            let name_score = fuzzy_score(query, if by_path { link } else { &display });
+            let (alias, alias_score) = note
+                .aliases
+                .iter()
+                .filter_map(|alias| Some((alias, fuzzy_score(query, alias)?)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map_or((None, None), |(alias, score)| (Some(alias.clone()), Some(score)));
+
+            let (alias, score) = match (name_score, alias_score) {
+                (Some(name), Some(alias_score)) if alias_score > name => (alias, alias_score),
+                (Some(name), _) => (None, name),
+                (None, Some(alias_score)) => (alias, alias_score),
+                (None, None) => return None,
+            };
+            Some(WikilinkCandidate {
+                display,
+                path: note.path,
+                relative_path: relative,
+                kind: note.kind,
+                alias,
+                score,
+            })
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.display.len().cmp(&b.display.len()))
+            .then_with(|| a.display.to_lowercase().cmp(&b.display.to_lowercase()))
+    });
+    candidates.truncate(limit.unwrap_or(DEFAULT_LIMIT));
+    Ok(candidates)
+}
@@ -0,0 +1,317 @@
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewWindow};
+
+use crate::error::IgneError;
+use crate::{atomic_write, focused_window_label};
+
+/// Geometry of secondary windows, keyed by the note or vault they show, in
+/// `<app_data>/window_geometry.json`. The main window is left to the window-state plugin.
+const GEOMETRY_FILE: &str = "window_geometry.json";
+
+/// How long after a move or resize the geometry is saved, so dragging writes once
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Position and size in physical pixels, as the OS reports them
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct WindowGeometry {
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+    pub maximized: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct WindowState {
+    pub label: String,
+    #[serde(flatten)]
+    pub geometry: WindowGeometry,
+    pub minimized: bool,
+    pub focused: bool,
+}
+
+/// Changes for `set_window_state`; omitted fields are left as they are
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WindowStateUpdate {
+    width: Option<u32>,
+    height: Option<u32>,
+    x: Option<i32>,
+    y: Option<i32>,
+    maximized: Option<bool>,
+    minimized: Option<bool>,
+}
+
+/// Which saved geometry each open secondary window uses, by window label
+pub struct WindowGeometryKeys {
+    keys: Mutex<HashMap<String, String>>,
+    /// Windows with a save scheduled after a move or resize
+    scheduled: Mutex<HashSet<String>>,
+}
+
+impl WindowGeometryKeys {
+    pub fn new() -> Self {
+        Self {
+            keys: Mutex::new(HashMap::new()),
+            scheduled: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn remove_window(&self, label: &str) {
+        if let Ok(mut keys) = self.keys.lock() {
+            keys.remove(label);
+        }
+    }
+}
+
+impl Default for WindowGeometryKeys {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn geometry_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(GEOMETRY_FILE))
+}
+
+fn load_all(app: &AppHandle) -> HashMap<String, WindowGeometry> {
+    geometry_path(app)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn geometry_of(window: &WebviewWindow) -> tauri::Result<WindowGeometry> {
+    let size = window.inner_size()?;
+    let position = window.outer_position()?;
+    Ok(WindowGeometry {
+        width: size.width,
+        height: size.height,
+        x: position.x,
+        y: position.y,
+        maximized: window.is_maximized()?,
+    })
+}
+
+/// A monitor's work area as (x, y, width, height)
+type Area = (i32, i32, u32, u32);
+
+fn overlap(geometry: &WindowGeometry, (x, y, width, height): Area) -> i64 {
+    let left = geometry.x.max(x) as i64;
+    let right = (geometry.x as i64 + geometry.width as i64).min(x as i64 + width as i64);
+    let top = geometry.y.max(y) as i64;
+    let bottom = (geometry.y as i64 + geometry.height as i64).min(y as i64 + height as i64);
+    (right - left).max(0) * (bottom - top).max(0)
+}
+
+/// Fit saved geometry onto the work area it overlaps most, or the first one when it's on
+/// none of them (its monitor was unplugged): shrunk to fit, then moved fully inside
+fn fit_to_areas(geometry: WindowGeometry, areas: &[Area]) -> WindowGeometry {
+    let Some(&(x, y, width, height)) = areas
+        .iter()
+        .filter(|area| overlap(&geometry, **area) > 0)
+        .max_by_key(|area| overlap(&geometry, **area))
+        .or(areas.first())
+    else {
+        return geometry;
+    };
+
+    let fitted_width = geometry.width.min(width);
+    let fitted_height = geometry.height.min(height);
+    let max_x = x as i64 + (width - fitted_width) as i64;
+    let max_y = y as i64 + (height - fitted_height) as i64;
+    WindowGeometry {
+        width: fitted_width,
+        height: fitted_height,
+        x: (geometry.x as i64).clamp(x as i64, max_x) as i32,
+        y: (geometry.y as i64).clamp(y as i64, max_y) as i32,
+        ..geometry
+    }
+}
+
+/// Apply saved geometry to a window built for `key`, kept on a connected monitor, and
+/// remember the key so the window's geometry is saved under it as it moves and closes
+pub fn restore(app: &AppHandle, window: &WebviewWindow, key: &str) {
+    if let Ok(mut keys) = app.state::<WindowGeometryKeys>().keys.lock() {
+        keys.insert(window.label().to_string(), key.to_string());
+    }
+    let Some(saved) = load_all(app).get(key).copied() else {
+        return;
+    };
+    let areas: Vec<Area> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| {
+            let area = monitor.work_area();
+            (area.position.x, area.position.y, area.size.width, area.size.height)
+        })
+        .collect();
+    let geometry = fit_to_areas(saved, &areas);
+    let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+    let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+    if geometry.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Save a secondary window's geometry shortly after it moves or resizes. Later events
+/// within the delay are covered by the same save, which reads the geometry when it runs.
+pub fn schedule_save(app: &AppHandle, label: &str) {
+    let state = app.state::<WindowGeometryKeys>();
+    if !state.keys.lock().is_ok_and(|keys| keys.contains_key(label)) {
+        return;
+    }
+    if !state.scheduled.lock().is_ok_and(|mut scheduled| scheduled.insert(label.to_string())) {
+        return;
+    }
+
+    let app = app.clone();
+    let label = label.to_string();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DELAY).await;
+        if let Ok(mut scheduled) = app.state::<WindowGeometryKeys>().scheduled.lock() {
+            scheduled.remove(&label);
+        }
+        remember(&app, &label);
+    });
+}
+
+/// Save a secondary window's geometry, on close and after moves and resizes. A maximized
+/// or minimized window keeps the size and position it had before, so restoring and then
+/// un-maximizing behaves.
+pub fn remember(app: &AppHandle, label: &str) {
+    let key = app
+        .state::<WindowGeometryKeys>()
+        .keys
+        .lock()
+        .ok()
+        .and_then(|keys| keys.get(label).cloned());
+    let Some(window) = app.get_webview_window(label) else {
+        return;
+    };
+    let (Some(key), Some(path), Ok(current)) = (key, geometry_path(app), geometry_of(&window)) else {
+        return;
+    };
+
+    let mut all = load_all(app);
+    let geometry = match all.get(&key) {
+        Some(saved) if current.maximized || window.is_minimized().unwrap_or(false) => WindowGeometry {
+            maximized: current.maximized,
+            ..*saved
+        },
+        _ => current,
+    };
+    all.insert(key, geometry);
+
+    let result = serde_json::to_string_pretty(&all)
+        .map_err(IgneError::from)
+        .and_then(|json| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| IgneError::io(e, dir))?;
+            }
+            atomic_write(&path, json.as_bytes())
+        });
+    if let Err(e) = result {
+        warn!("Failed to save window geometry: {}", e);
+    }
+}
+
+/// Label, geometry and state of the focused window
+#[tauri::command]
+pub fn get_window_state(app: AppHandle) -> Result<WindowState, IgneError> {
+    let label = focused_window_label(&app);
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| IgneError::not_found(format!("Window not found: {}", label), &label))?;
+    let failed = |e: tauri::Error| IgneError::other(format!("Failed to read window state: {}", e));
+    Ok(WindowState {
+        geometry: geometry_of(&window).map_err(failed)?,
+        minimized: window.is_minimized().map_err(failed)?,
+        focused: window.is_focused().map_err(failed)?,
+        label,
+    })
+}
+
+/// Restore a window's size, position and maximized/minimized state, e.g. from an earlier
+/// `get_window_state`. Size and position are in physical pixels.
+#[tauri::command]
+pub fn set_window_state(label: String, state: WindowStateUpdate, app: AppHandle) -> Result<(), IgneError> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| IgneError::not_found(format!("Window not found: {}", label), &label))?;
+    let failed = |e: tauri::Error| IgneError::other(format!("Failed to set window state: {}", e));
+
+    // Geometry only sticks on a normal window, so leave maximized/minimized first
+    if state.maximized == Some(false) || state.width.is_some() || state.x.is_some() {
+        window.unmaximize().map_err(failed)?;
+    }
+    if state.minimized == Some(false) {
+        window.unminimize().map_err(failed)?;
+    }
+
+    if let (Some(width), Some(height)) = (state.width, state.height) {
+        window.set_size(PhysicalSize::new(width, height)).map_err(failed)?;
+    }
+    if let (Some(x), Some(y)) = (state.x, state.y) {
+        window.set_position(PhysicalPosition::new(x, y)).map_err(failed)?;
+    }
+
+    if state.maximized == Some(true) {
+        window.maximize().map_err(failed)?;
+    }
+    if state.minimized == Some(true) {
+        window.minimize().map_err(failed)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geometry(x: i32, y: i32, width: u32, height: u32) -> WindowGeometry {
+        WindowGeometry {
+            width,
+            height,
+            x,
+            y,
+            maximized: false,
+        }
+    }
+
+    fn rect(geometry: WindowGeometry) -> Area {
+        (geometry.x, geometry.y, geometry.width, geometry.height)
+    }
+
+    #[test]
+    fn geometry_on_screen_is_kept() {
+        let saved = geometry(100, 100, 800, 600);
+        assert_eq!(rect(fit_to_areas(saved, &[(0, 0, 1920, 1040)])), (100, 100, 800, 600));
+    }
+
+    #[test]
+    fn geometry_from_an_unplugged_monitor_moves_to_a_connected_one() {
+        let saved = geometry(2500, 200, 800, 600);
+        assert_eq!(rect(fit_to_areas(saved, &[(0, 0, 1920, 1040)])), (1120, 200, 800, 600));
+    }
+
+    #[test]
+    fn geometry_larger_than_the_work_area_shrinks() {
+        let saved = geometry(-50, -20, 2560, 1440);
+        assert_eq!(rect(fit_to_areas(saved, &[(0, 25, 1920, 1055)])), (0, 25, 1920, 1055));
+    }
+
+    #[test]
+    fn geometry_goes_to_the_monitor_it_mostly_covers() {
+        let areas = [(0, 0, 1920, 1080), (1920, 0, 1920, 1080)];
+        let saved = geometry(1800, 100, 800, 600);
+        assert_eq!(rect(fit_to_areas(saved, &areas)), (1920, 100, 800, 600));
+    }
+}
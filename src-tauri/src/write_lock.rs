@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Manager};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+use crate::error::IgneError;
+use crate::scope::resolve;
+
+/// How long after a save the watcher treats changes to that file as our own
+const SELF_WRITE_WINDOW: Duration = Duration::from_millis(1500);
+
+/// Size and modification time of a file, `None` when it doesn't exist
+type Fingerprint = Option<(u64, Option<SystemTime>)>;
+
+fn fingerprint(path: &Path) -> Fingerprint {
+    fs::metadata(path).ok().map(|metadata| (metadata.len(), metadata.modified().ok()))
+}
+
+enum SelfWrite {
+    /// About to be or being written: every change counts as ours
+    Writing,
+    /// Written, leaving the file like this. A change that leaves it any other way came
+    /// from outside, even within the window.
+    Wrote(Fingerprint),
+}
+
+/// Serializes writes to the same file and remembers which files the app itself just
+/// wrote, so the watcher doesn't bounce a save back to the editor as an external change.
+/// Locks are async so a queued write waits without blocking a runtime thread.
+pub struct WriteLocks {
+    locks: Mutex<HashMap<PathBuf, Arc<AsyncMutex<()>>>>,
+    recent_writes: Mutex<HashMap<PathBuf, (Instant, SelfWrite)>>,
+}
+
+impl WriteLocks {
+    pub fn new() -> Self {
+        Self {
+            locks: Mutex::new(HashMap::new()),
+            recent_writes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Lock for each distinct file, in path order so two multi-file writes can't deadlock
+    fn locks_for(&self, paths: &[&Path]) -> Result<Vec<Arc<AsyncMutex<()>>>, IgneError> {
+        let mut keys: Vec<PathBuf> = paths.iter().map(|p| resolve(p)).collect();
+        keys.sort();
+        keys.dedup();
+
+        let mut locks = self.locks.lock()?;
+        // Drop locks nobody is holding so the map doesn't grow with every file ever saved
+        locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+        Ok(keys
+            .into_iter()
+            .map(|key| locks.entry(key).or_default().clone())
+            .collect())
+    }
+
+    fn mark(&self, path: &Path, write: SelfWrite) {
+        if let Ok(mut recent) = self.recent_writes.lock() {
+            let now = Instant::now();
+            recent.retain(|_, (at, _)| now.duration_since(*at) < SELF_WRITE_WINDOW);
+            recent.insert(resolve(path), (now, write));
+        }
+    }
+
+    /// `path` is about to be written by the app
+    fn mark_written(&self, path: &Path) {
+        self.mark(path, SelfWrite::Writing);
+    }
+
+    /// The app finished writing `path`; remember what it left on disk
+    fn mark_wrote(&self, path: &Path) {
+        self.mark(path, SelfWrite::Wrote(fingerprint(path)));
+    }
+
+    async fn run<T>(
+        &self,
+        paths: &[&Path],
+        write: impl FnOnce() -> Result<T, IgneError> + Send,
+    ) -> Result<T, IgneError> {
+        let mut guards: Vec<OwnedMutexGuard<()>> = Vec::with_capacity(paths.len());
+        for lock in self.locks_for(paths)? {
+            guards.push(lock.lock_owned().await);
+        }
+
+        for path in paths {
+            self.mark_written(path);
+        }
+        // `write` borrows the caller's locals, so it can't move to the blocking pool;
+        // block_in_place hands this worker's other tasks to another thread instead
+        let result = tokio::task::block_in_place(write);
+        for path in paths {
+            self.mark_wrote(path);
+        }
+        result
+    }
+
+    /// Whether the current state of `path` is the app's own doing: it's being written, or
+    /// was written within the self-write window and hasn't changed since
+    pub fn is_self_write(&self, path: &Path) -> bool {
+        let Ok(recent) = self.recent_writes.lock() else {
+            return false;
+        };
+        recent.get(&resolve(path)).is_some_and(|(at, write)| {
+            at.elapsed() < SELF_WRITE_WINDOW
+                && match write {
+                    SelfWrite::Writing => true,
+                    SelfWrite::Wrote(written) => *written == fingerprint(path),
+                }
+        })
+    }
+}
+
+impl Default for WriteLocks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run `write` while holding the locks for every file in `paths`, marking them as
+/// self-writes on both sides so watcher events delivered mid-write are suppressed too.
+/// Afterwards only changes that leave the files as `write` did count as ours.
+/// Writes to the same file queue up in order; writes to different files run in parallel.
+pub async fn with_write_locks<T>(
+    app: &AppHandle,
+    paths: &[&Path],
+    write: impl FnOnce() -> Result<T, IgneError> + Send,
+) -> Result<T, IgneError> {
+    app.state::<WriteLocks>().run(paths, write).await
+}
+
+pub async fn with_write_lock<T>(
+    app: &AppHandle,
+    path: &Path,
+    write: impl FnOnce() -> Result<T, IgneError> + Send,
+) -> Result<T, IgneError> {
+    with_write_locks(app, &[path], write).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::atomic_write;
+
+    #[test]
+    fn interleaved_writes_to_one_file_keep_the_last() {
+        let dir = std::env::temp_dir().join(format!("igne-write-lock-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("note.md");
+
+        let runtime = tokio::runtime::Builder::new_multi_thread().worker_threads(4).build().unwrap();
+        let locks = Arc::new(WriteLocks::new());
+        let last = Arc::new(AtomicUsize::new(usize::MAX));
+        let tasks: Vec<_> = (0..100)
+            .map(|i| {
+                let (locks, last, path) = (locks.clone(), last.clone(), path.clone());
+                runtime.spawn(async move {
+                    locks
+                        .run(&[&path], || {
+                            // Long enough that a write outside the lock would interleave
+                            atomic_write(&path, format!("write {}\n", i).repeat(1000).as_bytes())?;
+                            last.store(i, Ordering::SeqCst);
+                            Ok(())
+                        })
+                        .await
+                })
+            })
+            .collect();
+        for task in tasks {
+            runtime.block_on(task).unwrap().unwrap();
+        }
+
+        let last = last.load(Ordering::SeqCst);
+        assert_eq!(fs::read_to_string(&path).unwrap(), format!("write {}\n", last).repeat(1000));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1, "temp files left behind");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,9 +1,9 @@
 import { useState, useEffect, useCallback, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { open } from '@tauri-apps/plugin-dialog';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
 import { openPath } from '@tauri-apps/plugin-opener';
 import { Group as PanelGroup, Panel, Separator as PanelResizeHandle, useDefaultLayout, usePanelRef, type PanelImperativeHandle } from 'react-resizable-panels';
-import { registerStandaloneHandler } from './main';
+import { registerStandaloneHandler, windowInit } from './main';
 import {
   FolderOpen,
 } from 'lucide-react';
@@ -64,6 +64,8 @@ import { detectVaultRoot, isVault, getParentDir } from './utils/vaultDetection';
 import { CommandRegistry } from './commands/registry';
 import { setWorkspaceManager } from './tools/workspace';
 import { logger } from './utils/logger';
+import { ensureDefaultVault } from './utils/defaultVault';
+import { errorMessage } from './utils/errorMessage';
 
 // Dynamic app styles based on theme - using CSS variables from obsidian.css
 const styles = {
@@ -474,15 +476,14 @@ function App() {
   // Handle "Open File" action (menu or keyboard shortcut)
   const handleOpenFile = useCallback(async () => {
     try {
-      const selected = await open({
-        multiple: false,
+      const selected = await invoke<string | null>('pick_file', {
         title: 'Open Markdown File',
         filters: [
           { name: 'Markdown', extensions: ['md', 'markdown'] },
         ],
       });
 
-      if (selected && typeof selected === 'string') {
+      if (selected) {
         handleOpenStandaloneFile(selected);
       }
     } catch (e) {
@@ -511,10 +512,27 @@ function App() {
         console.error('[App] Store init failed:', e);
       }
 
-      // Try to reopen the last workspace
+      // Try to reopen the last workspace, unless this window was opened for a vault or note
       const lastOpened = vaultsStore.getLastOpenedVault();
 
-      if (lastOpened && await fileExists(lastOpened)) {
+      if (windowInit?.vaultPath) {
+        try {
+          await handleOpenWorkspace(windowInit.vaultPath, {
+            initialFilePath: windowInit.file ?? undefined,
+          });
+        } catch (e) {
+          console.error('[App] Failed to open window workspace:', e);
+        }
+      } else if (windowInit?.file) {
+        await handleOpenStandaloneFile(windowInit.file);
+      } else if (vaultsStore.isFirstRun()) {
+        // First launch: start in the default vault, created with a welcome note
+        try {
+          await handleOpenWorkspace(await ensureDefaultVault());
+        } catch (e) {
+          console.error('[App] Failed to open default vault:', e);
+        }
+      } else if (lastOpened && await fileExists(lastOpened)) {
         try {
           console.log('[App] Reopening last workspace:', lastOpened);
           await handleOpenWorkspace(lastOpened);
@@ -535,6 +553,22 @@ function App() {
   // eslint-disable-next-line react-hooks/exhaustive-deps
   }, []); // Run once on mount
 
+  // The backend warms the last vault's indexes at launch; log how long each phase took
+  // so slow starts can be reported. Phases finished before this window listened are
+  // caught up by asking once up front.
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    const logTimings = () => {
+      invoke('get_startup_timings')
+        .then(timings => logger.info('App', 'Startup timings', timings))
+        .catch(e => console.error('[App] Failed to get startup timings:', e));
+    };
+
+    getCurrentWebviewWindow().listen('index-ready', logTimings).then(fn => { unlisten = fn; });
+    logTimings();
+    return () => unlisten?.();
+  }, []);
+
   // Cleanup stores on unmount to prevent resource leaks
   useEffect(() => {
     return () => {
@@ -741,13 +775,9 @@ function App() {
 
   const handleOpenVault = async () => {
     try {
-      const selected = await open({
-        directory: true,
-        multiple: false,
-        title: 'Open Folder',
-      });
+      const selected = await invoke<string | null>('pick_folder', { title: 'Open Folder' });
 
-      if (selected && typeof selected === 'string') {
+      if (selected) {
         await handleOpenWorkspace(selected);
       }
     } catch (e) {
@@ -777,7 +807,7 @@ function App() {
         logger.info('handleFileSelect', 'Opening binary file with system app', { path });
         openPath(path)
           .then(() => logger.info('handleFileSelect', 'openPath succeeded', { path }))
-          .catch((err) => logger.error('handleFileSelect', 'openPath failed', { path, error: String(err) }));
+          .catch((err) => logger.error('handleFileSelect', 'openPath failed', { path, error: errorMessage(err) }));
         return;
       }
 
@@ -875,6 +905,37 @@ function App() {
     };
   }, []);
 
+  // Report unsaved notes so closing the window or quitting asks before losing them
+  useEffect(() => {
+    const dirtyCount = openTabs.filter(t => t.isDirty && !t.isVirtual).length;
+    invoke('set_window_dirty', { windowLabel: getCurrentWebviewWindow().label, dirtyCount })
+      .catch(e => console.error('[App] Failed to report unsaved notes:', e));
+  }, [openTabs]);
+
+  // Answer to the unsaved-changes prompt. On "Save" write every dirty note; the close
+  // goes ahead once the report above drops to zero. "Don't Save" is handled natively.
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    getCurrentWebviewWindow()
+      .listen<{ action: string; quitting: boolean }>('window-close-choice', async (event) => {
+        if (event.payload.action !== 'save') return;
+
+        const dirtyTabs = tabStateRef.current.tabs.filter(t => t.isDirty && !t.isVirtual);
+        for (const tab of dirtyTabs) {
+          try {
+            await invoke('write_file', { path: tab.path, content: tab.content });
+            markSaved(tab.path);
+          } catch (e) {
+            console.error('[App] Failed to save before closing:', tab.path, e);
+          }
+        }
+      })
+      .then(fn => { unlisten = fn; });
+
+    return () => unlisten?.();
+  }, [markSaved, tabStateRef]);
+
   const handleContentChange = useCallback(async (path: string, content: string) => {
     // Check if this is a virtual file that needs materializing
     const currentState = tabStateRef.current;
@@ -1546,7 +1607,9 @@ function App() {
   // Uses refs to avoid stale closure issues
   useEffect(() => {
     const setupMenuListeners = async () => {
-      const { listen } = await import('@tauri-apps/api/event');
+      // Menu events go to the focused window only, so listen on this window
+      const currentWindow = getCurrentWebviewWindow();
+      const listen = (event: string, handler: () => void) => currentWindow.listen(event, handler);
 
       const unlisteners = await Promise.all([
         listen('menu-new-file', () => {
@@ -13,6 +13,7 @@ import type {
 } from './types';
 import type { CommandSource } from '../tools/types';
 import { AuditLog } from './audit';
+import { errorMessage } from '../utils/errorMessage';
 
 // =============================================================================
 // Command Registry Class
@@ -84,7 +85,7 @@ class CommandRegistryClass {
       result = await command.callback(...args);
     } catch (e) {
       success = false;
-      error = e instanceof Error ? e.message : String(e);
+      error = errorMessage(e);
       throw e; // Re-throw to caller
     } finally {
       const event: CommandExecutedEvent = {
@@ -1,5 +1,4 @@
 import { useState } from 'react';
-import { open } from '@tauri-apps/plugin-dialog';
 import { invoke } from '@tauri-apps/api/core';
 import { X, FolderOpen } from 'lucide-react';
 import { FocusTrapWrapper } from './FocusTrapWrapper';
@@ -29,13 +28,9 @@ export function CreateVaultDialog({ onClose, onVaultCreated }: CreateVaultDialog
 
   const handleSelectLocation = async () => {
     try {
-      const selected = await open({
-        directory: true,
-        multiple: false,
-        title: 'Select Parent Folder',
-      });
+      const selected = await invoke<string | null>('pick_folder', { title: 'Select Parent Folder' });
 
-      if (selected && typeof selected === 'string') {
+      if (selected) {
         setVaultLocation(selected);
         setError(null);
       }
@@ -1,10 +1,11 @@
 import { useState, useEffect } from 'react';
-import { open } from '@tauri-apps/plugin-dialog';
+import { invoke } from '@tauri-apps/api/core';
 import { FolderOpen, Plus, FileText, Folder } from 'lucide-react';
 import { vaultsStore } from '../stores/VaultsStore';
 import type { VaultEntry } from '../types';
 import { CreateVaultDialog } from './CreateVaultDialog';
 import { ConfirmDialog } from './ConfirmDialog';
+import { detectVault } from '../utils/vaultDetection';
 
 interface HomeScreenProps {
   onOpen: (path: string) => void;
@@ -14,6 +15,7 @@ export function HomeScreen({ onOpen }: HomeScreenProps) {
   const [recentItems, setRecentItems] = useState<VaultEntry[]>([]);
   const [isCreateDialogOpen, setIsCreateDialogOpen] = useState(false);
   const [removeConfirm, setRemoveConfirm] = useState<string | null>(null);
+  const [notVaultConfirm, setNotVaultConfirm] = useState<string | null>(null);
 
   useEffect(() => {
     setRecentItems(vaultsStore.getVaults());
@@ -25,15 +27,23 @@ export function HomeScreen({ onOpen }: HomeScreenProps) {
   };
 
   const handleOpenFolder = async () => {
-    const selected = await open({
-      directory: true,
-      multiple: false,
-      title: 'Open Folder',
-    });
+    // pick_folder grants the folder, so it can be checked before opening
+    const selected = await invoke<string | null>('pick_folder', { title: 'Open Folder' });
+    if (!selected) return;
 
-    if (selected && typeof selected === 'string') {
-      onOpen(selected);
+    const detection = await detectVault(selected).catch(() => null);
+    if (detection && !detection.is_vault) {
+      setNotVaultConfirm(selected);
+      return;
     }
+    onOpen(selected);
+  };
+
+  const confirmOpenNotVault = () => {
+    if (notVaultConfirm) {
+      onOpen(notVaultConfirm);
+    }
+    setNotVaultConfirm(null);
   };
 
   const handleRemoveItem = (path: string, e: React.MouseEvent) => {
@@ -241,6 +251,16 @@ export function HomeScreen({ onOpen }: HomeScreenProps) {
           onCancel={() => setRemoveConfirm(null)}
         />
       )}
+
+      {notVaultConfirm && (
+        <ConfirmDialog
+          title="Open Folder"
+          message="This folder has no .obsidian folder and no notes at its top level. Open it anyway?"
+          confirmLabel="Open"
+          onConfirm={confirmOpenNotVault}
+          onCancel={() => setNotVaultConfirm(null)}
+        />
+      )}
     </div>
   );
 }
@@ -7,7 +7,8 @@
 
 import { useEffect, useRef, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { listen, UnlistenFn } from '@tauri-apps/api/event';
+import { UnlistenFn } from '@tauri-apps/api/event';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
 import { FileEntry } from '../types';
 import { searchStore } from '../stores/searchStore';
 import { filterHiddenFiles } from '../utils/fileFilters';
@@ -65,9 +66,10 @@ export function useFileWatcher({
         console.log('[useFileWatcher] Native watcher started for:', vaultPath);
         setIsWatching(true);
 
-        // Listen for file system change events from Rust
+        // Listen for file system change events from Rust, which only go to the window
+        // that started the watch
         // Debounce: coalesce rapid changes (e.g. 10 saves in 1s) into a single reindex
-        const unlisten = await listen('fs-change', () => {
+        const unlisten = await getCurrentWebviewWindow().listen('fs-change', () => {
           if (debounceTimerRef.current) {
             clearTimeout(debounceTimerRef.current);
           }
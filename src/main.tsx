@@ -4,7 +4,7 @@ import './utils/tauriMock';
 
 import { StrictMode } from 'react';
 import { createRoot } from 'react-dom/client';
-import { listen } from '@tauri-apps/api/event';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
 import App from './App';
 import { ErrorBoundary } from './components/ErrorBoundary';
 import './index.css';
@@ -37,9 +37,18 @@ const pendingEvents: string[] = [];
 let reactReady = false;
 let standaloneHandler: ((path: string) => void) | null = null;
 
+/** What a window opened after the first should show, set by the backend before load */
+export interface WindowInit {
+  vaultPath: string | null;
+  file: string | null;
+}
+
+export const windowInit: WindowInit | null = (window as any).__IGNE_INIT__ ?? null;
+
 // Start listening for standalone file events IMMEDIATELY (before React renders)
-// This prevents the race condition where the event arrives before the listener is set up
-listen<string>('open-standalone-file', (event) => {
+// This prevents the race condition where the event arrives before the listener is set up.
+// The backend sends them to the focused window only.
+getCurrentWebviewWindow().listen<string>('open-standalone-file', (event) => {
   console.log('[main.tsx] Received open-standalone-file event:', event.payload);
   if (reactReady && standaloneHandler) {
     standaloneHandler(event.payload);
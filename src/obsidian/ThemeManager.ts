@@ -5,6 +5,7 @@
 import { EventRef } from './eventRef';
 import { Events } from './events';
 import type { App } from './types';
+import { errorMessage } from '../utils/errorMessage';
 
 export class ThemeManager extends Events {
   private themeEl: HTMLStyleElement;
@@ -35,7 +36,7 @@ export class ThemeManager extends Events {
       this.trigger('css-change');
     } catch (error) {
       console.error(`Failed to load theme ${themeName}:`, error);
-      throw new Error(`Failed to load theme: ${error}`);
+      throw new Error(`Failed to load theme: ${errorMessage(error)}`);
     }
   }
 
@@ -84,7 +85,7 @@ export class ThemeManager extends Events {
       this.trigger('css-change');
     } catch (error) {
       console.error(`Failed to load snippet ${snippetName}:`, error);
-      throw new Error(`Failed to load snippet: ${error}`);
+      throw new Error(`Failed to load snippet: ${errorMessage(error)}`);
     }
   }
 
@@ -128,7 +128,10 @@ export class Vault extends Events {
 
     for (const part of parts) {
       if (current instanceof TFolder) {
-        const child = current.children.find((c) => c.name === part);
+        // Names from macOS disks may be NFD while the requested path is NFC
+        const child = current.children.find(
+          (c) => c.name === part || c.name.normalize('NFC') === part.normalize('NFC')
+        );
         if (child) {
           current = child;
         } else {
@@ -1,18 +1,8 @@
-import { invoke } from '@tauri-apps/api/core';
 import type { GlobalSettings } from '../types';
-import { readJsonSafe, writeJsonSafe, fileExists } from '../utils/safeJson';
+import { readAppConfigSafe, writeAppConfigSafe } from '../utils/safeJson';
 import { logger } from '../utils/logger';
 
-// Helper function to get app data directory
-async function getAppDataDir(): Promise<string> {
-  try {
-    const dir = await invoke<string>('get_app_data_dir');
-    return dir;
-  } catch {
-    // Fallback to a default location
-    return '';
-  }
-}
+const SETTINGS_FILE = 'settings.json';
 
 const DEFAULT_GLOBAL_SETTINGS: GlobalSettings = {
   version: 1,
@@ -35,34 +25,25 @@ const DEFAULT_GLOBAL_SETTINGS: GlobalSettings = {
 
 class GlobalSettingsStore {
   private settings: GlobalSettings = { ...DEFAULT_GLOBAL_SETTINGS };
-  private settingsPath: string = '';
 
   async init(): Promise<void> {
     logger.debug('GlobalSettingsStore', 'init() called');
     try {
-      const appData = await getAppDataDir();
-      this.settingsPath = `${appData}/settings.json`;
-      logger.debug('GlobalSettingsStore', 'settingsPath:', this.settingsPath);
-
       // Load existing settings
-      if (this.settingsPath && await fileExists(this.settingsPath)) {
-        const loaded = await readJsonSafe<Partial<GlobalSettings>>(this.settingsPath);
+      const loaded = await readAppConfigSafe<Partial<GlobalSettings>>(SETTINGS_FILE);
 
-        if (loaded) {
-          // Merge with defaults to handle version upgrades and missing fields
-          this.settings = {
-            ...DEFAULT_GLOBAL_SETTINGS,
-            ...loaded,
-            version: loaded.version || 1,
-          };
+      if (loaded) {
+        // Merge with defaults to handle version upgrades and missing fields
+        this.settings = {
+          ...DEFAULT_GLOBAL_SETTINGS,
+          ...loaded,
+          version: loaded.version || 1,
+        };
 
-          logger.info('GlobalSettingsStore', 'Loaded settings', {
-            openLastVault: this.settings.openLastVault,
-            language: this.settings.language,
-          });
-        } else {
-          logger.debug('GlobalSettingsStore', 'No existing settings found, using defaults');
-        }
+        logger.info('GlobalSettingsStore', 'Loaded settings', {
+          openLastVault: this.settings.openLastVault,
+          language: this.settings.language,
+        });
       } else {
         logger.debug('GlobalSettingsStore', 'No existing settings found, using defaults');
       }
@@ -73,12 +54,7 @@ class GlobalSettingsStore {
 
   async save(): Promise<void> {
     try {
-      if (!this.settingsPath) {
-        const appData = await getAppDataDir();
-        this.settingsPath = `${appData}/settings.json`;
-      }
-
-      await writeJsonSafe(this.settingsPath, this.settings, {
+      await writeAppConfigSafe(SETTINGS_FILE, this.settings, {
         preserveUnknown: true,
         merge: true,
       });
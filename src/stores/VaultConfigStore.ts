@@ -1,7 +1,7 @@
 import { invoke } from '@tauri-apps/api/core';
 import type { VaultSettings, AppearanceSettings, WorkspaceState } from '../types';
 import { DEFAULT_VAULT_SETTINGS, DEFAULT_APPEARANCE_SETTINGS } from '../types';
-import { readJsonSafe, writeJsonSafe, fileExists } from '../utils/safeJson';
+import { readJsonSafe, writeJsonSafe, readAppConfigSafe, writeAppConfigSafe, fileExists } from '../utils/safeJson';
 
 export interface VaultConfigInitOptions {
   /** When true, store config in app data dir instead of .obsidian/ */
@@ -38,7 +38,7 @@ class VaultConfigStore {
     return this.rootPath;
   }
 
-  /** The config directory path */
+  /** The config directory path; in app-data mode, the config name prefix under the app data dir */
   get currentConfigDir(): string {
     return this.configDir;
   }
@@ -50,9 +50,8 @@ class VaultConfigStore {
 
     if (options?.useAppDataDir) {
       // Store config in app data directory, keyed by folder path hash
-      const appDataDir = await invoke<string>('get_app_data_dir');
       const hash = simpleHash(rootPath);
-      this.configDir = `${appDataDir}/workspaces/${hash}`;
+      this.configDir = `workspaces/${hash}`;
     } else {
       // Vault mode: use .obsidian/ directory (don't auto-create it)
       this.configDir = `${rootPath}/.obsidian`;
@@ -65,18 +64,30 @@ class VaultConfigStore {
   }
 
   private async ensureConfigDir(): Promise<void> {
+    // The backend creates app-data config folders as it writes them
+    if (!this.isVaultMode) return;
     if (!(await fileExists(this.configDir))) {
       await invoke('create_directory', { path: this.configDir });
       console.log('[VaultConfigStore] Created config directory:', this.configDir);
     }
   }
 
+  private async readConfig<T>(file: string): Promise<T | null> {
+    const path = `${this.configDir}/${file}`;
+    return this.isVaultMode ? readJsonSafe<T>(path) : readAppConfigSafe<T>(path);
+  }
+
+  private async writeConfig<T>(file: string, data: T): Promise<void> {
+    await this.ensureConfigDir();
+    const path = `${this.configDir}/${file}`;
+    const options = { preserveUnknown: true, merge: true };
+    await (this.isVaultMode ? writeJsonSafe(path, data, options) : writeAppConfigSafe(path, data, options));
+  }
+
   // === SETTINGS ===
 
   private async loadSettings(): Promise<void> {
-    const path = `${this.configDir}/app.json`;
-
-    const loaded = await readJsonSafe<Partial<VaultSettings>>(path);
+    const loaded = await this.readConfig<Partial<VaultSettings>>('app.json');
 
     if (loaded) {
       // Merge with defaults, preserving any unknown keys
@@ -92,12 +103,7 @@ class VaultConfigStore {
   }
 
   async saveSettings(): Promise<void> {
-    await this.ensureConfigDir();
-    const path = `${this.configDir}/app.json`;
-    await writeJsonSafe(path, this.settings, {
-      preserveUnknown: true,
-      merge: true,
-    });
+    await this.writeConfig('app.json', this.settings);
     console.log('[VaultConfigStore] Saved vault settings to app.json');
   }
 
@@ -117,9 +123,7 @@ class VaultConfigStore {
   // === APPEARANCE ===
 
   private async loadAppearance(): Promise<void> {
-    const path = `${this.configDir}/appearance.json`;
-
-    const loaded = await readJsonSafe<Partial<AppearanceSettings>>(path);
+    const loaded = await this.readConfig<Partial<AppearanceSettings>>('appearance.json');
 
     if (loaded) {
       // Merge with defaults, preserving any unknown keys
@@ -135,12 +139,7 @@ class VaultConfigStore {
   }
 
   async saveAppearance(): Promise<void> {
-    await this.ensureConfigDir();
-    const path = `${this.configDir}/appearance.json`;
-    await writeJsonSafe(path, this.appearance, {
-      preserveUnknown: true,
-      merge: true,
-    });
+    await this.writeConfig('appearance.json', this.appearance);
     console.log('[VaultConfigStore] Saved appearance settings to appearance.json');
   }
 
@@ -160,9 +159,7 @@ class VaultConfigStore {
   // === WORKSPACE ===
 
   private async loadWorkspace(): Promise<void> {
-    const path = `${this.configDir}/workspace.json`;
-
-    this.workspace = await readJsonSafe<WorkspaceState>(path);
+    this.workspace = await this.readConfig<WorkspaceState>('workspace.json');
 
     if (this.workspace) {
       console.log('[VaultConfigStore] Loaded workspace state from workspace.json');
@@ -173,12 +170,7 @@ class VaultConfigStore {
 
   async saveWorkspace(workspace: WorkspaceState): Promise<void> {
     this.workspace = workspace;
-    await this.ensureConfigDir();
-    const path = `${this.configDir}/workspace.json`;
-    await writeJsonSafe(path, workspace, {
-      preserveUnknown: true,
-      merge: true,
-    });
+    await this.writeConfig('workspace.json', workspace);
     console.log('[VaultConfigStore] Saved workspace state to workspace.json');
   }
 
@@ -189,18 +181,11 @@ class VaultConfigStore {
   // === HOTKEYS ===
 
   async loadHotkeys(): Promise<Record<string, any[]>> {
-    const path = `${this.configDir}/hotkeys.json`;
-
-    return (await readJsonSafe<Record<string, any[]>>(path)) || {};
+    return (await this.readConfig<Record<string, any[]>>('hotkeys.json')) || {};
   }
 
   async saveHotkeys(hotkeys: Record<string, any[]>): Promise<void> {
-    await this.ensureConfigDir();
-    const path = `${this.configDir}/hotkeys.json`;
-    await writeJsonSafe(path, hotkeys, {
-      preserveUnknown: true,
-      merge: true,
-    });
+    await this.writeConfig('hotkeys.json', hotkeys);
     console.log('[VaultConfigStore] Saved hotkeys to hotkeys.json');
   }
 }
@@ -23,22 +23,7 @@ import type { VaultsRegistry, VaultEntry, RecentItemType } from '../types';
 
 const CURRENT_VERSION = 2;
 
-async function fileExists(path: string): Promise<boolean> {
-  try {
-    const meta = await invoke<{ exists: boolean }>('stat_path', { path });
-    return meta.exists;
-  } catch {
-    return false;
-  }
-}
-
-async function getAppDataDir(): Promise<string> {
-  try {
-    return await invoke<string>('get_app_data_dir');
-  } catch {
-    return '';
-  }
-}
+const VAULTS_FILE = 'vaults.json';
 
 class VaultsStore {
   private registry: VaultsRegistry = {
@@ -47,16 +32,15 @@ class VaultsStore {
     lastOpened: null,
   };
 
-  private vaultsPath: string = '';
+  /** No registry existed yet: the app is running for the first time */
+  private firstRun = false;
 
   async init(): Promise<void> {
     try {
-      const appData = await getAppDataDir();
-      this.vaultsPath = `${appData}/vaults.json`;
+      const content = await invoke<string | null>('read_app_config', { name: VAULTS_FILE });
 
-      if (await fileExists(this.vaultsPath)) {
+      if (content !== null) {
         try {
-          const content = await invoke<string>('read_file', { path: this.vaultsPath });
           const loaded = JSON.parse(content) as Record<string, unknown>;
           const version = (loaded.version as number) || 1;
           const vaults = (loaded.vaults as VaultEntry[]) || [];
@@ -87,6 +71,7 @@ class VaultsStore {
         }
       } else {
         // First load — run migrations for any legacy data sources
+        this.firstRun = true;
         this.migrate(0);
         console.log('[VaultsStore] No existing registry, starting fresh');
       }
@@ -146,11 +131,6 @@ class VaultsStore {
 
   async save(): Promise<void> {
     try {
-      if (!this.vaultsPath) {
-        const appData = await getAppDataDir();
-        this.vaultsPath = `${appData}/vaults.json`;
-      }
-
       // Serialize with current field names (no lastOpenedVault)
       const serialized = {
         version: this.registry.version,
@@ -158,8 +138,8 @@ class VaultsStore {
         lastOpened: this.registry.lastOpened,
       };
 
-      await invoke('write_file', {
-        path: this.vaultsPath,
+      await invoke('write_app_config', {
+        name: VAULTS_FILE,
         content: JSON.stringify(serialized, null, 2),
       });
     } catch (e) {
@@ -167,6 +147,11 @@ class VaultsStore {
     }
   }
 
+  // Whether init() found no registry, i.e. this is the app's first launch
+  isFirstRun(): boolean {
+    return this.firstRun;
+  }
+
   // Get all known items, sorted by last opened (most recent first)
   getVaults(): VaultEntry[] {
     return [...this.registry.vaults].sort((a, b) => b.lastOpened - a.lastOpened);
@@ -2,26 +2,7 @@ import { getCurrentWindow, LogicalPosition, LogicalSize } from '@tauri-apps/api/
 import { invoke } from '@tauri-apps/api/core';
 import type { WindowState } from '../types';
 
-// Helper function to check if a file exists
-async function fileExists(path: string): Promise<boolean> {
-  try {
-    const meta = await invoke<{ exists: boolean }>('stat_path', { path });
-    return meta.exists;
-  } catch {
-    return false;
-  }
-}
-
-// Helper function to get app data directory
-async function getAppDataDir(): Promise<string> {
-  try {
-    const dir = await invoke<string>('get_app_data_dir');
-    return dir;
-  } catch {
-    // Fallback to a default location
-    return '';
-  }
-}
+const STATE_FILE = 'window-state.json';
 
 class WindowStateStore {
   private state: WindowState = {
@@ -36,14 +17,11 @@ class WindowStateStore {
 
   private saveDebounceTimer: number | null = null;
   private appWindow: ReturnType<typeof getCurrentWindow> | null = null;
-  private statePath: string = '';
   private stateCheckInterval: number | null = null;
 
   async init(): Promise<void> {
     try {
       this.appWindow = getCurrentWindow();
-      const appData = await getAppDataDir();
-      this.statePath = `${appData}/window-state.json`;
 
       await this.load();
       await this.restore();
@@ -55,8 +33,8 @@ class WindowStateStore {
 
   private async load(): Promise<void> {
     try {
-      if (this.statePath && await fileExists(this.statePath)) {
-        const content = await invoke<string>('read_file', { path: this.statePath });
+      const content = await invoke<string | null>('read_app_config', { name: STATE_FILE });
+      if (content !== null) {
         const loaded = JSON.parse(content) as Partial<WindowState>;
 
         // Merge with defaults
@@ -80,13 +58,8 @@ class WindowStateStore {
 
   private async save(): Promise<void> {
     try {
-      if (!this.statePath) {
-        const appData = await getAppDataDir();
-        this.statePath = `${appData}/window-state.json`;
-      }
-
-      await invoke('write_file', {
-        path: this.statePath,
+      await invoke('write_app_config', {
+        name: STATE_FILE,
         content: JSON.stringify(this.state, null, 2),
       });
       console.log('[WindowStateStore] Saved window state');
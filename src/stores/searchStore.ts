@@ -1,7 +1,7 @@
 import MiniSearch from 'minisearch';
 import { invoke } from '@tauri-apps/api/core';
 import type { FileEntry, SearchDocument } from '../types';
-import { toVaultPath, toOsPath, getVaultBasename } from '../utils/vaultPaths';
+import { toVaultPath, toOsPath, getVaultBasename, noteNameKey } from '../utils/vaultPaths';
 
 class SearchStore {
   private miniSearch: MiniSearch;
@@ -14,6 +14,8 @@ class SearchStore {
       idField: 'id',
       fields: ['name', 'content'],
       storeFields: ['id', 'path', 'name'],
+      // NFC so NFD names from macOS match NFC queries
+      processTerm: (term) => term.normalize('NFC').toLowerCase(),
     });
   }
 
@@ -238,21 +240,21 @@ class SearchStore {
 
     // Filter by file name
     if (filters.file) {
-      const fileLower = filters.file.toLowerCase();
+      const fileLower = noteNameKey(filters.file);
       filtered = filtered.filter((result) => {
         const doc = this.files.get(result.id);
         if (!doc) return false;
-        return doc.name.toLowerCase().includes(fileLower);
+        return noteNameKey(doc.name).includes(fileLower);
       });
     }
 
     // Filter by path
     if (filters.path) {
-      const pathLower = filters.path.toLowerCase();
+      const pathLower = noteNameKey(filters.path);
       filtered = filtered.filter((result) => {
         const doc = this.files.get(result.id);
         if (!doc) return false;
-        return doc.path.toLowerCase().includes(pathLower);
+        return noteNameKey(doc.path).includes(pathLower);
       });
     }
 
@@ -325,7 +327,7 @@ class SearchStore {
 
     const targetName = match[1];
     const exactMatch = Array.from(this.files.values()).find(
-      (doc) => doc.name.toLowerCase() === targetName.toLowerCase()
+      (doc) => noteNameKey(doc.name) === noteNameKey(targetName)
     );
 
     return exactMatch || null;
@@ -337,7 +339,7 @@ class SearchStore {
 
   noteExists(name: string): boolean {
     return Array.from(this.files.values()).some(
-      (doc) => doc.name.toLowerCase() === name.toLowerCase()
+      (doc) => noteNameKey(doc.name) === noteNameKey(name)
     );
   }
 
@@ -347,7 +349,7 @@ class SearchStore {
    */
   getFilePathByName(name: string): string | null {
     const doc = Array.from(this.files.values()).find(
-      (doc) => doc.name.toLowerCase() === name.toLowerCase()
+      (doc) => noteNameKey(doc.name) === noteNameKey(name)
     );
     // Return OS path for file reading
     return doc ? this.toOsPathForIo(doc.path) : null;
@@ -359,7 +361,7 @@ class SearchStore {
    */
   getVaultPathByName(name: string): string | null {
     const doc = Array.from(this.files.values()).find(
-      (doc) => doc.name.toLowerCase() === name.toLowerCase()
+      (doc) => noteNameKey(doc.name) === noteNameKey(name)
     );
     return doc?.path || null;
   }
@@ -5,6 +5,8 @@
  * Each tool has a defined input type and returns a Result type.
  */
 
+import { errorMessage } from '../utils/errorMessage';
+
 // =============================================================================
 // Result Type
 // =============================================================================
@@ -27,7 +29,7 @@ export function tryResult<T>(fn: () => T): Result<T> {
   try {
     return ok(fn());
   } catch (e) {
-    return err(e instanceof Error ? e : new Error(String(e)));
+    return err(e instanceof Error ? e : new Error(errorMessage(e)));
   }
 }
 
@@ -35,7 +37,7 @@ export async function tryResultAsync<T>(fn: () => Promise<T>): Promise<Result<T>
   try {
     return ok(await fn());
   } catch (e) {
-    return err(e instanceof Error ? e : new Error(String(e)));
+    return err(e instanceof Error ? e : new Error(errorMessage(e)));
   }
 }
 
@@ -13,6 +13,7 @@ import type {
   SetActiveFileInput,
 } from './types';
 import { ok, err } from './types';
+import { errorMessage } from '../utils/errorMessage';
 
 // =============================================================================
 // Workspace State Interface
@@ -67,7 +68,7 @@ export const openFile: Tool<OpenFileInput, void> = {
       await workspaceManager.openFile(input.path, input.newTab);
       return ok(undefined);
     } catch (e) {
-      return err(e instanceof Error ? e : new Error(String(e)));
+      return err(e instanceof Error ? e : new Error(errorMessage(e)));
     }
   },
 };
@@ -88,7 +89,7 @@ export const closeFile: Tool<CloseFileInput, void> = {
       await workspaceManager.closeFile(input.path);
       return ok(undefined);
     } catch (e) {
-      return err(e instanceof Error ? e : new Error(String(e)));
+      return err(e instanceof Error ? e : new Error(errorMessage(e)));
     }
   },
 };
@@ -109,7 +110,7 @@ export const setActiveFile: Tool<SetActiveFileInput, void> = {
       await workspaceManager.setActiveFile(input.path);
       return ok(undefined);
     } catch (e) {
-      return err(e instanceof Error ? e : new Error(String(e)));
+      return err(e instanceof Error ? e : new Error(errorMessage(e)));
     }
   },
 };
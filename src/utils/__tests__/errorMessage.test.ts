@@ -0,0 +1,26 @@
+import { describe, it, expect } from 'vitest';
+import { errorMessage, isIgneError } from '../errorMessage';
+
+describe('errorMessage', () => {
+  it('reads the message of a backend error', () => {
+    const error = { code: 'NOT_FOUND', message: 'No such file', path: '/vault/a.md' };
+    expect(errorMessage(error)).toBe('No such file');
+  });
+
+  it('reads the message of an Error', () => {
+    expect(errorMessage(new Error('boom'))).toBe('boom');
+  });
+
+  it('passes strings through', () => {
+    expect(errorMessage('plain')).toBe('plain');
+  });
+});
+
+describe('isIgneError', () => {
+  it('requires a string code and message', () => {
+    expect(isIgneError({ code: 'IO', message: 'x', path: null })).toBe(true);
+    expect(isIgneError({ message: 'x' })).toBe(false);
+    expect(isIgneError(null)).toBe(false);
+    expect(isIgneError('IO')).toBe(false);
+  });
+});
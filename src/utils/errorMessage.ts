@@ -0,0 +1,34 @@
+/**
+ * Error as the backend serializes it: `{ code, message, path }`, plus extra fields
+ * for some codes (`size`/`limit`, `offset`/`line`).
+ */
+export interface IgneError {
+  code: string;
+  message: string;
+  path: string | null;
+}
+
+/**
+ * Whether a rejected `invoke` carried a structured backend error.
+ */
+export function isIgneError(error: unknown): error is IgneError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as { code?: unknown }).code === 'string' &&
+    typeof (error as { message?: unknown }).message === 'string'
+  );
+}
+
+/**
+ * Readable text for anything thrown: a backend error's `message`, an `Error`'s
+ * `message`, or the value itself as a string.
+ *
+ * Interpolating a backend error directly shows "[object Object]".
+ */
+export function errorMessage(error: unknown): string {
+  if (error instanceof Error || isIgneError(error)) {
+    return error.message;
+  }
+  return String(error);
+}
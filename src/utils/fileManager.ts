@@ -9,6 +9,7 @@
 
 import { invoke } from '@tauri-apps/api/core';
 import { searchStore } from '../stores/searchStore';
+import { errorMessage } from './errorMessage';
 
 interface RenameOptions {
   oldPath: string; // OS-absolute path
@@ -51,7 +52,7 @@ export async function renameFileWithLinkUpdates(options: RenameOptions) {
       newPath,
     });
   } catch (e) {
-    throw new Error(`Failed to rename file: ${e}`);
+    throw new Error(`Failed to rename file: ${errorMessage(e)}`);
   }
 
   // 3. Update index (file already renamed, must continue even if this fails)
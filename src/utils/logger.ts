@@ -34,6 +34,7 @@ class Logger {
   private logBuffer: LogEntry[] = [];
   private maxBufferSize = 1000;
   private logFilePath: string | null = null;
+  private sessionId: string | null = null;
   private writeQueue: string[] = [];
   private isWriting = false;
 
@@ -56,18 +57,15 @@ class Logger {
   private async initFileLogging() {
     try {
       const appDataDir = await invoke<string>('get_app_data_dir');
-      const logsDir = `${appDataDir}/logs`;
 
-      // Create logs directory
-      await invoke('create_directory', { path: logsDir }).catch(() => {});
-
-      // Create session log file with timestamp
+      // Create session log file with timestamp; the backend creates the logs directory
       const sessionId = new Date().toISOString().replace(/[:.]/g, '-').slice(0, 19);
-      this.logFilePath = `${logsDir}/session-${sessionId}.log`;
 
       // Write header
       const header = `=== Igne Log Session Started ===\nTime: ${new Date().toISOString()}\nDev Mode: ${this.isDev}\n${'='.repeat(40)}\n\n`;
-      await invoke('write_file', { path: this.logFilePath, content: header });
+      await invoke('append_session_log', { sessionId, content: header });
+      this.sessionId = sessionId;
+      this.logFilePath = `${appDataDir}/logs/session-${sessionId}.log`;
 
       console.log('[Logger] File logging initialized:', this.logFilePath);
     } catch (e) {
@@ -76,7 +74,7 @@ class Logger {
   }
 
   private async writeToFile(line: string) {
-    if (!this.logFilePath) return;
+    if (!this.sessionId) return;
 
     this.writeQueue.push(line);
 
@@ -88,14 +86,7 @@ class Logger {
         const lines = this.writeQueue.splice(0, this.writeQueue.length);
         const content = lines.join('\n') + '\n';
 
-        // Read existing content and append
-        try {
-          const existing = await invoke<string>('read_file', { path: this.logFilePath });
-          await invoke('write_file', { path: this.logFilePath, content: existing + content });
-        } catch {
-          // File might not exist yet, just write
-          await invoke('write_file', { path: this.logFilePath, content });
-        }
+        await invoke('append_session_log', { sessionId: this.sessionId, content });
       }
     } catch (e) {
       console.error('[Logger] Failed to write to log file:', e);
@@ -62,36 +62,10 @@ export async function writeJsonSafe<T>(
       existingData = await readJsonSafe<Record<string, unknown>>(path);
     }
 
-    let finalData: Record<string, unknown>;
-
-    if (merge && existingData) {
-      // Merge: new data takes precedence, but we keep unknown keys from existing
-      finalData = {
-        ...existingData, // Keep existing keys (including unknown ones)
-        ...(data as Record<string, unknown>), // Override with new data
-      };
-    } else if (preserveUnknown && existingData) {
-      // Only preserve unknown keys (keys not in new data)
-      const newKeys = new Set(Object.keys(data as Record<string, unknown>));
-      finalData = {
-        ...(data as Record<string, unknown>), // Start with new data
-      };
-
-      // Add back any unknown keys from existing data
-      Object.entries(existingData).forEach(([key, value]) => {
-        if (!newKeys.has(key)) {
-          finalData[key] = value;
-        }
-      });
-    } else {
-      // No merging or preservation, just write the new data
-      finalData = data as Record<string, unknown>;
-    }
-
     // Write the merged/preserved data
     await invoke('write_file', {
       path,
-      content: JSON.stringify(finalData, null, 2),
+      content: JSON.stringify(mergeJson(existingData, data, { preserveUnknown, merge }), null, 2),
     });
   } catch (error) {
     console.error(`[safeJson] Failed to write ${path}:`, error);
@@ -99,6 +73,93 @@ export async function writeJsonSafe<T>(
   }
 }
 
+/**
+ * Combine new data with what's on disk, per the writeJsonSafe options
+ */
+function mergeJson<T>(
+  existingData: Record<string, unknown> | null,
+  data: T,
+  { preserveUnknown, merge }: { preserveUnknown: boolean; merge: boolean }
+): Record<string, unknown> {
+  if (merge && existingData) {
+    // Merge: new data takes precedence, but we keep unknown keys from existing
+    return {
+      ...existingData, // Keep existing keys (including unknown ones)
+      ...(data as Record<string, unknown>), // Override with new data
+    };
+  }
+
+  if (preserveUnknown && existingData) {
+    // Only preserve unknown keys (keys not in new data)
+    const newKeys = new Set(Object.keys(data as Record<string, unknown>));
+    const finalData: Record<string, unknown> = {
+      ...(data as Record<string, unknown>), // Start with new data
+    };
+
+    // Add back any unknown keys from existing data
+    Object.entries(existingData).forEach(([key, value]) => {
+      if (!newKeys.has(key)) {
+        finalData[key] = value;
+      }
+    });
+    return finalData;
+  }
+
+  // No merging or preservation, just write the new data
+  return data as Record<string, unknown>;
+}
+
+/**
+ * Read one of the app's own config files from the app data dir, which the
+ * generic file commands can't reach
+ *
+ * @param name - Config file name, e.g. `settings.json` or `workspaces/<hash>/app.json`
+ * @returns Parsed object or null if file doesn't exist/is invalid
+ */
+export async function readAppConfigSafe<T>(name: string): Promise<T | null> {
+  try {
+    const content = await invoke<string | null>('read_app_config', { name });
+    return content === null ? null : (JSON.parse(content) as T);
+  } catch (error) {
+    console.warn(`[safeJson] Failed to read app config ${name}:`, error);
+    return null;
+  }
+}
+
+/**
+ * writeJsonSafe for the app's own config files
+ *
+ * @param name - Config file name, e.g. `settings.json` or `workspaces/<hash>/app.json`
+ * @param data - Data to write
+ * @param options - Options for merging behavior
+ */
+export async function writeAppConfigSafe<T>(
+  name: string,
+  data: T,
+  options: {
+    /** Preserve unknown keys from existing file (default: true) */
+    preserveUnknown?: boolean;
+    /** Merge with existing data instead of overwriting (default: true) */
+    merge?: boolean;
+  } = {}
+): Promise<void> {
+  const { preserveUnknown = true, merge = true } = options;
+
+  try {
+    const existingData = preserveUnknown || merge
+      ? await readAppConfigSafe<Record<string, unknown>>(name)
+      : null;
+
+    await invoke('write_app_config', {
+      name,
+      content: JSON.stringify(mergeJson(existingData, data, { preserveUnknown, merge }), null, 2),
+    });
+  } catch (error) {
+    console.error(`[safeJson] Failed to write app config ${name}:`, error);
+    throw error;
+  }
+}
+
 /**
  * Check if a file exists
  *
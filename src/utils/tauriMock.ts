@@ -109,6 +109,8 @@ if (!isTauri && typeof window !== 'undefined') {
           return null;
 
         case 'plugin:dialog|open':
+        case 'pick_folder':
+        case 'pick_file':
           // File dialog - return mock vault path
           return MOCK_VAULT_PATH;
 
@@ -116,6 +118,20 @@ if (!isTauri && typeof window !== 'undefined') {
         case 'get_app_data_dir':
           return MOCK_APP_DATA_DIR;
 
+        // App config files
+        case 'read_app_config':
+          return mockFiles[`${MOCK_APP_DATA_DIR}/${args?.name as string}`] ?? null;
+
+        case 'write_app_config':
+          mockFiles[`${MOCK_APP_DATA_DIR}/${args?.name as string}`] = args?.content as string;
+          return null;
+
+        case 'append_session_log':
+          return null;
+
+        case 'get_startup_timings':
+          return { vault_path: null, phases: [], search_index_path: null };
+
         // File operations
         case 'read_file': {
           let path = args?.path as string;
@@ -245,6 +261,7 @@ if (!isTauri && typeof window !== 'undefined') {
         case 'watch_directory':
         case 'unwatch_directory':
         case 'unwatch_all':
+        case 'set_window_dirty':
           return null;
 
         default:
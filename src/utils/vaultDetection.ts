@@ -35,6 +35,22 @@ export async function isVault(dirPath: string): Promise<boolean> {
   }
 }
 
+export interface VaultDetectionResult {
+  is_vault: boolean;
+  has_obsidian_config: boolean;
+  /** Capped at 10,000 */
+  markdown_file_count: number;
+  vault_name: string | null;
+}
+
+/**
+ * Check whether a folder looks like a vault (.obsidian/ or notes at its top level).
+ * The folder must be in scope, e.g. just returned by the pick_folder command.
+ */
+export async function detectVault(dirPath: string): Promise<VaultDetectionResult> {
+  return invoke<VaultDetectionResult>('is_vault_directory', { path: dirPath });
+}
+
 /**
  * Get the parent directory of a file path.
  */
@@ -89,6 +89,18 @@ export function normalizePath(path: string): string {
   return path.replace(/\\/g, '/');
 }
 
+/**
+ * Comparison key for note names: NFC-normalized and lowercased.
+ * macOS stores names as NFD ("Re\u0301sume\u0301") while typed links are NFC ("Résumé"),
+ * so names must be normalized before comparing. Never use the key to build fs paths.
+ *
+ * @param name - Note or file name
+ * @returns The normalized comparison key
+ */
+export function noteNameKey(name: string): string {
+  return name.normalize('NFC').toLowerCase();
+}
+
 /**
  * Get the directory name of a vault-absolute path
  * Example: /Folder/SubFolder/Note.md -> /Folder/SubFolder
@@ -1,4 +1,5 @@
 import { searchStore } from '../stores/searchStore';
+import { noteNameKey } from './vaultPaths';
 import type { WikilinkSearchResult } from '../types';
 
 export function searchWikilinks(query: string): WikilinkSearchResult[] {
@@ -22,7 +23,7 @@ export function searchWikilinks(query: string): WikilinkSearchResult[] {
   // Combine fuzzy results with partial matches
   const names = new Set(results.map((r) => r.name));
   for (const name of allNames) {
-    if (!names.has(name) && name.toLowerCase().includes(query.toLowerCase())) {
+    if (!names.has(name) && noteNameKey(name).includes(noteNameKey(query))) {
       names.add(name);
     }
   }